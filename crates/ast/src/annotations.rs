@@ -1,4 +1,60 @@
+use crate::expression::Expression;
 use crate::macros::ast_node;
-use crate::SyntaxKind;
+use crate::{SyntaxKind, SyntaxToken};
+use rowan::ast::{support, AstChildren};
+use text_size::TextRange;
 
 ast_node!(Annotation, SyntaxKind::Annotation);
+
+impl Annotation {
+    /// The full name of the annotation, with path segments joined by `::`.
+    pub fn name(&self) -> String {
+        self.segments()
+            .map(|x| x.text().to_string())
+            .collect::<Vec<_>>()
+            .join("::")
+    }
+
+    pub fn segments(&self) -> impl Iterator<Item = SyntaxToken> {
+        self.0
+            .children_with_tokens()
+            .filter_map(|x| x.into_token())
+            .filter(|x| x.kind() == SyntaxKind::Identifier)
+    }
+
+    /// The positional arguments, in order.
+    pub fn arguments(&self) -> AstChildren<Expression> {
+        support::children(&self.0)
+    }
+
+    pub fn named_arguments(&self) -> AstChildren<AnnotationArgument> {
+        support::children(&self.0)
+    }
+
+    /// The value of the named argument `name`, if present.
+    pub fn named_argument(&self, name: &str) -> Option<Expression> {
+        self.named_arguments()
+            .find(|x| x.name().text() == name)
+            .and_then(|x| x.value())
+    }
+
+    pub fn text_range(&self) -> TextRange {
+        self.0.text_range()
+    }
+}
+
+ast_node!(AnnotationArgument, SyntaxKind::AnnotationArgument);
+
+impl AnnotationArgument {
+    pub fn name(&self) -> SyntaxToken {
+        support::token(&self.0, SyntaxKind::Identifier).unwrap()
+    }
+
+    pub fn value(&self) -> Option<Expression> {
+        support::child(&self.0)
+    }
+
+    pub fn text_range(&self) -> TextRange {
+        self.0.text_range()
+    }
+}
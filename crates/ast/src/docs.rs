@@ -0,0 +1,22 @@
+use crate::{SyntaxKind, SyntaxNode};
+
+/// Collects the doc comments attached to a node, one line per comment, without their markers.
+/// Returns `None` if the node has no doc comments.
+pub(crate) fn docs(node: &SyntaxNode) -> Option<String> {
+    let lines: Vec<String> = node
+        .children_with_tokens()
+        .filter_map(|x| x.into_token())
+        .filter(|x| x.kind() == SyntaxKind::DocComment)
+        .map(|x| {
+            // Both `///` and `//!` are three bytes long
+            let text = &x.text()[3..];
+            text.strip_prefix(' ').unwrap_or(text).to_owned()
+        })
+        .collect();
+
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join("\n"))
+    }
+}
@@ -53,7 +53,7 @@ pub enum Event {
 pub fn build_from_events(mut events: Vec<Event>, text: &str) -> SyntaxNode {
     let mut builder = GreenNodeBuilder::new();
 
-    for idx in 0..events.len() - 1 {
+    for idx in 0..events.len() {
         process_event(&mut events, text, &mut builder, idx);
     }
 
@@ -1,9 +1,9 @@
 use crate::macros::ast_node;
 use crate::{SyntaxKind, SyntaxNode, SyntaxToken};
 use rowan::ast::{support, AstNode};
-use text_size::TextRange;
+use text_size::{TextRange, TextSize};
 
-#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Literal {
     Boolean(BooleanLiteral),
     None(NoneLiteral),
@@ -69,6 +69,10 @@ impl BooleanLiteral {
     pub fn token(&self) -> SyntaxToken {
         support::token(&self.0, SyntaxKind::Boolean).unwrap()
     }
+
+    pub fn value(&self) -> bool {
+        self.token().text() == "true"
+    }
 }
 
 ast_node!(NoneLiteral, SyntaxKind::Literal);
@@ -85,6 +89,109 @@ impl StringLiteral {
     pub fn token(&self) -> SyntaxToken {
         support::token(&self.0, SyntaxKind::String).unwrap()
     }
+
+    pub fn value(&self) -> Result<String, EscapeError> {
+        string_value(&self.token())
+    }
+}
+
+/// An invalid escape sequence inside a string literal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EscapeError {
+    pub kind: EscapeErrorKind,
+
+    /// Range of the offending escape sequence, starting at its `\`.
+    pub range: TextRange,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EscapeErrorKind {
+    /// The character after the `\` does not start an escape sequence.
+    UnknownEscape,
+    /// A `\u` escape is not followed by four hex digits or one to six hex digits in braces.
+    MalformedUnicodeEscape,
+    /// A `\u` escape does not describe a valid unicode scalar value.
+    InvalidCodePoint,
+}
+
+/// Decodes a [`SyntaxKind::String`] token, stripping the quotes and resolving escape sequences.
+pub(crate) fn string_value(token: &SyntaxToken) -> Result<String, EscapeError> {
+    let text = token.text();
+    // Skip the opening quote, both kinds of quotes are a single byte long
+    let offset = token.text_range().start() + TextSize::new(1);
+    let inner = &text[1..text.len() - 1];
+
+    let error = |kind, start: usize, end: usize| EscapeError {
+        kind,
+        range: TextRange::new(
+            offset + TextSize::new(start as u32),
+            offset + TextSize::new(end as u32),
+        ),
+    };
+
+    let mut result = String::with_capacity(inner.len());
+    let mut chars = inner.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        let Some((idx, c)) = chars.next() else {
+            return Err(error(EscapeErrorKind::UnknownEscape, start, inner.len()));
+        };
+
+        let end = idx + c.len_utf8();
+        let value = match c {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            '\\' => '\\',
+            '"' => '"',
+            '\'' => '\'',
+            'u' => {
+                let braced = chars.next_if(|(_, c)| *c == '{').is_some();
+                let mut digits = String::new();
+                let mut end = end + braced as usize;
+
+                while let Some((idx, c)) = chars.next_if(|(_, c)| c.is_ascii_hexdigit()) {
+                    digits.push(c);
+                    end = idx + 1;
+
+                    if !braced && digits.len() == 4 {
+                        break;
+                    }
+                }
+
+                let valid = if braced {
+                    let closed = chars.next_if(|(_, c)| *c == '}');
+                    if let Some((idx, _)) = closed {
+                        end = idx + 1;
+                    }
+
+                    closed.is_some() && (1..=6).contains(&digits.len())
+                } else {
+                    digits.len() == 4
+                };
+
+                if !valid {
+                    return Err(error(EscapeErrorKind::MalformedUnicodeEscape, start, end));
+                }
+
+                u32::from_str_radix(&digits, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| error(EscapeErrorKind::InvalidCodePoint, start, end))?
+            }
+            _ => return Err(error(EscapeErrorKind::UnknownEscape, start, end)),
+        };
+
+        result.push(value);
+    }
+
+    Ok(result)
 }
 
 ast_node!(IntegerLiteral, SyntaxKind::Literal);
@@ -93,6 +200,36 @@ impl IntegerLiteral {
     pub fn token(&self) -> SyntaxToken {
         support::token(&self.0, SyntaxKind::Integer).unwrap()
     }
+
+    pub fn value(&self) -> Result<u128, IntegerOverflowError> {
+        integer_value(&self.token())
+    }
+}
+
+/// The value of an integer literal does not fit into 128 bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntegerOverflowError {
+    /// Range of the integer literal.
+    pub range: TextRange,
+}
+
+/// Parses an [`SyntaxKind::Integer`] token, which may be written in decimal, hexadecimal (`0x`),
+/// octal (`0o`) or binary (`0b`) and may contain `_` separators.
+pub(crate) fn integer_value(token: &SyntaxToken) -> Result<u128, IntegerOverflowError> {
+    let text = token.text();
+    let (digits, radix) = match text.get(..2) {
+        Some("0x") => (&text[2..], 16),
+        Some("0o") => (&text[2..], 8),
+        Some("0b") => (&text[2..], 2),
+        _ => (text, 10),
+    };
+
+    let digits = digits.replace('_', "");
+
+    // The lexer only produces valid digits, so the only possible error is an overflow
+    u128::from_str_radix(&digits, radix).map_err(|_| IntegerOverflowError {
+        range: token.text_range(),
+    })
 }
 
 ast_node!(FloatLiteral, SyntaxKind::Literal);
@@ -101,6 +238,11 @@ impl FloatLiteral {
     pub fn token(&self) -> SyntaxToken {
         support::token(&self.0, SyntaxKind::Float).unwrap()
     }
+
+    pub fn value(&self) -> f64 {
+        // The lexer only produces valid floats, which can always be parsed
+        self.token().text().parse().unwrap()
+    }
 }
 
 ast_node!(PathLiteral, SyntaxKind::Literal);
@@ -14,6 +14,7 @@ ast_multi_node! {
     Unary(UnaryOperator) => SyntaxKind::UnaryOperator,
     Error(ErrorValue) => SyntaxKind::ErrorValue,
     Tuple(TupleExpression) => SyntaxKind::TupleExpression,
+    Group(GroupExpression) => SyntaxKind::GroupExpression,
     List(ListExpression) => SyntaxKind::ListExpression,
     Literal(Literal) => SyntaxKind::Literal,
 }
@@ -26,6 +27,7 @@ impl Expression {
             Expression::Unary(x) => x.syntax().text_range(),
             Expression::Error(x) => x.syntax().text_range(),
             Expression::Tuple(x) => x.syntax().text_range(),
+            Expression::Group(x) => x.syntax().text_range(),
             Expression::List(x) => x.syntax().text_range(),
             Expression::Literal(x) => x.syntax().text_range(),
         }
@@ -143,7 +145,7 @@ impl UnaryOperator {
         }
     }
 
-    pub fn inner(&self) -> Literal {
+    pub fn inner(&self) -> Expression {
         support::child(&self.0).unwrap()
     }
 }
@@ -164,6 +166,14 @@ impl TupleExpression {
     }
 }
 
+ast_node!(GroupExpression, SyntaxKind::GroupExpression);
+
+impl GroupExpression {
+    pub fn inner(&self) -> Expression {
+        support::child(&self.0).unwrap()
+    }
+}
+
 ast_node!(ListExpression, SyntaxKind::ListExpression);
 
 impl ListExpression {
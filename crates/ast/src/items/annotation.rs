@@ -0,0 +1,58 @@
+use crate::annotations::Annotation;
+use crate::docs;
+use crate::macros::ast_node;
+use crate::types::Type;
+use crate::{SyntaxKind, SyntaxToken};
+use rowan::ast::{support, AstChildren};
+use text_size::TextRange;
+
+ast_node!(AnnotationDefinition, SyntaxKind::AnnotationDefinition);
+
+impl AnnotationDefinition {
+    pub fn annotations(&self) -> AstChildren<Annotation> {
+        support::children(&self.0)
+    }
+
+    pub fn docs(&self) -> Option<String> {
+        docs::docs(&self.0)
+    }
+
+    pub fn name(&self) -> SyntaxToken {
+        support::token(&self.0, SyntaxKind::Identifier).unwrap()
+    }
+
+    pub fn parameters(&self) -> AstChildren<AnnotationParameter> {
+        support::children(&self.0)
+    }
+
+    /// The kinds of items and members the annotation may be placed on.
+    /// Without any targets, the annotation is allowed everywhere.
+    pub fn targets(&self) -> AstChildren<AnnotationTarget> {
+        support::children(&self.0)
+    }
+}
+
+ast_node!(AnnotationParameter, SyntaxKind::AnnotationParameter);
+
+impl AnnotationParameter {
+    pub fn name(&self) -> SyntaxToken {
+        support::token(&self.0, SyntaxKind::Identifier).unwrap()
+    }
+
+    pub fn ty(&self) -> Type {
+        support::child(&self.0).unwrap()
+    }
+}
+
+ast_node!(AnnotationTarget, SyntaxKind::AnnotationTarget);
+
+impl AnnotationTarget {
+    /// The keyword or identifier naming the target.
+    pub fn token(&self) -> SyntaxToken {
+        self.0.first_token().unwrap()
+    }
+
+    pub fn text_range(&self) -> TextRange {
+        self.0.text_range()
+    }
+}
@@ -1,4 +1,6 @@
 use crate::annotations::Annotation;
+use crate::docs;
+use crate::expression::{string_value, EscapeError};
 use crate::macros::ast_node;
 use crate::{SyntaxKind, SyntaxToken};
 use rowan::ast::{support, AstChildren};
@@ -10,9 +12,22 @@ impl ImportDirective {
         support::children(&self.0)
     }
 
-    pub fn url(&self) -> SyntaxToken {
+    pub fn docs(&self) -> Option<String> {
+        docs::docs(&self.0)
+    }
+
+    pub fn url(&self) -> Result<String, EscapeError> {
+        string_value(&self.url_token())
+    }
+
+    pub fn url_token(&self) -> SyntaxToken {
         support::token(&self.0, SyntaxKind::String).unwrap()
     }
+
+    /// The name given with `as`, under which the items of the imported file are available.
+    pub fn alias(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Identifier)
+    }
 }
 
 ast_node!(PluginDirective, SyntaxKind::PluginDirective);
@@ -22,7 +37,15 @@ impl PluginDirective {
         support::children(&self.0)
     }
 
-    pub fn url(&self) -> SyntaxToken {
+    pub fn docs(&self) -> Option<String> {
+        docs::docs(&self.0)
+    }
+
+    pub fn url(&self) -> Result<String, EscapeError> {
+        string_value(&self.url_token())
+    }
+
+    pub fn url_token(&self) -> SyntaxToken {
         support::token(&self.0, SyntaxKind::String).unwrap()
     }
 
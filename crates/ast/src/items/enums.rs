@@ -1,6 +1,8 @@
 use crate::annotations::Annotation;
-use crate::expression::Expression;
+use crate::docs;
+use crate::expression::{Expression, IntegerOverflowError, Literal};
 use crate::macros::ast_node;
+use crate::types::Type;
 use crate::{SyntaxKind, SyntaxToken};
 use rowan::ast::{support, AstChildren};
 
@@ -11,10 +13,19 @@ impl EnumDefinition {
         support::children(&self.0)
     }
 
+    pub fn docs(&self) -> Option<String> {
+        docs::docs(&self.0)
+    }
+
     pub fn name(&self) -> SyntaxToken {
         support::token(&self.0, SyntaxKind::Identifier).unwrap()
     }
 
+    /// The integer type the discriminants are stored as, if given.
+    pub fn representation(&self) -> Option<Type> {
+        support::child(&self.0)
+    }
+
     pub fn members(&self) -> AstChildren<EnumMember> {
         support::children(&self.0)
     }
@@ -27,11 +38,23 @@ impl EnumMember {
         support::children(&self.0)
     }
 
+    pub fn docs(&self) -> Option<String> {
+        docs::docs(&self.0)
+    }
+
     pub fn name(&self) -> SyntaxToken {
         support::token(&self.0, SyntaxKind::Identifier).unwrap()
     }
 
-    pub fn expression(&self) -> Expression {
-        support::child(&self.0).unwrap()
+    pub fn expression(&self) -> Option<Expression> {
+        support::child(&self.0)
+    }
+
+    /// The explicit value of this member, if it is given as a plain integer literal.
+    pub fn value(&self) -> Option<Result<u128, IntegerOverflowError>> {
+        match self.expression()? {
+            Expression::Literal(Literal::Integer(x)) => Some(x.value()),
+            _ => None,
+        }
     }
 }
@@ -1,4 +1,6 @@
 use crate::annotations::Annotation;
+use crate::docs;
+use crate::expression::{integer_value, IntegerOverflowError};
 use crate::macros::ast_node;
 use crate::types::Type;
 use crate::{SyntaxKind, SyntaxToken};
@@ -11,6 +13,10 @@ impl InterfaceDefinition {
         support::children(&self.0)
     }
 
+    pub fn docs(&self) -> Option<String> {
+        docs::docs(&self.0)
+    }
+
     pub fn name(&self) -> SyntaxToken {
         support::token(&self.0, SyntaxKind::Identifier).unwrap()
     }
@@ -27,6 +33,10 @@ impl InterfaceFunction {
         support::children(&self.0)
     }
 
+    pub fn docs(&self) -> Option<String> {
+        docs::docs(&self.0)
+    }
+
     pub fn name(&self) -> SyntaxToken {
         support::token(&self.0, SyntaxKind::Identifier).unwrap()
     }
@@ -39,7 +49,11 @@ impl InterfaceFunction {
         support::child(&self.0)
     }
 
-    pub fn function_id(&self) -> Option<SyntaxToken> {
+    pub fn function_id(&self) -> Option<Result<u128, IntegerOverflowError>> {
+        self.function_id_token().map(|x| integer_value(&x))
+    }
+
+    pub fn function_id_token(&self) -> Option<SyntaxToken> {
         support::token(&self.0, SyntaxKind::Integer)
     }
 }
@@ -54,6 +68,10 @@ impl InterfaceFunctionParameter {
         support::children(&self.0)
     }
 
+    pub fn docs(&self) -> Option<String> {
+        docs::docs(&self.0)
+    }
+
     pub fn name(&self) -> SyntaxToken {
         support::token(&self.0, SyntaxKind::Identifier).unwrap()
     }
@@ -1,22 +1,27 @@
 mod alias;
+mod annotation;
 mod constants;
 mod directives;
 mod enums;
+mod handle;
 mod interfaces;
 mod structure;
 mod unions;
 mod uses;
 
 pub use alias::*;
+pub use annotation::*;
 pub use constants::*;
 pub use directives::*;
 pub use enums::*;
+pub use handle::*;
 pub use interfaces::*;
 pub use structure::*;
 pub use unions::*;
 pub use uses::*;
 
 use crate::annotations::Annotation;
+use crate::docs;
 use crate::macros::{ast_multi_node, ast_node};
 use crate::{SyntaxKind, SyntaxToken};
 use rowan::ast::{support, AstChildren};
@@ -29,6 +34,8 @@ ast_multi_node! {
     Union(UnionDefinition) => SyntaxKind::UnionDefinition,
     Enum(EnumDefinition) => SyntaxKind::EnumDefinition,
     Alias(AliasDefinition) => SyntaxKind::AliasDefinition,
+    Handle(HandleDefinition) => SyntaxKind::HandleDefinition,
+    Annotation(AnnotationDefinition) => SyntaxKind::AnnotationDefinition,
     Interface(InterfaceDefinition) => SyntaxKind::InterfaceDefinition,
     Const(ConstDefinition) => SyntaxKind::ConstDefinition,
     Module(ModuleDefinition) => SyntaxKind::ModuleDefinition,
@@ -38,6 +45,10 @@ ast_multi_node! {
 ast_node!(File, SyntaxKind::File);
 
 impl File {
+    pub fn docs(&self) -> Option<String> {
+        docs::docs(&self.0)
+    }
+
     pub fn items(&self) -> AstChildren<Item> {
         support::children(&self.0)
     }
@@ -50,6 +61,10 @@ impl ModuleDefinition {
         support::children(&self.0)
     }
 
+    pub fn docs(&self) -> Option<String> {
+        docs::docs(&self.0)
+    }
+
     pub fn name(&self) -> SyntaxToken {
         support::token(&self.0, SyntaxKind::Identifier).unwrap()
     }
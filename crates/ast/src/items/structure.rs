@@ -1,4 +1,5 @@
 use crate::annotations::Annotation;
+use crate::docs;
 use crate::macros::ast_node;
 use crate::types::Type;
 use crate::{SyntaxKind, SyntaxToken};
@@ -11,6 +12,10 @@ impl StructDefinition {
         support::children(&self.0)
     }
 
+    pub fn docs(&self) -> Option<String> {
+        docs::docs(&self.0)
+    }
+
     pub fn name(&self) -> SyntaxToken {
         support::token(&self.0, SyntaxKind::Identifier).unwrap()
     }
@@ -27,6 +32,10 @@ impl StructMember {
         support::children(&self.0)
     }
 
+    pub fn docs(&self) -> Option<String> {
+        docs::docs(&self.0)
+    }
+
     pub fn name(&self) -> SyntaxToken {
         support::token(&self.0, SyntaxKind::Identifier).unwrap()
     }
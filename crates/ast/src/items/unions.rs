@@ -1,5 +1,6 @@
 use super::StructMember;
 use crate::annotations::Annotation;
+use crate::docs;
 use crate::macros::{ast_multi_node, ast_node};
 use crate::types::Type;
 use crate::{SyntaxKind, SyntaxToken};
@@ -12,6 +13,10 @@ impl UnionDefinition {
         support::children(&self.0)
     }
 
+    pub fn docs(&self) -> Option<String> {
+        docs::docs(&self.0)
+    }
+
     pub fn name(&self) -> SyntaxToken {
         support::token(&self.0, SyntaxKind::Identifier).unwrap()
     }
@@ -35,6 +40,13 @@ impl UnionMember {
         }
     }
 
+    pub fn docs(&self) -> Option<String> {
+        match self {
+            UnionMember::Simple(x) => x.docs(),
+            UnionMember::Anonymous(x) => x.docs(),
+        }
+    }
+
     pub fn name(&self) -> SyntaxToken {
         match self {
             UnionMember::Simple(x) => x.name(),
@@ -50,6 +62,10 @@ impl SimpleUnionMember {
         support::children(&self.0)
     }
 
+    pub fn docs(&self) -> Option<String> {
+        docs::docs(&self.0)
+    }
+
     pub fn name(&self) -> SyntaxToken {
         support::token(&self.0, SyntaxKind::Identifier).unwrap()
     }
@@ -66,6 +82,10 @@ impl UnionAnonymousStruct {
         support::children(&self.0)
     }
 
+    pub fn docs(&self) -> Option<String> {
+        docs::docs(&self.0)
+    }
+
     pub fn name(&self) -> SyntaxToken {
         support::token(&self.0, SyntaxKind::Identifier).unwrap()
     }
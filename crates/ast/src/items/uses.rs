@@ -1,4 +1,5 @@
 use crate::annotations::Annotation;
+use crate::docs;
 use crate::macros::ast_node;
 use crate::{SyntaxKind, SyntaxToken};
 use rowan::ast::{support, AstChildren};
@@ -10,10 +11,43 @@ impl UseDirective {
         support::children(&self.0)
     }
 
+    pub fn docs(&self) -> Option<String> {
+        docs::docs(&self.0)
+    }
+
     pub fn segments(&self) -> impl Iterator<Item = SyntaxToken> {
         self.0
             .children_with_tokens()
             .filter_map(|x| x.into_token())
             .filter(|x| x.kind() == SyntaxKind::Identifier)
     }
+
+    /// The full paths imported by the directive, one for each name in a `{}` group.
+    pub fn paths(&self) -> Vec<Vec<SyntaxToken>> {
+        let mut prefix = Vec::new();
+        let mut group: Option<Vec<SyntaxToken>> = None;
+
+        for token in self.0.children_with_tokens().filter_map(|x| x.into_token()) {
+            match token.kind() {
+                SyntaxKind::Identifier => match &mut group {
+                    Some(group) => group.push(token),
+                    None => prefix.push(token),
+                },
+                SyntaxKind::Punctuation if token.text() == "{" => group = Some(Vec::new()),
+                _ => {}
+            }
+        }
+
+        match group {
+            Some(group) => group
+                .into_iter()
+                .map(|name| {
+                    let mut path = prefix.clone();
+                    path.push(name);
+                    path
+                })
+                .collect(),
+            None => vec![prefix],
+        }
+    }
 }
@@ -3,8 +3,11 @@
 #[repr(u16)]
 pub enum SyntaxKind {
     EndOfFile,
+    Error,
     Whitespace,
     Newline,
+    Comment,
+    DocComment,
     Keyword,
     Punctuation,
     Identifier,
@@ -16,6 +19,7 @@ pub enum SyntaxKind {
     InfixOperator,
     UnaryOperator,
     TupleExpression,
+    GroupExpression,
     ListExpression,
     Literal,
     Boolean,
@@ -23,6 +27,7 @@ pub enum SyntaxKind {
     ErrorValue,
 
     Annotation,
+    AnnotationArgument,
 
     ImportDirective,
     PluginDirective,
@@ -46,6 +51,10 @@ pub enum SyntaxKind {
     InterfaceFunction,
     InterfaceFunctionParameter,
 
+    AnnotationDefinition,
+    AnnotationParameter,
+    AnnotationTarget,
+
     NamedType,
     ListType,
     TupleType,
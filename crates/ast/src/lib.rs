@@ -5,6 +5,7 @@
 //! The AST is constructed using [`event::Event`]s, decoupling parser and AST.
 //!
 pub mod annotations;
+mod docs;
 pub mod event;
 pub mod expression;
 pub mod items;
@@ -1,6 +1,6 @@
 macro_rules! ast_node {
     ($type:ident, $kind:expr) => {
-        #[derive(PartialEq, Eq, Hash)]
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
         #[repr(transparent)]
         pub struct $type(crate::SyntaxNode);
 
@@ -28,7 +28,7 @@ macro_rules! ast_node {
 
 macro_rules! ast_multi_node {
     ($enum:ident , $($name:ident($type:ty) => $kind:pat),+ $(,)?) => {
-        #[derive(PartialEq, Eq, Hash)]
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
         pub enum $enum {
             $($name($type)),+
         }
@@ -1,3 +1,4 @@
+use crate::expression::{integer_value, IntegerOverflowError};
 use crate::macros::{ast_multi_node, ast_node};
 use crate::{SyntaxKind, SyntaxToken};
 use rowan::ast::{support, AstChildren};
@@ -49,6 +50,10 @@ impl ResultType {
     pub fn inner(&self) -> Type {
         support::child(&self.0).unwrap()
     }
+
+    pub fn error(&self) -> Option<Type> {
+        support::children(&self.0).nth(1)
+    }
 }
 
 ast_node!(OptionType, SyntaxKind::OptionType);
@@ -74,7 +79,11 @@ impl ListType {
         support::child(&self.0).unwrap()
     }
 
-    pub fn amount(&self) -> Option<SyntaxToken> {
+    pub fn amount(&self) -> Option<Result<u128, IntegerOverflowError>> {
+        self.amount_token().map(|x| integer_value(&x))
+    }
+
+    pub fn amount_token(&self) -> Option<SyntaxToken> {
         support::token(&self.0, SyntaxKind::Integer)
     }
 }
@@ -0,0 +1,1698 @@
+use crate::fingerprint::{fields_name, type_name};
+use crate::{FileId, Function, Hir, ItemId, ItemKind, Resolution};
+use ast::items::{EnumDefinition, Item, StructMember, UnionMember};
+use ast::types::Type;
+use ast::SyntaxToken;
+use report::{Report, Severity};
+use rowan::ast::{AstChildren, AstNode};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use text_size::TextRange;
+use type_check::{ConstValue, Primitive, Scopes};
+
+/// The index of the old file in the sources of a [`Change::report`].
+pub const OLD_SOURCE: usize = 0;
+/// The index of the new file in the sources of a [`Change::report`].
+pub const NEW_SOURCE: usize = 1;
+
+/// A difference between two versions of a file, found by [`compare`].
+#[derive(Debug)]
+pub struct Change {
+    pub kind: ChangeKind,
+    /// Whether peers built from the old version can no longer talk to ones built from the new one
+    pub breaking: bool,
+    /// The changed definition in the old file, or where an added one was missing
+    pub old: Option<TextRange>,
+    /// The changed definition in the new file, or where a removed one used to be
+    pub new: Option<TextRange>,
+    /// Where the definition of an imported item changed, in the files defining it in the old and
+    /// the new version
+    pub definition: Option<((FileId, TextRange), (FileId, TextRange))>,
+}
+
+#[derive(Debug)]
+pub enum ChangeKind {
+    ItemRemoved {
+        keyword: &'static str,
+        path: String,
+    },
+    ItemAdded {
+        keyword: &'static str,
+        path: String,
+    },
+    KindChanged {
+        path: String,
+        old: &'static str,
+        new: &'static str,
+    },
+    MemberRemoved {
+        /// What kind of member it is, like `field` or `function`
+        what: &'static str,
+        /// The path of the definition containing the member
+        path: String,
+        name: String,
+    },
+    MemberAdded {
+        what: &'static str,
+        path: String,
+        name: String,
+    },
+    MemberMoved {
+        what: &'static str,
+        path: String,
+        name: String,
+        /// The one-based positions of the member
+        old: usize,
+        new: usize,
+    },
+    MemberRenamed {
+        what: &'static str,
+        path: String,
+        old: String,
+        new: String,
+    },
+    FunctionIdChanged {
+        path: String,
+        old: u32,
+        new: u32,
+    },
+    DiscriminantChanged {
+        path: String,
+        old: String,
+        new: String,
+    },
+    TypeChanged {
+        /// What has the type, like ``field `a` of `S` ``
+        what: String,
+        old: String,
+        new: String,
+        variance: Variance,
+    },
+    /// An imported item used by a type changed, which is all that changed about the type
+    DefinitionChanged {
+        /// What has the type, like ``parameter `s` of `I::a` ``
+        what: String,
+        /// How the definition of the item changed
+        change: Box<ChangeKind>,
+        variance: Variance,
+    },
+}
+
+/// How the values a type allows changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variance {
+    /// Every old value is still allowed, like when a `u8` becomes a `u16` or a `?u8`
+    Widened,
+    /// Every new value was allowed before, like when a member is removed from an enum
+    Narrowed,
+    /// Values of one version can't be read as the other
+    Incompatible,
+}
+
+impl Variance {
+    fn combine(a: Option<Variance>, b: Option<Variance>) -> Option<Variance> {
+        match (a, b) {
+            (None, x) | (x, None) => x,
+            (Some(a), Some(b)) if a == b => Some(a),
+            _ => Some(Variance::Incompatible),
+        }
+    }
+}
+
+impl ChangeKind {
+    /// The code as shown to users. These never change once assigned.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ChangeKind::ItemRemoved { .. } => "C0001",
+            ChangeKind::ItemAdded { .. } => "C0002",
+            ChangeKind::KindChanged { .. } => "C0003",
+            ChangeKind::MemberRemoved { .. } => "C0004",
+            ChangeKind::MemberAdded { .. } => "C0005",
+            ChangeKind::MemberMoved { .. } => "C0006",
+            ChangeKind::MemberRenamed { .. } => "C0007",
+            ChangeKind::FunctionIdChanged { .. } => "C0008",
+            ChangeKind::DiscriminantChanged { .. } => "C0009",
+            ChangeKind::TypeChanged { .. } => "C0010",
+            ChangeKind::DefinitionChanged { .. } => "C0011",
+        }
+    }
+
+    /// The labels of the old and the new range.
+    fn labels(&self) -> (String, String) {
+        match self {
+            ChangeKind::ItemRemoved { .. } => ("removed".into(), String::new()),
+            ChangeKind::ItemAdded { .. } => (String::new(), "added".into()),
+            ChangeKind::KindChanged { old, new, .. } => {
+                (format!("was a `{}`", old), format!("now a `{}`", new))
+            }
+            ChangeKind::MemberRemoved { .. } => ("removed".into(), "no longer here".into()),
+            ChangeKind::MemberAdded { .. } => ("not here before".into(), "added".into()),
+            ChangeKind::MemberMoved { old, new, .. } => {
+                (format!("at position {}", old), format!("now at {}", new))
+            }
+            ChangeKind::MemberRenamed { old, new, .. } => {
+                (format!("was `{}`", old), format!("now `{}`", new))
+            }
+            ChangeKind::FunctionIdChanged { old, new, .. } => {
+                (format!("was {}", old), format!("now {}", new))
+            }
+            ChangeKind::DiscriminantChanged { old, new, .. } => {
+                (format!("was {}", old), format!("now {}", new))
+            }
+            ChangeKind::TypeChanged { old, new, .. } => {
+                (format!("was `{}`", old), format!("now `{}`", new))
+            }
+            ChangeKind::DefinitionChanged { .. } => (
+                "used the old definition".into(),
+                "uses the new definition".into(),
+            ),
+        }
+    }
+}
+
+impl Change {
+    /// Describes the change for users. Breaking changes are errors, compatible ones notes. The
+    /// labels are in the sources [`OLD_SOURCE`] and [`NEW_SOURCE`].
+    pub fn report(&self) -> Report {
+        self.report_in(|_| None, |_| None)
+    }
+
+    /// Like [`Change::report`], with labels in the imported files where a definition changed too.
+    /// `old_sources` and `new_sources` give the index of the source of a file of each version, if
+    /// there is one.
+    pub fn report_in(
+        &self,
+        old_sources: impl Fn(FileId) -> Option<usize>,
+        new_sources: impl Fn(FileId) -> Option<usize>,
+    ) -> Report {
+        let severity = match self.breaking {
+            true => Severity::Error,
+            false => Severity::Note,
+        };
+        let mut report = Report::new(severity, self.kind.code(), self.kind.to_string());
+
+        let (old, new) = self.kind.labels();
+        if let Some(range) = self.new {
+            report = report.with_label_in(NEW_SOURCE, range, new);
+        }
+        if let Some(range) = self.old {
+            report = match self.new {
+                Some(_) => report.with_secondary_label_in(OLD_SOURCE, range, old),
+                None => report.with_label_in(OLD_SOURCE, range, old),
+            };
+        }
+
+        if let (ChangeKind::DefinitionChanged { change, .. }, Some((old, new))) =
+            (&self.kind, self.definition)
+        {
+            let labels = change.labels();
+            if let Some(source) = new_sources(new.0) {
+                report = report.with_secondary_label_in(source, new.1, labels.1);
+            }
+            if let Some(source) = old_sources(old.0) {
+                report = report.with_secondary_label_in(source, old.1, labels.0);
+            }
+        }
+
+        let variance = match self.kind {
+            ChangeKind::TypeChanged { variance, .. }
+            | ChangeKind::DefinitionChanged { variance, .. } => Some(variance),
+            _ => None,
+        };
+        match variance {
+            Some(Variance::Widened) if self.breaking => {
+                report.with_note("the old version can't read every value the new type allows")
+            }
+            Some(Variance::Narrowed) if self.breaking => {
+                report.with_note("the old version may still send values the new type doesn't allow")
+            }
+            _ => report,
+        }
+    }
+}
+
+impl Display for ChangeKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ChangeKind::ItemRemoved { keyword, path } => {
+                write!(f, "{} `{}` was removed", keyword, path)
+            }
+            ChangeKind::ItemAdded { keyword, path } => {
+                write!(f, "{} `{}` was added", keyword, path)
+            }
+            ChangeKind::KindChanged { path, old, new } => {
+                write!(f, "`{}` changed from `{}` to `{}`", path, old, new)
+            }
+            ChangeKind::MemberRemoved { what, path, name } => {
+                write!(f, "{} `{}` was removed from `{}`", what, name, path)
+            }
+            ChangeKind::MemberAdded { what, path, name } => {
+                write!(f, "{} `{}` was added to `{}`", what, name, path)
+            }
+            ChangeKind::MemberMoved {
+                what,
+                path,
+                name,
+                old,
+                new,
+            } => write!(
+                f,
+                "{} `{}` of `{}` moved from position {} to {}",
+                what, name, path, old, new
+            ),
+            ChangeKind::MemberRenamed {
+                what,
+                path,
+                old,
+                new,
+            } => write!(
+                f,
+                "{} `{}` of `{}` was renamed to `{}`",
+                what, old, path, new
+            ),
+            ChangeKind::FunctionIdChanged { path, old, new } => {
+                write!(f, "the ID of `{}` changed from {} to {}", path, old, new)
+            }
+            ChangeKind::DiscriminantChanged { path, old, new } => write!(
+                f,
+                "the discriminant of `{}` changed from {} to {}",
+                path, old, new
+            ),
+            ChangeKind::TypeChanged { what, old, new, .. } => write!(
+                f,
+                "the type of {} changed from `{}` to `{}`",
+                what, old, new
+            ),
+            ChangeKind::DefinitionChanged { what, change, .. } => {
+                write!(f, "the type of {} changed: {}", what, change)
+            }
+        }
+    }
+}
+
+/// Compares two versions of a file and classifies every difference in their definitions as
+/// compatible or breaking. Both must have been lowered without errors.
+///
+/// Items are matched by their paths. Struct fields, union members and parameters are sent by
+/// their position, so moving them breaks, while renaming them doesn't. Enum members are matched by
+/// their discriminant and functions by their ID when renamed. A changed type is compatible if it
+/// only widens values the new version reads, or only narrows values the old version reads.
+/// Constants aren't sent and are left out.
+///
+/// `old_files` and `new_files` are the models each version was lowered with. Imported items are
+/// only compared where they are used, by their definitions. A type that only changed through
+/// them is reported with what changed in the definition, see [`ChangeKind::DefinitionChanged`].
+pub fn compare(old: &Hir, old_files: &[Hir], new: &Hir, new_files: &[Hir]) -> Vec<Change> {
+    let mut comparison = Comparison {
+        old,
+        new,
+        old_files,
+        new_files,
+        usages: usages(old),
+        changes: Vec::new(),
+    };
+
+    let old_items = paths(old);
+    let new_items = paths(new);
+
+    for (path, id) in &old_items {
+        let item = old.item(*id);
+        match new_items.iter().find(|(x, _)| x == path) {
+            Some((_, new_id)) if new.item(*new_id).kind != item.kind => {
+                let new_item = new.item(*new_id);
+                comparison.push(
+                    ChangeKind::KindChanged {
+                        path: path.clone(),
+                        old: item.kind.keyword(),
+                        new: new_item.kind.keyword(),
+                    },
+                    true,
+                    Some(item.name_range),
+                    Some(new_item.name_range),
+                );
+            }
+            Some((_, new_id)) => comparison.item(*id, *new_id),
+            None => comparison.push(
+                ChangeKind::ItemRemoved {
+                    keyword: item.kind.keyword(),
+                    path: path.clone(),
+                },
+                true,
+                Some(item.name_range),
+                None,
+            ),
+        }
+    }
+
+    for (path, id) in &new_items {
+        if !old_items.iter().any(|(x, _)| x == path) {
+            let item = new.item(*id);
+            comparison.push(
+                ChangeKind::ItemAdded {
+                    keyword: item.kind.keyword(),
+                    path: path.clone(),
+                },
+                false,
+                None,
+                Some(item.name_range),
+            );
+        }
+    }
+
+    comparison.changes
+}
+
+/// The paths of the items sent between peers, in order. Items defined more than once have been
+/// reported as errors, only the first definition is kept.
+fn paths(hir: &Hir) -> Vec<(String, ItemId)> {
+    let mut paths: Vec<(String, ItemId)> = Vec::new();
+    for item in hir.items() {
+        let path = hir.item_path(item.id);
+        if item.kind != ItemKind::Const && !paths.iter().any(|(x, _)| *x == path) {
+            paths.push((path, item.id));
+        }
+    }
+    paths
+}
+
+/// The directions values of an item are sent in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Usage {
+    /// Written by the old version and read by the new one, like parameters
+    input: bool,
+    /// Written by the new version and read by the old one, like return values
+    output: bool,
+}
+
+const INPUT: Usage = Usage {
+    input: true,
+    output: false,
+};
+const OUTPUT: Usage = Usage {
+    input: false,
+    output: true,
+};
+
+impl Usage {
+    fn breaks(self, variance: Variance) -> bool {
+        match variance {
+            Variance::Widened => self.output,
+            Variance::Narrowed => self.input,
+            Variance::Incompatible => true,
+        }
+    }
+
+    fn merge(self, other: Usage) -> Usage {
+        Usage {
+            input: self.input || other.input,
+            output: self.output || other.output,
+        }
+    }
+}
+
+/// Finds the directions the items of `hir` are sent in by its interfaces. Items no interface uses
+/// may still be used by other files, in either direction.
+fn usages(hir: &Hir) -> HashMap<ItemId, Usage> {
+    let mut usages: HashMap<ItemId, Usage> = HashMap::new();
+    let mut pending = Vec::new();
+
+    for item in hir.items() {
+        for function in hir.functions(item.id) {
+            for parameter in function.node.parameters() {
+                pending.push((parameter.ty(), INPUT));
+            }
+            pending.extend(function.node.return_type().map(|ty| (ty, OUTPUT)));
+        }
+    }
+
+    while let Some((ty, usage)) = pending.pop() {
+        let mut items = Vec::new();
+        references(hir, ty, &mut items);
+
+        for id in items {
+            let existing = usages.entry(id).or_default();
+            let merged = existing.merge(usage);
+            if merged != *existing {
+                *existing = merged;
+                pending.extend(member_types(&hir.item(id).node).map(|ty| (ty, merged)));
+            }
+        }
+    }
+
+    for item in hir.items() {
+        usages.entry(item.id).or_insert(Usage {
+            input: true,
+            output: true,
+        });
+    }
+    usages
+}
+
+/// The items named by `ty`.
+fn references(hir: &Hir, ty: Type, items: &mut Vec<ItemId>) {
+    let range = ty.syntax().text_range();
+    match ty {
+        Type::Named(_) | Type::Borrow(_) | Type::Own(_) => {
+            if let Some(Resolution::Item(id)) = hir.type_resolution(range) {
+                items.push(*id);
+            }
+        }
+        Type::Result(x) => {
+            references(hir, x.inner(), items);
+            if let Some(error) = x.error() {
+                references(hir, error, items);
+            }
+        }
+        Type::Option(x) => references(hir, x.inner(), items),
+        Type::List(x) => references(hir, x.inner(), items),
+        Type::Tuple(x) => {
+            for ty in x.types() {
+                references(hir, ty, items);
+            }
+        }
+    }
+}
+
+/// The types values of an item are made of.
+fn member_types(item: &Item) -> impl Iterator<Item = Type> {
+    let types = match item {
+        Item::Struct(x) => x.members().map(|x| x.ty()).collect(),
+        Item::Union(x) => x
+            .members()
+            .flat_map(|member| match member {
+                UnionMember::Simple(x) => vec![x.ty()],
+                UnionMember::Anonymous(x) => x.members().map(|x| x.ty()).collect(),
+            })
+            .collect(),
+        Item::Alias(x) => vec![x.ty()],
+        _ => Vec::new(),
+    };
+    types.into_iter()
+}
+
+struct Comparison<'a> {
+    old: &'a Hir,
+    new: &'a Hir,
+    old_files: &'a [Hir],
+    new_files: &'a [Hir],
+    /// The directions the items of the old version are sent in
+    usages: HashMap<ItemId, Usage>,
+    changes: Vec<Change>,
+}
+
+impl Comparison<'_> {
+    fn push(
+        &mut self,
+        kind: ChangeKind,
+        breaking: bool,
+        old: Option<TextRange>,
+        new: Option<TextRange>,
+    ) {
+        self.changes.push(Change {
+            kind,
+            breaking,
+            old,
+            new,
+            definition: None,
+        });
+    }
+
+    /// Compares two versions of an item of the same kind.
+    fn item(&mut self, old: ItemId, new: ItemId) {
+        let path = self.old.item_path(old);
+        let usage = self.usages[&old];
+        let ranges = (self.old.item(old).name_range, self.new.item(new).name_range);
+
+        match (&self.old.item(old).node, &self.new.item(new).node) {
+            (Item::Struct(a), Item::Struct(b)) => {
+                self.fields(
+                    &path,
+                    usage,
+                    a.members().collect(),
+                    b.members().collect(),
+                    ranges,
+                );
+            }
+            (Item::Union(a), Item::Union(b)) => {
+                let (a, b): (Vec<_>, Vec<_>) = (a.members().collect(), b.members().collect());
+                self.union_members(&path, usage, &a, &b, ranges);
+            }
+            (Item::Enum(a), Item::Enum(b)) => self.enum_members(&path, usage, a, b, ranges),
+            (Item::Alias(a), Item::Alias(b)) => {
+                let what = format!("alias `{}`", path);
+                self.ty(what, usage, (Some(a.ty()), Some(b.ty())), ranges);
+            }
+            (Item::Interface(_), Item::Interface(_)) => self.functions(&path, old, new),
+            // Handles have nothing to compare
+            _ => {}
+        }
+    }
+
+    fn fields(
+        &mut self,
+        path: &str,
+        usage: Usage,
+        old: Vec<StructMember>,
+        new: Vec<StructMember>,
+        parents: (TextRange, TextRange),
+    ) {
+        let names = (
+            old.iter().map(|x| x.name()).collect::<Vec<_>>(),
+            new.iter().map(|x| x.name()).collect::<Vec<_>>(),
+        );
+
+        for pair in pair_members(&names.0, &names.1, |i, j| i == j) {
+            match pair {
+                Pair::Both(i, j) => {
+                    self.positional("field", path, &names, (i, j));
+                    let what = format!("field `{}` of `{}`", names.1[j].text(), path);
+                    let types = (Some(old[i].ty()), Some(new[j].ty()));
+                    let ranges = (types_range(&old[i].ty()), types_range(&new[j].ty()));
+                    self.ty(what, usage, types, ranges);
+                }
+                Pair::Removed(i) => self.removed("field", path, &names.0[i], parents.1, true),
+                Pair::Added(j) => self.added("field", path, &names.1[j], parents.0, true),
+            }
+        }
+    }
+
+    fn union_members(
+        &mut self,
+        path: &str,
+        usage: Usage,
+        old: &[UnionMember],
+        new: &[UnionMember],
+        parents: (TextRange, TextRange),
+    ) {
+        let names = (
+            old.iter().map(|x| x.name()).collect::<Vec<_>>(),
+            new.iter().map(|x| x.name()).collect::<Vec<_>>(),
+        );
+
+        for pair in pair_members(&names.0, &names.1, |i, j| i == j) {
+            match pair {
+                Pair::Both(i, j) => {
+                    self.positional("member", path, &names, (i, j));
+                    let ranges = (names.0[i].text_range(), names.1[j].text_range());
+                    let what = format!("member `{}` of `{}`", names.1[j].text(), path);
+
+                    match (&old[i], &new[j]) {
+                        (UnionMember::Simple(a), UnionMember::Simple(b)) => {
+                            let ranges = (types_range(&a.ty()), types_range(&b.ty()));
+                            self.ty(what, usage, (Some(a.ty()), Some(b.ty())), ranges);
+                        }
+                        (UnionMember::Anonymous(a), UnionMember::Anonymous(b)) => {
+                            let path = format!("{}::{}", path, names.1[j].text());
+                            let members = (a.members().collect(), b.members().collect());
+                            self.fields(&path, usage, members.0, members.1, ranges);
+                        }
+                        (a, b) => {
+                            let kind = ChangeKind::TypeChanged {
+                                what,
+                                old: union_member_name(self.old, self.old_files, a),
+                                new: union_member_name(self.new, self.new_files, b),
+                                variance: Variance::Incompatible,
+                            };
+                            self.push(kind, true, Some(ranges.0), Some(ranges.1));
+                        }
+                    }
+                }
+                Pair::Removed(i) => {
+                    let breaking = usage.breaks(Variance::Narrowed);
+                    self.removed("member", path, &names.0[i], parents.1, breaking);
+                }
+                Pair::Added(j) => {
+                    let breaking = usage.breaks(Variance::Widened);
+                    self.added("member", path, &names.1[j], parents.0, breaking);
+                }
+            }
+        }
+    }
+
+    fn enum_members(
+        &mut self,
+        path: &str,
+        usage: Usage,
+        old: &EnumDefinition,
+        new: &EnumDefinition,
+        parents: (TextRange, TextRange),
+    ) {
+        let representation = (old.representation(), new.representation());
+        let ranges = (
+            representation.0.as_ref().map_or(parents.0, types_range),
+            representation.1.as_ref().map_or(parents.1, types_range),
+        );
+        // Enums without a representation are stored as `u32`
+        let shape = |hir, files, ty: Option<Type>| {
+            ty.map_or(Shape::Primitive(Primitive::U32), |x| shape(hir, files, x))
+        };
+        let name = |hir: &Hir, files, ty: Option<Type>| {
+            ty.map_or("u32".into(), |x| type_name(hir, files, hir.file(), x))
+        };
+        let what = format!("the representation of `{}`", path);
+        self.changed(
+            what,
+            usage,
+            (
+                shape(self.old, self.old_files, representation.0.clone()),
+                shape(self.new, self.new_files, representation.1.clone()),
+            ),
+            (
+                name(self.old, self.old_files, representation.0),
+                name(self.new, self.new_files, representation.1),
+            ),
+            ranges,
+        );
+
+        let values = (
+            discriminants(old, self.old, self.old_files),
+            discriminants(new, self.new, self.new_files),
+        );
+        let names = (
+            old.members().map(|x| x.name()).collect::<Vec<_>>(),
+            new.members().map(|x| x.name()).collect::<Vec<_>>(),
+        );
+
+        let renamed = |i: usize, j: usize| values.0[i].is_some() && values.0[i] == values.1[j];
+        for pair in pair_members(&names.0, &names.1, renamed) {
+            match pair {
+                Pair::Both(i, j) => {
+                    let ranges = (names.0[i].text_range(), names.1[j].text_range());
+                    if names.0[i].text() != names.1[j].text() {
+                        let kind = ChangeKind::MemberRenamed {
+                            what: "member",
+                            path: path.to_string(),
+                            old: names.0[i].text().to_string(),
+                            new: names.1[j].text().to_string(),
+                        };
+                        self.push(kind, false, Some(ranges.0), Some(ranges.1));
+                    }
+                    if values.0[i] != values.1[j] {
+                        let kind = ChangeKind::DiscriminantChanged {
+                            path: format!("{}::{}", path, names.1[j].text()),
+                            old: value_name(&values.0[i]),
+                            new: value_name(&values.1[j]),
+                        };
+                        self.push(kind, true, Some(ranges.0), Some(ranges.1));
+                    }
+                }
+                Pair::Removed(i) => {
+                    let breaking = usage.breaks(Variance::Narrowed);
+                    self.removed("member", path, &names.0[i], parents.1, breaking);
+                }
+                Pair::Added(j) => {
+                    let breaking = usage.breaks(Variance::Widened);
+                    self.added("member", path, &names.1[j], parents.0, breaking);
+                }
+            }
+        }
+    }
+
+    fn functions(&mut self, path: &str, old: ItemId, new: ItemId) {
+        let functions = (self.old.functions(old), self.new.functions(new));
+        let names = |functions: &[Function]| functions.iter().map(|x| x.node.name()).collect();
+        let names: (Vec<_>, Vec<_>) = (names(functions.0), names(functions.1));
+        let parents = (self.old.item(old).name_range, self.new.item(new).name_range);
+
+        // A function renamed but kept at its explicit ID is dispatched the same way
+        let renamed = |i: usize, j: usize| {
+            let (a, b) = (&functions.0[i], &functions.1[j]);
+            !a.derived && !b.derived && a.id == b.id
+        };
+
+        for pair in pair_members(&names.0, &names.1, renamed) {
+            let (i, j) = match pair {
+                Pair::Both(i, j) => (i, j),
+                Pair::Removed(i) => {
+                    self.removed("function", path, &names.0[i], parents.1, true);
+                    continue;
+                }
+                Pair::Added(j) => {
+                    self.added("function", path, &names.1[j], parents.0, false);
+                    continue;
+                }
+            };
+
+            let (a, b) = (&functions.0[i], &functions.1[j]);
+            let ranges = (names.0[i].text_range(), names.1[j].text_range());
+            if a.name != b.name {
+                let kind = ChangeKind::MemberRenamed {
+                    what: "function",
+                    path: path.to_string(),
+                    old: a.name.clone(),
+                    new: b.name.clone(),
+                };
+                self.push(kind, false, Some(ranges.0), Some(ranges.1));
+            }
+            if a.id != b.id {
+                let id_range = |function: &Function, name: TextRange| {
+                    function
+                        .node
+                        .function_id_token()
+                        .map_or(name, |x| x.text_range())
+                };
+                let kind = ChangeKind::FunctionIdChanged {
+                    path: format!("{}::{}", path, b.name),
+                    old: a.id,
+                    new: b.id,
+                };
+                let ranges = (id_range(a, ranges.0), id_range(b, ranges.1));
+                self.push(kind, true, Some(ranges.0), Some(ranges.1));
+            }
+
+            let function = format!("{}::{}", path, b.name);
+            let parameters = (
+                a.node.parameters().collect::<Vec<_>>(),
+                b.node.parameters().collect::<Vec<_>>(),
+            );
+            let parameter_names = (
+                parameters.0.iter().map(|x| x.name()).collect::<Vec<_>>(),
+                parameters.1.iter().map(|x| x.name()).collect::<Vec<_>>(),
+            );
+
+            for pair in pair_members(&parameter_names.0, &parameter_names.1, |i, j| i == j) {
+                match pair {
+                    Pair::Both(i, j) => {
+                        self.positional("parameter", &function, &parameter_names, (i, j));
+                        let (a, b) = (parameters.0[i].ty(), parameters.1[j].ty());
+                        let what = format!(
+                            "parameter `{}` of `{}`",
+                            parameter_names.1[j].text(),
+                            function
+                        );
+                        let ranges = (types_range(&a), types_range(&b));
+                        self.ty(what, INPUT, (Some(a), Some(b)), ranges);
+                    }
+                    Pair::Removed(i) => {
+                        let name = &parameter_names.0[i];
+                        self.removed("parameter", &function, name, ranges.1, true);
+                    }
+                    Pair::Added(j) => {
+                        let name = &parameter_names.1[j];
+                        self.added("parameter", &function, name, ranges.0, true);
+                    }
+                }
+            }
+
+            let returns = (a.node.return_type(), b.node.return_type());
+            let return_ranges = (
+                returns.0.as_ref().map_or(ranges.0, types_range),
+                returns.1.as_ref().map_or(ranges.1, types_range),
+            );
+            let what = format!("the return value of `{}`", function);
+            self.ty(what, OUTPUT, returns, return_ranges);
+        }
+    }
+
+    /// Reports a member of a definition sent by position that moved or was renamed.
+    fn positional(
+        &mut self,
+        what: &'static str,
+        path: &str,
+        names: &(Vec<SyntaxToken>, Vec<SyntaxToken>),
+        (i, j): (usize, usize),
+    ) {
+        let (a, b) = (&names.0[i], &names.1[j]);
+        let ranges = (Some(a.text_range()), Some(b.text_range()));
+
+        if i != j {
+            let kind = ChangeKind::MemberMoved {
+                what,
+                path: path.to_string(),
+                name: b.text().to_string(),
+                old: i + 1,
+                new: j + 1,
+            };
+            self.push(kind, true, ranges.0, ranges.1);
+        }
+        if a.text() != b.text() {
+            let kind = ChangeKind::MemberRenamed {
+                what,
+                path: path.to_string(),
+                old: a.text().to_string(),
+                new: b.text().to_string(),
+            };
+            self.push(kind, false, ranges.0, ranges.1);
+        }
+    }
+
+    fn removed(
+        &mut self,
+        what: &'static str,
+        path: &str,
+        name: &SyntaxToken,
+        parent: TextRange,
+        breaking: bool,
+    ) {
+        let kind = ChangeKind::MemberRemoved {
+            what,
+            path: path.to_string(),
+            name: name.text().to_string(),
+        };
+        self.push(kind, breaking, Some(name.text_range()), Some(parent));
+    }
+
+    fn added(
+        &mut self,
+        what: &'static str,
+        path: &str,
+        name: &SyntaxToken,
+        parent: TextRange,
+        breaking: bool,
+    ) {
+        let kind = ChangeKind::MemberAdded {
+            what,
+            path: path.to_string(),
+            name: name.text().to_string(),
+        };
+        self.push(kind, breaking, Some(parent), Some(name.text_range()));
+    }
+
+    /// Compares two versions of a type, a missing type being the empty tuple.
+    fn ty(
+        &mut self,
+        what: String,
+        usage: Usage,
+        (old, new): (Option<Type>, Option<Type>),
+        ranges: (TextRange, TextRange),
+    ) {
+        let shape = |hir, files, ty: Option<Type>| {
+            ty.map_or(Shape::Tuple(Vec::new()), |x| shape(hir, files, x))
+        };
+        let name = |hir: &Hir, files, ty: Option<Type>| {
+            ty.map_or("()".into(), |x| type_name(hir, files, hir.file(), x))
+        };
+        let shapes = (
+            shape(self.old, self.old_files, old.clone()),
+            shape(self.new, self.new_files, new.clone()),
+        );
+        let names = (
+            name(self.old, self.old_files, old),
+            name(self.new, self.new_files, new),
+        );
+        self.changed(what, usage, shapes, names, ranges);
+    }
+
+    fn changed(
+        &mut self,
+        what: String,
+        usage: Usage,
+        shapes: (Shape, Shape),
+        names: (String, String),
+        ranges: (TextRange, TextRange),
+    ) {
+        let Some(variance) = shapes.0.compare(&shapes.1) else {
+            return;
+        };
+
+        // A type written the same way can only change through the imported items it uses
+        let difference = match names.0 == names.1 {
+            true => shapes.0.difference(&shapes.1),
+            false => None,
+        };
+        let Some(difference) = difference else {
+            let kind = ChangeKind::TypeChanged {
+                what,
+                old: names.0,
+                new: names.1,
+                variance,
+            };
+            self.push(kind, usage.breaks(variance), Some(ranges.0), Some(ranges.1));
+            return;
+        };
+
+        self.changes.push(Change {
+            kind: ChangeKind::DefinitionChanged {
+                what,
+                change: Box::new(difference.kind),
+                variance,
+            },
+            breaking: usage.breaks(variance),
+            old: Some(ranges.0),
+            new: Some(ranges.1),
+            definition: Some((difference.old, difference.new)),
+        });
+    }
+}
+
+/// How the members of two versions of a definition correspond, by their indices.
+enum Pair {
+    Both(usize, usize),
+    Removed(usize),
+    Added(usize),
+}
+
+/// Pairs members by name. Members only found in one version are paired if `renamed` tells they
+/// are the same member under a new name.
+fn pair_members(
+    old: &[SyntaxToken],
+    new: &[SyntaxToken],
+    renamed: impl Fn(usize, usize) -> bool,
+) -> Vec<Pair> {
+    let mut pairs = Vec::new();
+    let mut matched = vec![false; new.len()];
+    let mut unmatched = Vec::new();
+
+    for (i, name) in old.iter().enumerate() {
+        match new.iter().position(|x| x.text() == name.text()) {
+            Some(j) if !matched[j] => {
+                matched[j] = true;
+                pairs.push(Pair::Both(i, j));
+            }
+            _ => unmatched.push(i),
+        }
+    }
+
+    for i in unmatched {
+        let j = (0..new.len()).find(|j| {
+            !matched[*j] && !old.iter().any(|x| x.text() == new[*j].text()) && renamed(i, *j)
+        });
+        match j {
+            Some(j) => {
+                matched[j] = true;
+                pairs.push(Pair::Both(i, j));
+            }
+            None => pairs.push(Pair::Removed(i)),
+        }
+    }
+
+    pairs.extend((0..new.len()).filter(|x| !matched[*x]).map(Pair::Added));
+    pairs
+}
+
+fn types_range(ty: &Type) -> TextRange {
+    ty.syntax().text_range()
+}
+
+fn union_member_name(hir: &Hir, files: &[Hir], member: &UnionMember) -> String {
+    match member {
+        UnionMember::Simple(x) => format!("({})", type_name(hir, files, hir.file(), x.ty())),
+        UnionMember::Anonymous(x) => fields_name(hir, files, hir.file(), x.members()),
+    }
+}
+
+fn discriminants(definition: &EnumDefinition, hir: &Hir, files: &[Hir]) -> Vec<Option<ConstValue>> {
+    let item = hir
+        .items()
+        .iter()
+        .find(|x| x.node.syntax() == definition.syntax());
+    let Some(item) = item else {
+        return vec![None; definition.members().count()];
+    };
+
+    let names = hir.names(files);
+    let scopes = Scopes::new(&names);
+    type_check::discriminants(definition, &scopes.scope(hir.scope(item.module)))
+}
+
+fn value_name(value: &Option<ConstValue>) -> String {
+    match value {
+        Some(ConstValue::Integer(x)) => x.to_string(),
+        Some(ConstValue::SignedInteger(x)) => x.to_string(),
+        _ => "an unknown value".into(),
+    }
+}
+
+/// A type with its paths resolved, to compare types of different files.
+#[derive(Debug)]
+enum Shape {
+    Primitive(Primitive),
+    /// An alias with the shape of its type
+    Alias(String, Box<Shape>),
+    /// An item of an imported file with its definition, since imported items aren't compared on
+    /// their own
+    Imported(Box<Imported>),
+    /// Any other named, `recv` or `send` type, as written in the canonical form
+    Named(String),
+    Option(Box<Shape>),
+    List(Box<Shape>, Option<u128>),
+    Tuple(Vec<Shape>),
+    Result(Box<Shape>, Option<Box<Shape>>),
+}
+
+/// An item of an imported file, as used by a type.
+#[derive(Debug)]
+struct Imported {
+    /// The name of the item, as written in the canonical form
+    name: String,
+    /// The file defining the item
+    file: FileId,
+    /// The name of the item in its definition
+    range: TextRange,
+    definition: Definition,
+}
+
+/// The definition of an imported item, with what is needed to describe how it changed.
+#[derive(Debug)]
+enum Definition {
+    Alias(Typed),
+    /// The fields of a struct, by position
+    Struct(Vec<(SyntaxToken, Typed)>),
+    /// The members of a union, by position. Anonymous members have the shape of a tuple of their
+    /// fields.
+    Union(Vec<(SyntaxToken, Typed)>),
+    /// The representation of an enum and the discriminants of its members
+    Enum(Typed, Vec<(SyntaxToken, Option<ConstValue>)>),
+}
+
+/// A type in the definition of an imported item.
+#[derive(Debug)]
+struct Typed {
+    /// The type as written in the canonical form
+    name: String,
+    range: TextRange,
+    shape: Shape,
+}
+
+/// A change found in the definition of an imported item, with where it is in the files defining
+/// the item in the old and the new version.
+struct Difference {
+    kind: ChangeKind,
+    old: (FileId, TextRange),
+    new: (FileId, TextRange),
+}
+
+/// The shape of `ty`, a type of `hir`, which was lowered with `files`.
+fn shape(hir: &Hir, files: &[Hir], ty: Type) -> Shape {
+    let mut shapes = Shapes {
+        hir,
+        files,
+        within: Vec::new(),
+    };
+    shapes.shape(hir.file(), ty)
+}
+
+/// Builds the shapes of the types of one version.
+struct Shapes<'a> {
+    hir: &'a Hir,
+    files: &'a [Hir],
+    /// The aliases and imported items being looked through, which are only named when reached
+    /// again
+    within: Vec<(FileId, ItemId)>,
+}
+
+impl Shapes<'_> {
+    /// Builds the shape of `ty`, found in `file`. Aliases are looked through and imported items
+    /// are followed into the files defining them.
+    fn shape(&mut self, file: FileId, ty: Type) -> Shape {
+        match ty {
+            Type::Named(ref x) => match self.target(file, x.syntax().text_range()) {
+                Some((_, Resolution::Primitive(primitive))) => Shape::Primitive(primitive),
+                Some((target, Resolution::Item(id))) if !self.within.contains(&(target, id)) => {
+                    self.item(file, ty, target, id)
+                }
+                _ => Shape::Named(type_name(self.hir, self.files, file, ty)),
+            },
+            Type::Borrow(_) | Type::Own(_) => {
+                Shape::Named(type_name(self.hir, self.files, file, ty))
+            }
+            Type::Option(x) => Shape::Option(Box::new(self.shape(file, x.inner()))),
+            Type::List(x) => {
+                let amount = x.amount().and_then(Result::ok);
+                Shape::List(Box::new(self.shape(file, x.inner())), amount)
+            }
+            Type::Tuple(x) => Shape::Tuple(x.types().map(|ty| self.shape(file, ty)).collect()),
+            Type::Result(x) => Shape::Result(
+                Box::new(self.shape(file, x.inner())),
+                x.error().map(|ty| Box::new(self.shape(file, ty))),
+            ),
+        }
+    }
+
+    /// What the named type at `range` of `file` refers to, in the file defining it.
+    fn target(&self, file: FileId, range: TextRange) -> Option<(FileId, Resolution)> {
+        let hir = self.hir.model(self.files, file);
+        match hir.type_resolution(range)? {
+            Resolution::Imported(import, path) => hir.resolve_imported(self.files, *import, path),
+            resolution => Some((file, resolution.clone())),
+        }
+    }
+
+    /// The shape of `ty`, found in `file`, which names the item `id` of `target`. Items of this
+    /// version are compared on their own, so only their name matters here, those of imported files
+    /// are compared by their definitions.
+    fn item(&mut self, file: FileId, ty: Type, target: FileId, id: ItemId) -> Shape {
+        let name = type_name(self.hir, self.files, file, ty);
+        let hir = self.hir.model(self.files, target);
+        let item = hir.item(id);
+        let imported = target != self.hir.file();
+        if !imported && !matches!(item.node, Item::Alias(_)) {
+            return Shape::Named(name);
+        }
+
+        self.within.push((target, id));
+        let definition = match &item.node {
+            Item::Alias(x) if !imported => {
+                let shape = Shape::Alias(name, Box::new(self.shape(target, x.ty())));
+                self.within.pop();
+                return shape;
+            }
+            Item::Alias(x) => Some(Definition::Alias(self.typed(target, x.ty()))),
+            Item::Struct(x) => {
+                let fields = x.members().map(|x| (x.name(), self.typed(target, x.ty())));
+                Some(Definition::Struct(fields.collect()))
+            }
+            Item::Union(x) => {
+                let members = x.members().map(|member| {
+                    let typed = match &member {
+                        UnionMember::Simple(x) => self.typed(target, x.ty()),
+                        UnionMember::Anonymous(x) => Typed {
+                            name: fields_name(self.hir, self.files, target, x.members()),
+                            range: x.name().text_range(),
+                            shape: self.fields(target, x.members()),
+                        },
+                    };
+                    (member.name(), typed)
+                });
+                Some(Definition::Union(members.collect()))
+            }
+            Item::Enum(x) => {
+                // Enums without a representation are stored as `u32`
+                let representation = match x.representation() {
+                    Some(ty) => self.typed(target, ty),
+                    None => Typed {
+                        name: "u32".into(),
+                        range: item.name_range,
+                        shape: Shape::Primitive(Primitive::U32),
+                    },
+                };
+                let values = discriminants(x, hir, self.files);
+                let members = x.members().map(|x| x.name()).zip(values).collect();
+                Some(Definition::Enum(representation, members))
+            }
+            // Handles and interfaces have nothing but their name
+            _ => None,
+        };
+        self.within.pop();
+
+        match definition {
+            Some(definition) => Shape::Imported(Box::new(Imported {
+                name,
+                file: target,
+                range: item.name_range,
+                definition,
+            })),
+            None => Shape::Named(name),
+        }
+    }
+
+    /// The shape of `ty`, a type of an imported definition in `file`, with its name.
+    fn typed(&mut self, file: FileId, ty: Type) -> Typed {
+        Typed {
+            name: type_name(self.hir, self.files, file, ty.clone()),
+            range: types_range(&ty),
+            shape: self.shape(file, ty),
+        }
+    }
+
+    /// The shape of the fields of a struct or union member, which are sent by position.
+    fn fields(&mut self, file: FileId, members: AstChildren<StructMember>) -> Shape {
+        Shape::Tuple(members.map(|x| self.shape(file, x.ty())).collect())
+    }
+}
+
+impl Shape {
+    /// How the values changed from `self` to `new`, `None` if the types are the same.
+    fn compare(&self, new: &Shape) -> Option<Variance> {
+        // Imported aliases aren't compared on their own, only the types they stand for are
+        match (self.imported_alias(), new.imported_alias()) {
+            (Some(a), _) => return a.compare(new),
+            (None, Some(b)) => return self.compare(b),
+            (None, None) => {}
+        }
+
+        match (self, new) {
+            // The alias itself is compared on its own
+            (Shape::Alias(a, _), Shape::Alias(b, _)) if a == b => None,
+            (Shape::Alias(_, a), b) => a.compare(b),
+            (a, Shape::Alias(_, b)) => a.compare(b),
+            (Shape::Primitive(a), Shape::Primitive(b)) => compare_primitives(*a, *b),
+            (Shape::Option(a), Shape::Option(b)) => a.compare(b),
+            (a, Shape::Option(b)) => Variance::combine(Some(Variance::Widened), a.compare(b)),
+            (Shape::Option(a), b) => Variance::combine(Some(Variance::Narrowed), a.compare(b)),
+            (Shape::List(a, n), Shape::List(b, m)) => {
+                let amount = match (n, m) {
+                    _ if n == m => None,
+                    (Some(_), None) => Some(Variance::Widened),
+                    (None, Some(_)) => Some(Variance::Narrowed),
+                    _ => Some(Variance::Incompatible),
+                };
+                Variance::combine(a.compare(b), amount)
+            }
+            (Shape::Tuple(a), Shape::Tuple(b)) => compare_positional(a.iter(), b.iter(), false),
+            (Shape::Result(a, e), Shape::Result(b, f)) => {
+                let error = match (e, f) {
+                    (Some(e), Some(f)) => e.compare(f),
+                    (None, None) => None,
+                    _ => Some(Variance::Incompatible),
+                };
+                Variance::combine(a.compare(b), error)
+            }
+            (Shape::Imported(a), Shape::Imported(b)) if a.name == b.name => {
+                a.definition.compare(&b.definition)
+            }
+            (Shape::Named(a), Shape::Named(b)) if a == b => None,
+            _ => Some(Variance::Incompatible),
+        }
+    }
+
+    /// The type an imported alias stands for.
+    fn imported_alias(&self) -> Option<&Shape> {
+        match self {
+            Shape::Imported(x) => match &x.definition {
+                Definition::Alias(x) => Some(&x.shape),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// The change in the definition of an imported item that `new` differs from `self` by, if
+    /// there is one. The first one found is picked.
+    fn difference(&self, new: &Shape) -> Option<Difference> {
+        match (self, new) {
+            (Shape::Imported(a), Shape::Imported(b)) if a.name == b.name => a.difference(b),
+            _ if self.imported_alias().is_some() => self.imported_alias()?.difference(new),
+            _ if new.imported_alias().is_some() => self.difference(new.imported_alias()?),
+            (Shape::Alias(a, _), Shape::Alias(b, _)) if a == b => None,
+            (Shape::Alias(_, a), b) => a.difference(b),
+            (a, Shape::Alias(_, b)) => a.difference(b),
+            (Shape::Option(a), Shape::Option(b)) => a.difference(b),
+            (a, Shape::Option(b)) => a.difference(b),
+            (Shape::Option(a), b) => a.difference(b),
+            (Shape::List(a, _), Shape::List(b, _)) => a.difference(b),
+            (Shape::Tuple(a), Shape::Tuple(b)) => {
+                a.iter().zip(b).find_map(|(a, b)| a.difference(b))
+            }
+            (Shape::Result(a, e), Shape::Result(b, f)) => {
+                a.difference(b).or_else(|| match (e, f) {
+                    (Some(e), Some(f)) => e.difference(f),
+                    _ => None,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Imported {
+    /// The change between the definitions of two versions of the item, if it changed. Changes to
+    /// the imported items it uses are described rather than the types using them.
+    fn difference(&self, new: &Imported) -> Option<Difference> {
+        self.definition.compare(&new.definition)?;
+
+        let path = &new.name;
+        let difference = |kind, old, new| Some(Difference { kind, old, new });
+        let typed = |what: String, a: &Typed, b: &Typed| {
+            let kind = ChangeKind::TypeChanged {
+                what,
+                old: a.name.clone(),
+                new: b.name.clone(),
+                variance: a.shape.compare(&b.shape)?,
+            };
+            difference(kind, (self.file, a.range), (new.file, b.range))
+        };
+        let members =
+            |what: &'static str, a: &[(SyntaxToken, Typed)], b: &[(SyntaxToken, Typed)]| {
+                for ((_, x), (name, y)) in a.iter().zip(b) {
+                    if x.shape.compare(&y.shape).is_some() {
+                        let what = format!("{} `{}` of `{}`", what, name.text(), path);
+                        return x.shape.difference(&y.shape).or_else(|| typed(what, x, y));
+                    }
+                }
+                match a.len().cmp(&b.len()) {
+                    Ordering::Less => {
+                        let name = &b[a.len()].0;
+                        let kind = ChangeKind::MemberAdded {
+                            what,
+                            path: path.clone(),
+                            name: name.text().to_string(),
+                        };
+                        difference(kind, (self.file, self.range), (new.file, name.text_range()))
+                    }
+                    Ordering::Greater => {
+                        let name = &a[b.len()].0;
+                        let kind = ChangeKind::MemberRemoved {
+                            what,
+                            path: path.clone(),
+                            name: name.text().to_string(),
+                        };
+                        difference(kind, (self.file, name.text_range()), (new.file, new.range))
+                    }
+                    Ordering::Equal => None,
+                }
+            };
+
+        match (&self.definition, &new.definition) {
+            (Definition::Alias(a), Definition::Alias(b)) => a
+                .shape
+                .difference(&b.shape)
+                .or_else(|| typed(format!("alias `{}`", path), a, b)),
+            (Definition::Struct(a), Definition::Struct(b)) => members("field", a, b),
+            (Definition::Union(a), Definition::Union(b)) => members("member", a, b),
+            (Definition::Enum(a, x), Definition::Enum(b, y)) => {
+                if a.shape.compare(&b.shape).is_some() {
+                    let what = format!("the representation of `{}`", path);
+                    return a.shape.difference(&b.shape).or_else(|| typed(what, a, b));
+                }
+                if let Some((name, _)) = y.iter().find(|(_, y)| !x.iter().any(|(_, x)| x == y)) {
+                    let kind = ChangeKind::MemberAdded {
+                        what: "member",
+                        path: path.clone(),
+                        name: name.text().to_string(),
+                    };
+                    return difference(
+                        kind,
+                        (self.file, self.range),
+                        (new.file, name.text_range()),
+                    );
+                }
+                let (name, _) = x.iter().find(|(_, x)| !y.iter().any(|(_, y)| x == y))?;
+                let kind = ChangeKind::MemberRemoved {
+                    what: "member",
+                    path: path.clone(),
+                    name: name.text().to_string(),
+                };
+                difference(kind, (self.file, name.text_range()), (new.file, new.range))
+            }
+            (a, b) => {
+                let kind = ChangeKind::KindChanged {
+                    path: path.clone(),
+                    old: a.keyword(),
+                    new: b.keyword(),
+                };
+                difference(kind, (self.file, self.range), (new.file, new.range))
+            }
+        }
+    }
+}
+
+impl Definition {
+    fn keyword(&self) -> &'static str {
+        match self {
+            Definition::Alias(_) => ItemKind::Alias.keyword(),
+            Definition::Struct(_) => ItemKind::Struct.keyword(),
+            Definition::Union(_) => ItemKind::Union.keyword(),
+            Definition::Enum(..) => ItemKind::Enum.keyword(),
+        }
+    }
+
+    /// The shapes of the fields of a struct or the members of a union.
+    fn members(&self) -> impl ExactSizeIterator<Item = &Shape> {
+        let members = match self {
+            Definition::Struct(x) | Definition::Union(x) => x.as_slice(),
+            _ => &[],
+        };
+        members.iter().map(|(_, x)| &x.shape)
+    }
+
+    /// How the values changed from `self` to `new`, `None` if the definitions are the same.
+    fn compare(&self, new: &Definition) -> Option<Variance> {
+        let shapes = (self.members(), new.members());
+        match (self, new) {
+            (Definition::Alias(a), Definition::Alias(b)) => a.shape.compare(&b.shape),
+            (Definition::Struct(_), Definition::Struct(_)) => {
+                compare_positional(shapes.0, shapes.1, false)
+            }
+            (Definition::Union(_), Definition::Union(_)) => {
+                compare_positional(shapes.0, shapes.1, true)
+            }
+            (Definition::Enum(a, x), Definition::Enum(b, y)) => {
+                let members = match (
+                    x.iter().all(|(_, x)| y.iter().any(|(_, y)| x == y)),
+                    y.iter().all(|(_, y)| x.iter().any(|(_, x)| x == y)),
+                ) {
+                    (true, true) => None,
+                    (true, false) => Some(Variance::Widened),
+                    (false, true) => Some(Variance::Narrowed),
+                    (false, false) => Some(Variance::Incompatible),
+                };
+                Variance::combine(a.shape.compare(&b.shape), members)
+            }
+            _ => Some(Variance::Incompatible),
+        }
+    }
+}
+
+/// Compares shapes sent by position. Values of tuples and structs need every one of them, so
+/// adding or removing one is incompatible, while a union value is one of its members.
+fn compare_positional<'a>(
+    old: impl ExactSizeIterator<Item = &'a Shape>,
+    new: impl ExactSizeIterator<Item = &'a Shape>,
+    union: bool,
+) -> Option<Variance> {
+    let amount = match old.len().cmp(&new.len()) {
+        Ordering::Equal => None,
+        _ if !union => return Some(Variance::Incompatible),
+        Ordering::Less => Some(Variance::Widened),
+        Ordering::Greater => Some(Variance::Narrowed),
+    };
+    old.zip(new).fold(amount, |variance, (a, b)| {
+        Variance::combine(variance, a.compare(b))
+    })
+}
+
+fn compare_primitives(old: Primitive, new: Primitive) -> Option<Variance> {
+    if old == new {
+        return None;
+    }
+
+    let variance = match (old.bounds(), new.bounds()) {
+        (Some((old_min, old_max)), Some((new_min, new_max))) => {
+            if new_min <= old_min && old_max <= new_max {
+                Variance::Widened
+            } else if old_min <= new_min && new_max <= old_max {
+                Variance::Narrowed
+            } else {
+                Variance::Incompatible
+            }
+        }
+        _ => match (old, new) {
+            (Primitive::F32, Primitive::F64) => Variance::Widened,
+            (Primitive::F64, Primitive::F32) => Variance::Narrowed,
+            _ => Variance::Incompatible,
+        },
+    };
+    Some(variance)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{compare, Change, NEW_SOURCE, OLD_SOURCE};
+    use crate::{lower_input, FileId, Hir, ImportTarget};
+    use text_size::TextRange;
+
+    /// The changes from `old` to `new`, prefixed with `!` if they are breaking.
+    fn check(old: &str, new: &str) -> Vec<String> {
+        let [old, new] = [old, new].map(lower_input);
+        assert!(old.errors().is_empty(), "{:?}", old.errors());
+        assert!(new.errors().is_empty(), "{:?}", new.errors());
+        messages(compare(&old, &[], &new, &[]))
+    }
+
+    fn messages(changes: Vec<Change>) -> Vec<String> {
+        changes
+            .iter()
+            .map(|x| format!("{}{}", if x.breaking { "!" } else { "" }, x.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_items() {
+        assert!(check(
+            "struct S {}\nconst A = 1",
+            "// Docs\nstruct S {}\nconst A = 2"
+        )
+        .is_empty());
+        assert_eq!(
+            check(
+                "struct S {}\nhandle H",
+                "mod m {\nstruct S {}\n}\nenum H {}"
+            ),
+            vec![
+                "!struct `S` was removed",
+                "!`H` changed from `handle` to `enum`",
+                "struct `m::S` was added",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_functions() {
+        let old = "interface I {\nfn a() = 1\nfn b(x: u8)\nfn c(): u8 = 3\n}";
+
+        assert_eq!(
+            check(
+                old,
+                "interface I {\nfn a() = 2\nfn b(y: u8)\nfn d(): u8 = 3\nfn e()\n}"
+            ),
+            vec![
+                "!the ID of `I::a` changed from 1 to 2",
+                "parameter `x` of `I::b` was renamed to `y`",
+                "function `c` of `I` was renamed to `d`",
+                "function `e` was added to `I`",
+            ]
+        );
+        assert_eq!(
+            check(old, "interface I {\nfn a(x: u8) = 1\nfn c(): u16 = 3\n}"),
+            vec![
+                "!parameter `x` was added to `I::a`",
+                "!the type of the return value of `I::c` changed from `u8` to `u16`",
+                "!function `b` was removed from `I`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_types() {
+        let old = "interface I {\nfn a(x: u16, y: ?S): S\n}\nstruct S {\na: u8\nb: [u8; 4]\n}";
+
+        // Parameters may accept more values, return values may be more specific
+        assert_eq!(
+            check(
+                old,
+                "interface I {\nfn a(x: u32, y: S): S\n}\nstruct S {\na: u8\nb: [u8; 4]\n}"
+            ),
+            vec![
+                "the type of parameter `x` of `I::a` changed from `u16` to `u32`",
+                "!the type of parameter `y` of `I::a` changed from `?S` to `S`",
+            ]
+        );
+        assert_eq!(
+            check(
+                old,
+                "interface I {\nfn a(x: u8, y: ?S): S\n}\nstruct S {\nb: [u8]\na: u8\n}"
+            ),
+            vec![
+                "!the type of parameter `x` of `I::a` changed from `u16` to `u8`",
+                "!field `a` of `S` moved from position 1 to 2",
+                "!field `b` of `S` moved from position 2 to 1",
+                "!the type of field `b` of `S` changed from `[u8; 4]` to `[u8]`",
+            ]
+        );
+
+        // Aliases are looked through
+        assert!(check(
+            "type A = u8\nstruct S {\na: A\n}",
+            "type A = u8\nstruct S {\na: u8\n}"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_enums() {
+        let old = "interface I {\nfn a(e: E)\n}\nenum E {\nA\nB\nC\n}";
+
+        assert_eq!(
+            check(
+                old,
+                "interface I {\nfn a(e: E)\n}\nenum E {\nA\nC = 2\nD = 1\nE\n}"
+            ),
+            vec![
+                "member `B` of `E` was renamed to `D`",
+                "member `E` was added to `E`"
+            ]
+        );
+        assert_eq!(
+            check(
+                old,
+                "interface I {\nfn a(e: E)\n}\nenum E: u8 {\nA = 1\nB\n}"
+            ),
+            vec![
+                "!the type of the representation of `E` changed from `u32` to `u8`",
+                "!the discriminant of `E::A` changed from 0 to 1",
+                "!the discriminant of `E::B` changed from 1 to 2",
+                "!member `C` was removed from `E`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_imports() {
+        // The changes between two versions of `lib`, imported by the same file
+        let changes = |old: &str, new: &str| {
+            let [old, new] = [old, new].map(|lib| {
+                let files = vec![lower_input(lib)];
+                let input = "import \"lib.yip\" as lib\ninterface I {\nfn a(s: lib::S): lib::E\n}";
+                let parse = parsing::parse_file(input);
+                let hir =
+                    Hir::lower_with_imports(parse.file(), &[ImportTarget::File(FileId(0))], &files);
+                assert!(hir.errors().is_empty(), "{:?}", hir.errors());
+                (hir, files)
+            });
+            compare(&old.0, &old.1, &new.0, &new.1)
+        };
+        let check = |old: &str, new: &str| messages(changes(old, new));
+        let lib = "type T = u8\nstruct S {\nx: T\n}\nenum E {\nA\nB\n}";
+
+        assert!(check(lib, "struct S {\ny: u8\n}\nenum E {\nB = 1\nA = 0\n}").is_empty());
+        assert_eq!(
+            check(lib, "type T = u16\nstruct S {\nx: T\n}\nenum E {\nA\n}"),
+            vec![
+                "the type of parameter `s` of `I::a` changed: the type of alias `\"lib.yip\"::T` \
+                 changed from `u8` to `u16`",
+                "the type of the return value of `I::a` changed: member `B` was removed from \
+                 `\"lib.yip\"::E`",
+            ]
+        );
+        assert_eq!(
+            check(lib, "struct S {\nx: u8\ny: u8\n}\nenum E {\nA\nB\nC\n}"),
+            vec![
+                "!the type of parameter `s` of `I::a` changed: field `y` was added to \
+                 `\"lib.yip\"::S`",
+                "!the type of the return value of `I::a` changed: member `C` was added to \
+                 `\"lib.yip\"::E`",
+            ]
+        );
+        assert_eq!(
+            check(lib, "struct S {\nx: u16\n}\nenum E {\nA\nB\n}"),
+            vec![
+                "the type of parameter `s` of `I::a` changed: the type of field `x` of \
+                 `\"lib.yip\"::S` changed from `\"lib.yip\"::T` to `u16`"
+            ]
+        );
+
+        // The change is labeled in the imported files too
+        let changes = changes(lib, "struct S {\nx: u16\n}\nenum E {\nA\nB\n}");
+        let report = changes[0].report_in(|_| Some(2), |_| Some(3));
+        let labels: Vec<_> = report
+            .labels
+            .iter()
+            .map(|x| (x.source, x.primary, x.message.as_str()))
+            .collect();
+        assert_eq!(
+            labels,
+            vec![
+                (NEW_SOURCE, true, "uses the new definition"),
+                (OLD_SOURCE, false, "used the old definition"),
+                (3, false, "now `u16`"),
+                (2, false, "was `\"lib.yip\"::T`"),
+            ]
+        );
+        assert_eq!(report.labels[2].range, TextRange::new(14.into(), 17.into()));
+    }
+
+    #[test]
+    fn test_report() {
+        let changes = compare(
+            &lower_input("struct S {\na: u8\n}"),
+            &[],
+            &lower_input("struct S {\na: i8\n}"),
+            &[],
+        );
+        let report = changes[0].report();
+        let labels: Vec<_> = report
+            .labels
+            .iter()
+            .map(|x| (x.source, x.primary, x.message.as_str()))
+            .collect();
+        assert_eq!(
+            labels,
+            vec![
+                (NEW_SOURCE, true, "now `i8`"),
+                (OLD_SOURCE, false, "was `u8`"),
+            ]
+        );
+    }
+}
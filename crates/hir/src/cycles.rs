@@ -0,0 +1,246 @@
+use crate::{Hir, HirError, ItemId, ItemKind, Resolution};
+use ast::items::{Item, UnionMember};
+use ast::types::Type;
+use rowan::ast::AstNode;
+use std::collections::{HashMap, HashSet};
+use text_size::TextRange;
+
+/// A type in `source` naming the item `target`.
+#[derive(Clone, Copy)]
+struct Edge {
+    source: ItemId,
+    target: ItemId,
+    range: TextRange,
+}
+
+type Graph = HashMap<ItemId, Vec<Edge>>;
+
+/// Reports aliases that refer to themselves, and structs, unions and aliases that contain
+/// themselves by value.
+///
+/// Aliases are replaced by their type, so an alias may not refer to itself in any way. Other types
+/// may refer to themselves through a list, an option or a result, which don't store their value
+/// inline. Tuples do, so recursion through them is reported.
+pub(crate) fn check_cycles(hir: &Hir, errors: &mut Vec<HirError>) {
+    let mut aliases = Graph::new();
+    let mut values = Graph::new();
+
+    for item in hir.items() {
+        let types = match &item.node {
+            Item::Alias(x) => vec![x.ty()],
+            Item::Struct(x) => x.members().map(|x| x.ty()).collect(),
+            Item::Union(x) => x
+                .members()
+                .flat_map(|member| match member {
+                    UnionMember::Simple(x) => vec![x.ty()],
+                    UnionMember::Anonymous(x) => x.members().map(|x| x.ty()).collect(),
+                })
+                .collect(),
+            _ => continue,
+        };
+
+        let mut edges = Vec::new();
+        for ty in types.clone() {
+            references(hir, item.id, ty, false, &mut edges);
+        }
+        values.insert(item.id, edges);
+
+        if item.kind == ItemKind::Alias {
+            let mut edges = Vec::new();
+            for ty in types {
+                references(hir, item.id, ty, true, &mut edges);
+            }
+            edges.retain(|x| hir.item(x.target).kind == ItemKind::Alias);
+            aliases.insert(item.id, edges);
+        }
+    }
+
+    for cycle in find_cycles(hir, &aliases) {
+        let (path, references, range) = describe(hir, &cycle);
+        errors.push(HirError::AliasCycle {
+            path,
+            references,
+            range,
+        });
+    }
+
+    for cycle in find_cycles(hir, &values) {
+        // Cycles of aliases alone have been reported above
+        if cycle
+            .iter()
+            .all(|x| hir.item(x.source).kind == ItemKind::Alias)
+        {
+            continue;
+        }
+
+        let (path, references, range) = describe(hir, &cycle);
+        errors.push(HirError::RecursiveType {
+            path,
+            references,
+            range,
+        });
+    }
+}
+
+/// Collects the items named by `ty`. Types behind an indirection are only included if `all` is
+/// set.
+fn references(hir: &Hir, source: ItemId, ty: Type, all: bool, edges: &mut Vec<Edge>) {
+    match ty {
+        Type::Named(x) => {
+            let range = x.syntax().text_range();
+            if let Some(Resolution::Item(target)) = hir.type_resolution(range) {
+                edges.push(Edge {
+                    source,
+                    target: *target,
+                    range,
+                });
+            }
+        }
+        Type::Tuple(x) => {
+            for ty in x.types() {
+                references(hir, source, ty, all, edges);
+            }
+        }
+        Type::Option(x) if all => references(hir, source, x.inner(), all, edges),
+        Type::List(x) if all => references(hir, source, x.inner(), all, edges),
+        Type::Result(x) if all => {
+            references(hir, source, x.inner(), all, edges);
+            if let Some(error) = x.error() {
+                references(hir, source, error, all, edges);
+            }
+        }
+        // Handles are references, the other types hold their values out of line
+        Type::Borrow(_) | Type::Own(_) | Type::Option(_) | Type::List(_) | Type::Result(_) => {}
+    }
+}
+
+/// Finds cycles with a depth first search from every item in order. Each cycle is returned as the
+/// edges forming it, starting at the item it leads back to.
+fn find_cycles(hir: &Hir, graph: &Graph) -> Vec<Vec<Edge>> {
+    let mut search = Search {
+        graph,
+        done: HashSet::new(),
+        stack: Vec::new(),
+        edges: Vec::new(),
+        cycles: Vec::new(),
+    };
+
+    for item in hir.items() {
+        if graph.contains_key(&item.id) {
+            search.visit(item.id);
+        }
+    }
+    search.cycles
+}
+
+struct Search<'a> {
+    graph: &'a Graph,
+    /// Items whose cycles have all been found
+    done: HashSet<ItemId>,
+    stack: Vec<ItemId>,
+    /// The edges between the items on the stack
+    edges: Vec<Edge>,
+    cycles: Vec<Vec<Edge>>,
+}
+
+impl Search<'_> {
+    fn visit(&mut self, id: ItemId) {
+        if self.done.contains(&id) {
+            return;
+        }
+        self.stack.push(id);
+
+        for edge in &self.graph[&id] {
+            if let Some(idx) = self.stack.iter().position(|x| *x == edge.target) {
+                let mut cycle = self.edges[idx..].to_vec();
+                cycle.push(*edge);
+                self.cycles.push(cycle);
+            } else if self.graph.contains_key(&edge.target) {
+                self.edges.push(*edge);
+                self.visit(edge.target);
+                self.edges.pop();
+            }
+        }
+
+        self.stack.pop();
+        self.done.insert(id);
+    }
+}
+
+fn describe(hir: &Hir, cycle: &[Edge]) -> (Vec<String>, Vec<TextRange>, TextRange) {
+    let first = cycle[0].source;
+    let path = std::iter::once(first)
+        .chain(cycle.iter().map(|x| x.target))
+        .map(|x| hir.item_path(x))
+        .collect();
+    let references = cycle.iter().map(|x| x.range).collect();
+
+    (path, references, hir.item(first).name_range)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::lower_input as lower;
+    use crate::HirError;
+
+    /// The paths of the cycles found in `input`.
+    fn check(input: &str) -> Vec<String> {
+        lower(input)
+            .errors()
+            .iter()
+            .map(|x| match x {
+                HirError::AliasCycle { path, .. } => format!("alias {}", path.join(" -> ")),
+                HirError::RecursiveType { path, .. } => format!("value {}", path.join(" -> ")),
+                _ => panic!("Expected a cycle, found {:?}", x),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_aliases() {
+        assert_eq!(check("type A = B\ntype B = A"), vec!["alias A -> B -> A"]);
+        assert_eq!(check("type A = [?A]"), vec!["alias A -> A"]);
+        assert_eq!(check("type A = (u8, A)"), vec!["alias A -> A"]);
+        assert!(check("type A = B\ntype B = [u8]").is_empty());
+    }
+
+    #[test]
+    fn test_values() {
+        assert_eq!(check("struct S {\na: u8\nb: S\n}"), vec!["value S -> S"]);
+        assert_eq!(
+            check("mod m {\nstruct S {\na: (u8, T)\n}\n}\nunion T {\nA {\nb: m::S\n}\n}"),
+            vec!["value m::S -> T -> m::S"]
+        );
+        assert_eq!(
+            check("struct S {\na: A\n}\ntype A = (S, bool)"),
+            vec!["value S -> A -> S"]
+        );
+
+        assert!(check("struct S {\na: [S]\nb: ?S\nc: result S error S\n}").is_empty());
+        assert!(
+            check("union T {\nA(?T)\nB(u8)\n}\ntype L = [L2]\nstruct L2 {\na: L\n}").is_empty()
+        );
+    }
+
+    #[test]
+    fn test_report() {
+        let hir = lower("struct A {\nb: B\n}\nstruct B {\na: A\n}");
+        assert_eq!(hir.errors().len(), 1);
+        assert_eq!(
+            hir.errors()[0].to_string(),
+            "type `A` contains itself and has infinite size"
+        );
+
+        let report = hir.errors()[0].report();
+        let labels: Vec<_> = report.labels.iter().map(|x| x.message.as_str()).collect();
+        assert_eq!(
+            labels,
+            vec![
+                "recursive without indirection",
+                "contains `B`",
+                "contains `A`"
+            ]
+        );
+        assert_eq!(report.notes[0], "the cycle is A -> B -> A");
+    }
+}
@@ -0,0 +1,145 @@
+use crate::{Hir, HirError};
+use ast::items::{Item, UnionMember};
+use ast::SyntaxToken;
+use std::collections::HashMap;
+
+/// Reports items sharing a name within a module, and members sharing a name within their struct,
+/// union, enum, interface or function. Declaring a module more than once is allowed, its items
+/// are merged.
+pub(crate) fn check_duplicates(hir: &Hir, errors: &mut Vec<HirError>) {
+    for module in hir.modules() {
+        for (name, bindings) in &module.definitions {
+            let first = &bindings[0];
+            for (idx, binding) in bindings.iter().enumerate().skip(1) {
+                let repeated = bindings[..idx]
+                    .iter()
+                    .any(|x| x.resolution == binding.resolution);
+                if !repeated {
+                    errors.push(HirError::Duplicate {
+                        what: "name",
+                        name: name.clone(),
+                        range: binding.range,
+                        original: first.range,
+                    });
+                }
+            }
+        }
+    }
+
+    for item in hir.items() {
+        match &item.node {
+            Item::Struct(x) => check_names("field", x.members().map(|x| x.name()), errors),
+            Item::Union(x) => {
+                check_names("member", x.members().map(|x| x.name()), errors);
+                for member in x.members() {
+                    if let UnionMember::Anonymous(member) = member {
+                        check_names("field", member.members().map(|x| x.name()), errors);
+                    }
+                }
+            }
+            Item::Enum(x) => check_names("member", x.members().map(|x| x.name()), errors),
+            Item::Interface(x) => {
+                check_names("function", x.functions().map(|x| x.name()), errors);
+                for function in x.functions() {
+                    check_names("parameter", function.parameters().map(|x| x.name()), errors);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn check_names(
+    what: &'static str,
+    names: impl Iterator<Item = SyntaxToken>,
+    errors: &mut Vec<HirError>,
+) {
+    let mut seen = HashMap::new();
+
+    for name in names {
+        match seen.get(name.text()) {
+            Some(original) => errors.push(HirError::Duplicate {
+                what,
+                name: name.text().to_string(),
+                range: name.text_range(),
+                original: *original,
+            }),
+            None => {
+                seen.insert(name.text().to_string(), name.text_range());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{lower_input, HirError};
+    use text_size::TextSize;
+
+    fn check(input: &str) -> Vec<(String, u32, u32)> {
+        lower_input(input)
+            .errors()
+            .iter()
+            .map(|x| {
+                let HirError::Duplicate {
+                    range, original, ..
+                } = x
+                else {
+                    panic!("Expected a duplicate, found {:?}", x);
+                };
+                let start = |x: TextSize| u32::from(x);
+                (x.to_string(), start(range.start()), start(original.start()))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_items() {
+        assert!(check("mod m {\nstruct A {}\n}\nmod m {\nstruct B {}\n}").is_empty());
+
+        let errors = check("struct Foo {}\nenum Foo {}\ntype B = Foo\nmod Foo {}");
+        assert_eq!(
+            errors,
+            vec![
+                ("name `Foo` is defined more than once".into(), 19, 7),
+                ("name `Foo` is defined more than once".into(), 43, 7),
+            ]
+        );
+
+        let errors = check("mod m {\nconst A = 1\nconst A = 2\n}");
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn test_members() {
+        let errors = check("struct S {\nid: u8\nname: string\nid: u16\n}");
+        assert_eq!(
+            errors,
+            vec![("field `id` is defined more than once".into(), 31, 11)]
+        );
+
+        let errors = check("enum E {\nA\nB\nA = 3\n}");
+        assert_eq!(
+            errors,
+            vec![("member `A` is defined more than once".into(), 13, 9)]
+        );
+
+        let errors = check("union U {\nA(u8)\nA {\nx: u8\nx: u8\n}\n}");
+        assert_eq!(
+            errors,
+            vec![
+                ("member `A` is defined more than once".into(), 16, 10),
+                ("field `x` is defined more than once".into(), 26, 20),
+            ]
+        );
+
+        let errors = check("interface I {\nfn f(a: u8, a: u8)\nfn f()\n}");
+        assert_eq!(
+            errors,
+            vec![
+                ("parameter `a` is defined more than once".into(), 26, 19),
+                ("function `f` is defined more than once".into(), 36, 17),
+            ]
+        );
+    }
+}
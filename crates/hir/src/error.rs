@@ -0,0 +1,215 @@
+use report::Report;
+use std::fmt::{self, Display, Formatter};
+use text_size::TextRange;
+
+#[derive(Debug)]
+pub enum HirError {
+    UnresolvedName {
+        name: String,
+        range: TextRange,
+    },
+    AmbiguousName {
+        name: String,
+        range: TextRange,
+        /// The definitions or `use` directives bringing the name into scope
+        candidates: Vec<TextRange>,
+    },
+    NotAType {
+        name: String,
+        range: TextRange,
+    },
+    Duplicate {
+        /// What kind of name is duplicated, like `field` or `parameter`
+        what: &'static str,
+        name: String,
+        range: TextRange,
+        /// Where the name was first defined
+        original: TextRange,
+    },
+    AliasCycle {
+        /// The paths of the aliases in the cycle, starting and ending with the same alias
+        path: Vec<String>,
+        /// The types naming the next alias of the cycle
+        references: Vec<TextRange>,
+        range: TextRange,
+    },
+    RecursiveType {
+        /// The paths of the items in the cycle, starting and ending with the same item
+        path: Vec<String>,
+        /// The types naming the next item of the cycle
+        references: Vec<TextRange>,
+        range: TextRange,
+    },
+    InvalidFunctionId {
+        range: TextRange,
+    },
+    FunctionIdCollision {
+        id: u32,
+        name: String,
+        /// The explicit ID, or the name of the function if the ID was derived
+        range: TextRange,
+        /// The function first using the ID
+        original_name: String,
+        original: TextRange,
+        /// Whether both IDs were derived from the function names
+        derived: bool,
+    },
+    ImportFailed {
+        url: String,
+        reason: String,
+        range: TextRange,
+    },
+    ImportCycle {
+        /// The URLs of the files in the cycle, starting and ending with the imported file
+        urls: Vec<String>,
+        range: TextRange,
+    },
+}
+
+impl HirError {
+    /// The code as shown to users. These never change once assigned.
+    pub fn code(&self) -> &'static str {
+        match self {
+            HirError::UnresolvedName { .. } => "H0001",
+            HirError::AmbiguousName { .. } => "H0002",
+            HirError::NotAType { .. } => "H0003",
+            HirError::Duplicate { .. } => "H0004",
+            HirError::AliasCycle { .. } => "H0005",
+            HirError::RecursiveType { .. } => "H0006",
+            HirError::InvalidFunctionId { .. } => "H0007",
+            HirError::FunctionIdCollision { .. } => "H0008",
+            HirError::ImportFailed { .. } => "H0009",
+            HirError::ImportCycle { .. } => "H0010",
+        }
+    }
+
+    /// The range of the name or type that caused the error.
+    pub fn range(&self) -> TextRange {
+        match *self {
+            HirError::UnresolvedName { range, .. }
+            | HirError::AmbiguousName { range, .. }
+            | HirError::NotAType { range, .. }
+            | HirError::Duplicate { range, .. }
+            | HirError::AliasCycle { range, .. }
+            | HirError::RecursiveType { range, .. }
+            | HirError::InvalidFunctionId { range }
+            | HirError::FunctionIdCollision { range, .. }
+            | HirError::ImportFailed { range, .. }
+            | HirError::ImportCycle { range, .. } => range,
+        }
+    }
+
+    /// Describes the error for users, pointing at every candidate of an ambiguous name and at the
+    /// first definition of a duplicate name or function ID.
+    pub fn report(&self) -> Report {
+        let range = self.range();
+        let report = Report::error(self.code(), self.to_string());
+
+        match self {
+            HirError::UnresolvedName { .. } => report.with_label(range, "not found in this scope"),
+            HirError::AmbiguousName { candidates, .. } => candidates.iter().fold(
+                report.with_label(range, "ambiguous name"),
+                |report, candidate| report.with_secondary_label(*candidate, "could refer to this"),
+            ),
+            HirError::NotAType { .. } => report
+                .with_label(range, "not a type")
+                .with_note("constants and modules can't be used as types"),
+            HirError::Duplicate { original, .. } => report
+                .with_label(range, "defined again here")
+                .with_secondary_label(*original, "first defined here"),
+            HirError::AliasCycle {
+                path, references, ..
+            } => cycle_labels(
+                report.with_label(range, "alias defined here"),
+                path,
+                references,
+                "refers to",
+            )
+            .with_note(format!("the cycle is {}", path.join(" -> "))),
+            HirError::RecursiveType {
+                path, references, ..
+            } => cycle_labels(
+                report.with_label(range, "recursive without indirection"),
+                path,
+                references,
+                "contains",
+            )
+            .with_note(format!("the cycle is {}", path.join(" -> ")))
+            .with_note("place an option or a list somewhere in the cycle"),
+            HirError::InvalidFunctionId { .. } => report
+                .with_label(range, "ID out of range")
+                .with_note("larger IDs are reserved for functions without an explicit ID"),
+            HirError::FunctionIdCollision {
+                original, derived, ..
+            } => {
+                let report = report
+                    .with_label(range, "ID used again here")
+                    .with_secondary_label(*original, "first used here");
+                if *derived {
+                    report.with_note(
+                        "the IDs were derived from the function names, give one of the functions \
+                         an explicit ID with `= N`",
+                    )
+                } else {
+                    report
+                }
+            }
+            HirError::ImportFailed { reason, .. } => report
+                .with_label(range, "imported here")
+                .with_note(reason.clone()),
+            HirError::ImportCycle { urls, .. } => report
+                .with_label(range, "imported here")
+                .with_note(format!("the cycle is {}", urls.join(" -> "))),
+        }
+    }
+}
+
+/// Labels each reference of a cycle with the item it leads to.
+fn cycle_labels(report: Report, path: &[String], references: &[TextRange], verb: &str) -> Report {
+    references
+        .iter()
+        .zip(&path[1..])
+        .fold(report, |report, (range, next)| {
+            report.with_secondary_label(*range, format!("{} `{}`", verb, next))
+        })
+}
+
+impl Display for HirError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            HirError::UnresolvedName { name, .. } => write!(f, "cannot find `{}`", name),
+            HirError::AmbiguousName { name, .. } => write!(f, "`{}` is ambiguous", name),
+            HirError::NotAType { name, .. } => write!(f, "`{}` is not a type", name),
+            HirError::Duplicate { what, name, .. } => {
+                write!(f, "{} `{}` is defined more than once", what, name)
+            }
+            HirError::AliasCycle { path, .. } => write!(f, "alias `{}` refers to itself", path[0]),
+            HirError::RecursiveType { path, .. } => {
+                write!(
+                    f,
+                    "type `{}` contains itself and has infinite size",
+                    path[0]
+                )
+            }
+            HirError::InvalidFunctionId { .. } => write!(
+                f,
+                "function ID is larger than {}",
+                crate::MAX_EXPLICIT_FUNCTION_ID
+            ),
+            HirError::FunctionIdCollision {
+                id,
+                name,
+                original_name,
+                ..
+            } => write!(
+                f,
+                "function `{}` has the same ID {} as `{}`",
+                name, id, original_name
+            ),
+            HirError::ImportFailed { url, .. } => write!(f, "can't load `{}`", url),
+            HirError::ImportCycle { urls, .. } => {
+                write!(f, "`{}` imports itself", urls[0])
+            }
+        }
+    }
+}
@@ -0,0 +1,507 @@
+use crate::{FileId, Hir, ItemId, Resolution};
+use ast::annotations::Annotation;
+use ast::items::{Item, StructMember, UnionMember};
+use ast::types::Type;
+use ast::{SyntaxKind, SyntaxNode, SyntaxToken};
+use rowan::ast::{AstChildren, AstNode};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::{self, Display, Formatter};
+use type_check::{ConstValue, Scopes};
+
+/// A hash of the canonical form of an item and every item it uses, see [`Hir::fingerprint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fingerprint(pub u128);
+
+impl Fingerprint {
+    /// The 128 bit FNV-1a hash of `canonical`. Changing the hash or the canonical form changes
+    /// every fingerprint, which peers would see as incompatible definitions.
+    fn of(canonical: &str) -> Self {
+        const OFFSET: u128 = 0x6c62_272e_07bb_0142_62b8_2175_6295_c58d;
+        const PRIME: u128 = 0x0000_0000_0100_0000_0000_0000_0000_013b;
+
+        let hash = canonical.bytes().fold(OFFSET, |hash, byte| {
+            (hash ^ byte as u128).wrapping_mul(PRIME)
+        });
+        Fingerprint(hash)
+    }
+}
+
+impl Display for Fingerprint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:032x}", self.0)
+    }
+}
+
+pub(crate) fn fingerprint(hir: &Hir, files: &[Hir], id: ItemId) -> Fingerprint {
+    Fingerprint::of(&canonical_form(hir, files, id))
+}
+
+/// Writes the item `id` and the items its types refer to, one definition per line. The item
+/// itself comes first, the others are sorted by path. Docs, comments and formatting are left out,
+/// types are written with the full paths of what they resolved to, enum members with their
+/// discriminants and interface functions are ordered by their IDs. Items of imported files are
+/// written like the others, named by their path followed by the fingerprint of their own
+/// canonical form, so how their file was imported doesn't matter.
+pub(crate) fn canonical_form(hir: &Hir, files: &[Hir], id: ItemId) -> String {
+    let start = (hir.file(), id);
+    let mut definitions = BTreeMap::new();
+    let mut seen = HashSet::from([start]);
+    let mut pending = vec![start];
+
+    while let Some((file, id)) = pending.pop() {
+        let mut writer = Writer::new(hir, files, file, Naming::Definition);
+        writer.item(id);
+        let name = writer.name(file, id);
+
+        for used in writer.uses {
+            if seen.insert(used) {
+                pending.push(used);
+            }
+        }
+        definitions.insert((name, file, id), writer.out);
+    }
+
+    let first = definitions
+        .remove(&(hir.item_path(id), start.0, id))
+        .unwrap();
+    std::iter::once(first)
+        .chain(definitions.into_values())
+        .map(|x| x + "\n")
+        .collect()
+}
+
+/// Writes `ty`, found in `file`, the way the canonical form of an item of `hir` does.
+pub(crate) fn type_name(hir: &Hir, files: &[Hir], file: FileId, ty: Type) -> String {
+    let mut writer = Writer::new(hir, files, file, Naming::Import);
+    writer.ty(ty);
+    writer.out
+}
+
+/// Writes the fields of a struct or of a union member, found in `file`, the way the canonical form
+/// of an item of `hir` does.
+pub(crate) fn fields_name(
+    hir: &Hir,
+    files: &[Hir],
+    file: FileId,
+    members: AstChildren<StructMember>,
+) -> String {
+    let mut writer = Writer::new(hir, files, file, Naming::Import);
+    writer.fields(members);
+    writer.out
+}
+
+/// How items of imported files are named, as their paths alone could name items of the root too.
+#[derive(Clone, Copy)]
+enum Naming {
+    /// By their path and the fingerprint of their definition, for canonical forms
+    Definition,
+    /// By their path prefixed with the URL of the first import of their file, as written, for
+    /// messages
+    Import,
+}
+
+struct Writer<'a> {
+    /// The model the canonical form is written for, its items are named by their paths alone
+    root: &'a Hir,
+    /// The models `root` was lowered with
+    files: &'a [Hir],
+    naming: Naming,
+    /// The model of the file being written
+    hir: &'a Hir,
+    out: String,
+    /// The items referred to by the written types, with the files defining them
+    uses: Vec<(FileId, ItemId)>,
+}
+
+impl<'a> Writer<'a> {
+    fn new(root: &'a Hir, files: &'a [Hir], file: FileId, naming: Naming) -> Self {
+        Writer {
+            root,
+            files,
+            naming,
+            hir: root.model(files, file),
+            out: String::new(),
+            uses: Vec::new(),
+        }
+    }
+
+    /// The name of the item `id` of `file`. Items of other files than the root are named as
+    /// [`Naming`] says. Imports can't form cycles, so writing the canonical forms of imported items
+    /// for their names always ends.
+    fn name(&self, file: FileId, id: ItemId) -> String {
+        let hir = self.root.model(self.files, file);
+        let path = hir.item_path(id);
+        if file == self.root.file() {
+            return path;
+        }
+        if let Naming::Definition = self.naming {
+            return format!("{}#{}", path, hir.fingerprint(self.files, id));
+        }
+
+        let import = std::iter::once(self.root)
+            .chain(self.files)
+            .flat_map(|x| x.imports())
+            .find(|x| x.file == Some(file));
+        match import {
+            Some(import) => format!("{}::{}", import.node.url_token().text(), path),
+            None => path,
+        }
+    }
+
+    fn item(&mut self, id: ItemId) {
+        let path = self.name(self.hir.file(), id);
+
+        match &self.hir.item(id).node {
+            Item::Struct(x) => {
+                self.annotations(x.annotations());
+                self.out.push_str(&format!("struct {} ", path));
+                self.fields(x.members());
+            }
+            Item::Union(x) => {
+                self.annotations(x.annotations());
+                self.out.push_str(&format!("union {} {{", path));
+                for (idx, member) in x.members().enumerate() {
+                    self.separator(idx);
+                    match member {
+                        UnionMember::Simple(member) => {
+                            self.annotations(member.annotations());
+                            self.out.push_str(member.name().text());
+                            self.out.push('(');
+                            self.ty(member.ty());
+                            self.out.push(')');
+                        }
+                        UnionMember::Anonymous(member) => {
+                            self.annotations(member.annotations());
+                            self.out.push_str(member.name().text());
+                            self.out.push(' ');
+                            self.fields(member.members());
+                        }
+                    }
+                }
+                self.close();
+            }
+            Item::Enum(x) => {
+                self.annotations(x.annotations());
+                self.out.push_str(&format!("enum {}", path));
+                if let Some(ty) = x.representation() {
+                    self.out.push_str(": ");
+                    self.ty(ty);
+                }
+                self.out.push_str(" {");
+
+                // Discriminants are written as evaluated, so changing a constant they use shows
+                let names = self.hir.names(self.files);
+                let scopes = Scopes::new(&names);
+                let scope = scopes.scope(self.hir.scope(self.hir.item(id).module));
+                let values = type_check::discriminants(x, &scope);
+
+                for ((idx, member), value) in x.members().enumerate().zip(values) {
+                    self.separator(idx);
+                    self.annotations(member.annotations());
+                    self.out.push_str(member.name().text());
+                    match (value, member.expression()) {
+                        (Some(ConstValue::Integer(value)), _) => {
+                            self.out.push_str(&format!(" = {}", value))
+                        }
+                        (Some(ConstValue::SignedInteger(value)), _) => {
+                            self.out.push_str(&format!(" = {}", value))
+                        }
+                        (_, Some(expression)) => {
+                            self.out.push_str(" = ");
+                            self.out.push_str(&tokens(expression.syntax()));
+                        }
+                        (_, None) => {}
+                    }
+                }
+                self.close();
+            }
+            Item::Alias(x) => {
+                self.annotations(x.annotations());
+                self.out.push_str(&format!("type {} = ", path));
+                self.ty(x.ty());
+            }
+            Item::Interface(x) => {
+                self.annotations(x.annotations());
+                self.out.push_str(&format!("interface {} {{", path));
+
+                let mut functions: Vec<_> = self.hir.functions(id).iter().collect();
+                functions.sort_by_key(|x| x.id);
+                for (idx, function) in functions.into_iter().enumerate() {
+                    self.separator(idx);
+                    self.annotations(function.node.annotations());
+                    self.out.push_str(&format!("fn {}(", function.name));
+                    for (idx, parameter) in function.node.parameters().enumerate() {
+                        if idx > 0 {
+                            self.out.push_str(", ");
+                        }
+                        self.annotations(parameter.annotations());
+                        self.out.push_str(&format!("{}: ", parameter.name().text()));
+                        self.ty(parameter.ty());
+                    }
+                    self.out.push(')');
+                    if let Some(ty) = function.node.return_type() {
+                        self.out.push_str(": ");
+                        self.ty(ty);
+                    }
+                    self.out.push_str(&format!(" = {}", function.id));
+                }
+                self.close();
+            }
+            Item::Handle(x) => {
+                self.annotations(x.annotations());
+                self.out.push_str(&format!("handle {}", path));
+            }
+            Item::Const(x) => {
+                self.annotations(x.annotations());
+                self.out.push_str(&format!("const {}", path));
+                if let Some(ty) = x.ty() {
+                    self.out.push_str(": ");
+                    self.ty(ty);
+                }
+                self.out.push_str(" = ");
+                self.out.push_str(&tokens(x.expression().syntax()));
+            }
+            Item::Annotation(_)
+            | Item::Module(_)
+            | Item::Import(_)
+            | Item::Use(_)
+            | Item::Plugin(_) => unreachable!("not an item of the hir"),
+        }
+    }
+
+    fn fields(&mut self, members: AstChildren<StructMember>) {
+        self.out.push('{');
+        for (idx, member) in members.enumerate() {
+            self.separator(idx);
+            self.annotations(member.annotations());
+            self.out.push_str(&format!("{}: ", member.name().text()));
+            self.ty(member.ty());
+        }
+        self.close();
+    }
+
+    /// Separates the members of a struct, union, enum or interface.
+    fn separator(&mut self, idx: usize) {
+        self.out.push_str(if idx == 0 { " " } else { ", " });
+    }
+
+    fn close(&mut self) {
+        self.out
+            .push_str(if self.out.ends_with('{') { "}" } else { " }" });
+    }
+
+    fn annotations(&mut self, annotations: AstChildren<Annotation>) {
+        for annotation in annotations {
+            self.out.push_str(&tokens(annotation.syntax()));
+            self.out.push(' ');
+        }
+    }
+
+    fn ty(&mut self, ty: Type) {
+        match ty {
+            Type::Named(x) => self.path(x.syntax(), x.segments()),
+            Type::Borrow(x) => {
+                self.out.push_str("recv ");
+                self.path(x.syntax(), x.segments());
+            }
+            Type::Own(x) => {
+                self.out.push_str("send ");
+                self.path(x.syntax(), x.segments());
+            }
+            Type::Result(x) => {
+                self.out.push_str("result ");
+                self.ty(x.inner());
+                if let Some(error) = x.error() {
+                    self.out.push_str(" error ");
+                    self.ty(error);
+                }
+            }
+            Type::Option(x) => {
+                self.out.push('?');
+                self.ty(x.inner());
+            }
+            Type::Tuple(x) => {
+                self.out.push('(');
+                for (idx, ty) in x.types().enumerate() {
+                    if idx > 0 {
+                        self.out.push_str(", ");
+                    }
+                    self.ty(ty);
+                }
+                self.out.push(')');
+            }
+            Type::List(x) => {
+                self.out.push('[');
+                self.ty(x.inner());
+                match (x.amount(), x.amount_token()) {
+                    (Some(Ok(amount)), _) => self.out.push_str(&format!("; {}", amount)),
+                    (_, Some(token)) => self.out.push_str(&format!("; {}", token.text())),
+                    (_, None) => {}
+                }
+                self.out.push(']');
+            }
+        }
+    }
+
+    /// Writes what the path of a named, `recv` or `send` type resolved to, or the path as written
+    /// if it didn't resolve.
+    fn path(&mut self, node: &SyntaxNode, segments: impl Iterator<Item = SyntaxToken>) {
+        match self.hir.type_resolution(node.text_range()) {
+            Some(Resolution::Primitive(primitive)) => self.out.push_str(primitive.name()),
+            Some(Resolution::Item(id)) => self.used(self.hir.file(), *id),
+            Some(Resolution::Imported(import, path)) => {
+                match self.hir.resolve_imported(self.files, *import, path) {
+                    Some((file, Resolution::Item(id))) => self.used(file, id),
+                    // Imports of files that weren't loaded
+                    _ => {
+                        let url = self.hir.import(*import).node.url_token();
+                        self.out.push_str(url.text());
+                        for segment in path {
+                            self.out.push_str(&format!("::{}", segment));
+                        }
+                    }
+                }
+            }
+            Some(Resolution::Module(_)) | None => {
+                let segments: Vec<_> = segments.map(|x| x.text().to_string()).collect();
+                self.out.push_str(&segments.join("::"));
+            }
+        }
+    }
+
+    /// Writes the name of a used item, which is then written too.
+    fn used(&mut self, file: FileId, id: ItemId) {
+        self.out.push_str(&self.name(file, id));
+        self.uses.push((file, id));
+    }
+}
+
+/// The tokens of `node` without whitespace and comments, separated by single spaces.
+fn tokens(node: &SyntaxNode) -> String {
+    node.descendants_with_tokens()
+        .filter_map(|x| x.into_token())
+        .filter(|x| {
+            !matches!(
+                x.kind(),
+                SyntaxKind::Whitespace
+                    | SyntaxKind::Newline
+                    | SyntaxKind::Comment
+                    | SyntaxKind::DocComment
+            )
+        })
+        .map(|x| x.text().to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{lower_input as lower, FileId, Hir, ImportTarget};
+
+    /// The canonical form of the interface `I` in `input`.
+    fn canonical(input: &str) -> String {
+        let hir = lower(input);
+        assert!(hir.errors().is_empty(), "{:?}", hir.errors());
+        let id = hir.items().iter().find(|x| x.name == "I").unwrap().id;
+        hir.canonical_form(&[], id)
+    }
+
+    #[test]
+    fn test_canonical_form() {
+        let input = "/// Docs\n@version(1)\ninterface I {\nfn b(): m::S = 2\nfn a(x: recv H, y: [u8; 0x10]) = 1\n}\nmod m {\n// Comment\nstruct S {\nbad: ?(E, string)\n}\n}\nenum E: u8 {\nA = 1\nB\n}\nhandle H\nstruct Unused {}";
+        assert_eq!(
+            canonical(input),
+            "@ version ( 1 ) interface I { fn a(x: recv H, y: [u8; 16]) = 1, fn b(): m::S = 2 }\n\
+             enum E: u8 { A = 1, B = 2 }\n\
+             handle H\n\
+             struct m::S { bad: ?(E, string) }\n"
+        );
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let fingerprint = |input: &str| {
+            let hir = lower(input);
+            let id = hir.items().iter().find(|x| x.name == "I").unwrap().id;
+            hir.fingerprint(&[], id)
+        };
+
+        let original =
+            fingerprint("interface I {\nfn a(x: S) = 1\nfn b() = 2\n}\nstruct S {\nx: u8\n}");
+        let reordered = fingerprint(
+            "struct S { x: u8 } // A comment\ninterface I {\n/// Docs\nfn b() = 2\nfn a(x: S) = 1\n}",
+        );
+        let used = fingerprint(
+            "mod m {\nstruct S {\nx: u8\n}\n}\nuse m::S\ninterface I {\nfn a(x: S) = 1\nfn b() = 2\n}",
+        );
+        let written = fingerprint(
+            "mod m {\nstruct S {\nx: u8\n}\n}\ninterface I {\nfn a(x: m::S) = 1\nfn b() = 2\n}",
+        );
+        let changed =
+            fingerprint("interface I {\nfn a(x: S) = 1\nfn b() = 2\n}\nstruct S {\nx: u16\n}");
+
+        assert_eq!(original, reordered);
+        assert_eq!(used, written);
+        assert_ne!(original, used);
+        assert_ne!(original, changed);
+
+        assert_eq!(original.to_string().len(), 32);
+
+        // Enums are compared by the values of their members, however they are written
+        let enumeration = |constant: u8, members: &str| {
+            fingerprint(&format!(
+                "const B = {}\ninterface I {{\nfn a(x: E) = 1\n}}\nenum E: u8 {{\n{}\n}}",
+                constant, members
+            ))
+        };
+        assert_eq!(enumeration(1, "A = B\nC"), enumeration(1, "A = 1\nC = 2"));
+        assert_ne!(enumeration(1, "A = B"), enumeration(2, "A = B"));
+    }
+
+    #[test]
+    fn test_imports() {
+        // The interface `I` of a file importing `lib` from `url`, with the loaded model of `lib`
+        let lower_url = |url: &str, lib: &str| {
+            let files = vec![lower(lib)];
+            let input = format!(
+                "import \"{}\" as lib\ninterface I {{\nfn a(x: lib::S) = 1\n}}",
+                url
+            );
+            let parse = parsing::parse_file(&input);
+            let hir =
+                Hir::lower_with_imports(parse.file(), &[ImportTarget::File(FileId(0))], &files);
+            assert!(hir.errors().is_empty(), "{:?}", hir.errors());
+            let id = hir.items()[0].id;
+            (hir, files, id)
+        };
+
+        let (hir, files, id) = lower_url("lib.yip", "struct S {\nt: T\n}\nstruct T {}");
+        let lib = &files[0];
+        let s = lib.fingerprint(&[], lib.items()[0].id);
+        let t = lib.fingerprint(&[], lib.items()[1].id);
+        assert_eq!(
+            hir.canonical_form(&files, id),
+            format!(
+                "interface I {{ fn a(x: S#{0}) = 1 }}\n\
+                 struct S#{0} {{ t: T#{1} }}\n\
+                 struct T#{1} {{}}\n",
+                s, t
+            )
+        );
+
+        let fingerprint = |url: &str, lib: &str| {
+            let (hir, files, id) = lower_url(url, lib);
+            hir.fingerprint(&files, id)
+        };
+        assert_ne!(
+            fingerprint("lib.yip", "struct S {\nx: u8\n}"),
+            fingerprint("lib.yip", "struct S {\nx: u64\n}")
+        );
+
+        // The same file imported through another spelling of its URL
+        assert_eq!(
+            fingerprint("schemas/lib.yip", "struct S {\nx: u8\n}"),
+            fingerprint("./schemas/../schemas/lib.yip", "struct S {\nx: u8\n}")
+        );
+    }
+}
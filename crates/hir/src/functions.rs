@@ -0,0 +1,156 @@
+use crate::{Function, Hir, HirError, ItemId, MAX_EXPLICIT_FUNCTION_ID};
+use ast::items::Item;
+use std::collections::HashMap;
+use text_size::TextRange;
+
+/// Assigns every interface function the ID it is dispatched by. Explicit IDs must not exceed
+/// [`MAX_EXPLICIT_FUNCTION_ID`], functions without one are given an ID derived from the path of
+/// the interface and their name. Two functions of an interface sharing an ID are reported.
+pub(crate) fn assign_ids(hir: &Hir, errors: &mut Vec<HirError>) -> HashMap<ItemId, Vec<Function>> {
+    let mut functions = HashMap::new();
+
+    for item in hir.items() {
+        let Item::Interface(interface) = &item.node else {
+            continue;
+        };
+        let path = hir.item_path(item.id);
+
+        let mut assigned = Vec::new();
+        // The ID, name and range of the first function using each ID
+        let mut used: HashMap<u32, (String, TextRange)> = HashMap::new();
+
+        for node in interface.functions() {
+            let name = node.name();
+            let explicit = match node.function_id() {
+                Some(Ok(id)) if id <= MAX_EXPLICIT_FUNCTION_ID as u128 => Some(id as u32),
+                Some(_) => {
+                    errors.push(HirError::InvalidFunctionId {
+                        range: node.function_id_token().unwrap().text_range(),
+                    });
+                    None
+                }
+                None => None,
+            };
+
+            let (id, derived, range) = match explicit {
+                Some(id) => (id, false, node.function_id_token().unwrap().text_range()),
+                None => (derive_id(&path, name.text()), true, name.text_range()),
+            };
+
+            // A function named like an earlier one has been reported as a duplicate already
+            let repeated = assigned.iter().any(|x: &Function| x.name == name.text());
+            match used.get(&id) {
+                Some((original_name, original)) if !repeated => {
+                    errors.push(HirError::FunctionIdCollision {
+                        id,
+                        name: name.text().to_string(),
+                        range,
+                        original_name: original_name.clone(),
+                        original: *original,
+                        derived,
+                    });
+                }
+                Some(_) => {}
+                None => {
+                    used.insert(id, (name.text().to_string(), range));
+                }
+            }
+
+            assigned.push(Function {
+                name: name.text().to_string(),
+                id,
+                derived,
+                node,
+            });
+        }
+
+        functions.insert(item.id, assigned);
+    }
+
+    functions
+}
+
+/// The 32 bit FNV-1a hash of `interface::function` with the highest bit set, which keeps derived
+/// IDs apart from explicit ones. This must never change, peers built from an older version of a
+/// file would otherwise dispatch to the wrong functions.
+fn derive_id(interface: &str, function: &str) -> u32 {
+    const OFFSET: u32 = 0x811c_9dc5;
+    const PRIME: u32 = 0x0100_0193;
+
+    let hash = interface
+        .bytes()
+        .chain(*b"::")
+        .chain(function.bytes())
+        .fold(OFFSET, |hash, byte| {
+            (hash ^ byte as u32).wrapping_mul(PRIME)
+        });
+    hash | !MAX_EXPLICIT_FUNCTION_ID
+}
+
+#[cfg(test)]
+mod test {
+    use crate::lower_input as lower;
+    use crate::{Hir, HirError};
+
+    /// The IDs of the functions of the last interface of `hir`.
+    fn ids(hir: &Hir) -> Vec<(&str, u32, bool)> {
+        let interface = hir.items().last().unwrap().id;
+        hir.functions(interface)
+            .iter()
+            .map(|x| (x.name.as_str(), x.id, x.derived))
+            .collect()
+    }
+
+    #[test]
+    fn test_explicit() {
+        let hir = lower("interface I {\nfn a() = 1\nfn b() = 0x7fff_ffff\n}");
+        assert!(hir.errors().is_empty());
+        assert_eq!(ids(&hir), vec![("a", 1, false), ("b", 0x7fff_ffff, false)]);
+
+        let hir = lower("interface I {\nfn a() = 0x8000_0000\nfn b() = 1\n}");
+        assert_eq!(hir.errors().len(), 1);
+        assert_eq!(
+            hir.errors()[0].to_string(),
+            "function ID is larger than 2147483647"
+        );
+    }
+
+    #[test]
+    fn test_derived() {
+        // Derived IDs are part of the wire format and must stay the same
+        let hir = lower("interface I {\nfn f()\nfn g() = 3\n}");
+        assert!(hir.errors().is_empty());
+        assert_eq!(ids(&hir), vec![("f", 0xc1c3_d1d2, true), ("g", 3, false)]);
+
+        let hir = lower("mod m {\ninterface I {\nfn f()\n}\n}");
+        let (_, id, _) = ids(&hir)[0];
+        assert_ne!(id, 0xc1c3_d1d2);
+        assert!(id > 0x7fff_ffff);
+    }
+
+    #[test]
+    fn test_collisions() {
+        let hir = lower("interface I {\nfn a() = 2\nfn b() = 2\n}\ninterface J {\nfn a() = 2\n}");
+        assert!(matches!(
+            hir.errors(),
+            [HirError::FunctionIdCollision { range, original, .. }]
+                if (u32::from(range.start()), u32::from(original.start())) == (34, 23)
+        ));
+        assert_eq!(
+            hir.errors()[0].to_string(),
+            "function `b` has the same ID 2 as `a`"
+        );
+
+        let hir = lower("interface I {\nfn f268724()\nfn f698200()\n}");
+        assert_eq!(hir.errors().len(), 1);
+        assert!(matches!(
+            hir.errors()[0],
+            HirError::FunctionIdCollision { derived: true, .. }
+        ));
+
+        // Duplicate names are reported on their own
+        let hir = lower("interface I {\nfn f()\nfn f()\n}");
+        assert_eq!(hir.errors().len(), 1);
+        assert!(matches!(hir.errors()[0], HirError::Duplicate { .. }));
+    }
+}
@@ -0,0 +1,502 @@
+//! ## Yip Semantic Model
+//!
+//! This crate lowers a parsed [`File`] into a [`Hir`], which connects names to what they refer to.
+//! Every struct, union, enum, alias, interface, handle and const is given an [`ItemId`], every
+//! `mod` block a [`ModuleId`] and every `import` an [`ImportId`]. `use` directives and the paths
+//! of types are resolved through the scopes of the modules they appear in.
+//!
+//! Paths through an `import ... as alias` resolve to [`Resolution::Imported`] with the rest of
+//! the path kept as written. Lowering doesn't load files, but when given the models of the
+//! imported files with [`Hir::lower_with_imports`] those paths are checked against the items of
+//! those files and can be followed with [`Hir::resolve_imported`].
+//!
+//! Constants and annotations are checked by `type_check`, which resolves the paths in their
+//! expressions through the [`Names`] of the model, into imported files too.
+//!
+//! Lowering also validates the model, reporting names defined more than once, aliases referring
+//! to themselves and types containing themselves by value. Interface functions are assigned the
+//! IDs they are dispatched by, see [`Function`].
+//!
+//! [`Hir::fingerprint`] hashes an interface together with every type it uses, so peers can check
+//! they were generated from the same definitions. [`compare`] goes further and lists how two
+//! versions of a file differ, telling which changes break peers built from the old version.
+//!
+mod compat;
+mod cycles;
+mod duplicates;
+mod error;
+mod fingerprint;
+mod functions;
+mod lower;
+mod names;
+
+pub use compat::{compare, Change, ChangeKind, Variance, NEW_SOURCE, OLD_SOURCE};
+pub use error::HirError;
+pub use fingerprint::Fingerprint;
+pub use names::Names;
+
+use ast::items::{AnnotationDefinition, File, ImportDirective, InterfaceFunction, Item as AstItem};
+use std::collections::HashMap;
+use text_size::TextRange;
+use type_check::{Primitive, ScopeId};
+
+/// Identifies an item of a [`Hir`]. Items are numbered in the order they appear in the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ItemId(u32);
+
+/// Identifies a module of a [`Hir`]. The top level of the file is always the first module, modules
+/// declared more than once share one ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ModuleId(u32);
+
+/// Identifies an `import` directive of a [`Hir`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ImportId(u32);
+
+/// Identifies the model of an imported file, by its index in the models given to
+/// [`Hir::lower_with_imports`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FileId(pub u32);
+
+/// What loading the file of an `import` directive gave.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportTarget {
+    /// The file was loaded and lowered
+    File(FileId),
+    /// The file couldn't be loaded or contains errors, with the reason
+    Failed(String),
+    /// The file imports the importing file again, with the URLs of the files in the cycle
+    Cycle(Vec<String>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ItemKind {
+    Struct,
+    Union,
+    Enum,
+    Alias,
+    Interface,
+    Handle,
+    Const,
+}
+
+impl ItemKind {
+    /// The keyword the item is defined with.
+    pub fn keyword(self) -> &'static str {
+        match self {
+            ItemKind::Struct => "struct",
+            ItemKind::Union => "union",
+            ItemKind::Enum => "enum",
+            ItemKind::Alias => "type",
+            ItemKind::Interface => "interface",
+            ItemKind::Handle => "handle",
+            ItemKind::Const => "const",
+        }
+    }
+}
+
+/// A named definition.
+#[derive(Debug)]
+pub struct Item {
+    pub id: ItemId,
+    pub kind: ItemKind,
+    pub name: String,
+    pub name_range: TextRange,
+    /// The module the item is defined in
+    pub module: ModuleId,
+    pub node: AstItem,
+}
+
+#[derive(Debug)]
+pub struct Module {
+    pub id: ModuleId,
+    /// The enclosing module, `None` for the top level of the file
+    pub parent: Option<ModuleId>,
+    /// The names of the module and the modules enclosing it, starting at the top level
+    pub path: Vec<String>,
+    /// Items, modules and imports defined in this module
+    definitions: HashMap<String, Vec<Binding>>,
+    /// Names brought into scope by `use`
+    uses: HashMap<String, Vec<Binding>>,
+    /// Annotations declared in this module, the first declaration of each name
+    annotations: HashMap<String, AnnotationDefinition>,
+}
+
+#[derive(Debug)]
+pub struct Import {
+    pub id: ImportId,
+    /// The name the imported items are available under, if given
+    pub alias: Option<String>,
+    pub module: ModuleId,
+    /// The model of the imported file, if it was loaded
+    pub file: Option<FileId>,
+    pub node: ImportDirective,
+}
+
+/// The largest ID a function can be given with `= N`.
+pub const MAX_EXPLICIT_FUNCTION_ID: u32 = 0x7fff_ffff;
+
+/// A function of an interface and the ID calls to it are dispatched by.
+///
+/// Functions without an explicit ID are given one derived from the path of the interface and the
+/// name of the function, with the highest bit set so it can't collide with an explicit ID. Moving
+/// or renaming such a function changes its ID.
+#[derive(Debug)]
+pub struct Function {
+    pub name: String,
+    pub id: u32,
+    /// Whether the ID was derived, because none was given or the given one was invalid
+    pub derived: bool,
+    pub node: InterfaceFunction,
+}
+
+/// What a name or path refers to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Resolution {
+    Primitive(Primitive),
+    Item(ItemId),
+    Module(ModuleId),
+    /// A path into an imported file, with the segments following the alias
+    Imported(ImportId, Vec<String>),
+}
+
+/// A name in a module scope. A name without a resolution was brought in by a `use` that failed to
+/// resolve, it is known to be broken but the error has been reported already.
+#[derive(Debug, Clone)]
+struct Binding {
+    resolution: Option<Resolution>,
+    /// The name of the definition, or the last segment of the `use` path
+    range: TextRange,
+}
+
+/// The resolved semantic model of a file.
+#[derive(Debug)]
+pub struct Hir {
+    /// The ID of this model among the models of a workspace
+    file: FileId,
+    modules: Vec<Module>,
+    items: Vec<Item>,
+    imports: Vec<Import>,
+    /// Resolutions of the paths of named, `recv` and `send` types, by the range of the type
+    types: HashMap<TextRange, Resolution>,
+    /// The functions of each interface, in the order they are defined
+    functions: HashMap<ItemId, Vec<Function>>,
+    errors: Vec<HirError>,
+}
+
+impl Hir {
+    /// Builds the semantic model of `file`, which must have been parsed without errors. Imported
+    /// files aren't looked into.
+    pub fn lower(file: &File) -> Self {
+        lower::lower_file(file, &[], &[])
+    }
+
+    /// Builds the semantic model of `file` with its imports loaded. `imports` holds what loading
+    /// each `import` directive gave, in the order they appear in the file, and `files` the models
+    /// the targets refer to.
+    pub fn lower_with_imports(file: &File, imports: &[ImportTarget], files: &[Hir]) -> Self {
+        lower::lower_file(file, imports, files)
+    }
+
+    /// The ID of this model, the number of models it was lowered with since it follows them.
+    pub fn file(&self) -> FileId {
+        self.file
+    }
+
+    /// The model of `file`, either this one or one of the `files` it was lowered with.
+    pub(crate) fn model<'a>(&'a self, files: &'a [Hir], file: FileId) -> &'a Hir {
+        if file == self.file {
+            self
+        } else {
+            &files[file.0 as usize]
+        }
+    }
+
+    /// The module for the top level of the file.
+    pub fn root(&self) -> ModuleId {
+        ModuleId(0)
+    }
+
+    /// The scope of `module` for the type checker.
+    pub fn scope(&self, module: ModuleId) -> ScopeId {
+        ScopeId {
+            file: self.file.0,
+            module: module.0,
+        }
+    }
+
+    /// Resolves the paths the type checker comes across, following imports into `files`, the
+    /// models this one was lowered with.
+    pub fn names<'a>(&'a self, files: &'a [Hir]) -> Names<'a> {
+        Names::new(self, files)
+    }
+
+    pub fn module(&self, id: ModuleId) -> &Module {
+        &self.modules[id.0 as usize]
+    }
+
+    pub fn modules(&self) -> &[Module] {
+        &self.modules
+    }
+
+    pub fn item(&self, id: ItemId) -> &Item {
+        &self.items[id.0 as usize]
+    }
+
+    /// All items of the file, in the order they are defined.
+    pub fn items(&self) -> &[Item] {
+        &self.items
+    }
+
+    pub fn import(&self, id: ImportId) -> &Import {
+        &self.imports[id.0 as usize]
+    }
+
+    pub fn imports(&self) -> &[Import] {
+        &self.imports
+    }
+
+    /// The full path of an item, like `a::b::Item`.
+    pub fn item_path(&self, id: ItemId) -> String {
+        let item = self.item(id);
+        let mut path = self.module(item.module).path.clone();
+        path.push(item.name.clone());
+        path.join("::")
+    }
+
+    /// What the type at `range` refers to, for named, `recv` and `send` types that resolved.
+    pub fn type_resolution(&self, range: TextRange) -> Option<&Resolution> {
+        self.types.get(&range)
+    }
+
+    /// Resolves `path` as if it was written in `module`. Paths into imported files aren't
+    /// followed.
+    pub fn resolve(&self, module: ModuleId, path: &[&str]) -> Option<Resolution> {
+        let mut errors = Vec::new();
+        let segments: Vec<_> = path.iter().map(|x| (*x, TextRange::default())).collect();
+        lower::resolve_path(self, &[], module, &segments, &mut errors)
+    }
+
+    /// Follows `path` from the top level of the file loaded for `import` to what it refers to,
+    /// through the imports of that file too. `files` are the models this one was lowered with.
+    pub fn resolve_imported(
+        &self,
+        files: &[Hir],
+        import: ImportId,
+        path: &[String],
+    ) -> Option<(FileId, Resolution)> {
+        lower::resolve_imported(self, files, import, path)
+    }
+
+    /// The functions of the interface `id` with their IDs, empty if the item isn't an interface.
+    pub fn functions(&self, id: ItemId) -> &[Function] {
+        self.functions.get(&id).map_or(&[], |x| x.as_slice())
+    }
+
+    /// The definition of the item `id` and of every item its types refer to, in a form that only
+    /// changes if the definitions do. Docs, comments, formatting, the order of items and functions
+    /// and how paths are written don't matter, items are named by their full paths. Imported items
+    /// are included and also named by their own fingerprints, `files` are the models this one was
+    /// lowered with.
+    pub fn canonical_form(&self, files: &[Hir], id: ItemId) -> String {
+        fingerprint::canonical_form(self, files, id)
+    }
+
+    /// A hash of the [canonical form](Hir::canonical_form) of the item `id`. Two interfaces with
+    /// the same fingerprint have been defined the same way. Only meaningful without errors.
+    pub fn fingerprint(&self, files: &[Hir], id: ItemId) -> Fingerprint {
+        fingerprint::fingerprint(self, files, id)
+    }
+
+    /// Names that couldn't be resolved, refer to something unexpected or are defined more than
+    /// once, invalid types and function IDs, and imports that couldn't be loaded.
+    pub fn errors(&self) -> &[HirError] {
+        &self.errors
+    }
+}
+
+/// Parses and lowers `input` for the tests of every module, which must parse without errors.
+#[cfg(test)]
+fn lower_input(input: &str) -> Hir {
+    let parse = parsing::parse_file(input);
+    assert!(parse.errors().is_empty(), "{:?}", parse.errors());
+    Hir::lower(parse.file())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::lower_input as lower;
+    use crate::{FileId, Hir, HirError, ImportTarget, ItemKind, Resolution};
+    use ast::items::Item;
+    use ast::types::Type;
+    use rowan::ast::AstNode;
+    use type_check::Primitive;
+
+    /// The resolution of the type of the last alias in `input`.
+    fn resolve_alias(input: &str) -> Option<Resolution> {
+        let hir = lower(input);
+        assert!(hir.errors().is_empty(), "{:?}", hir.errors());
+
+        let alias = hir
+            .items()
+            .iter()
+            .rev()
+            .find_map(|x| match &x.node {
+                Item::Alias(alias) => Some(alias.ty()),
+                _ => None,
+            })
+            .unwrap();
+        let range = match alias {
+            Type::Named(x) => x.syntax().text_range(),
+            Type::Borrow(x) => x.syntax().text_range(),
+            Type::Own(x) => x.syntax().text_range(),
+            _ => panic!("Expected a path"),
+        };
+        hir.type_resolution(range).cloned()
+    }
+
+    #[test]
+    fn test_items() {
+        let hir = lower(
+            "struct A {}\nmod m {\nenum B {}\nconst C = 1\n}\ninterface D {}\nmod m {\nhandle E\n}",
+        );
+
+        let items: Vec<_> = hir
+            .items()
+            .iter()
+            .map(|x| (x.kind, x.name.as_str()))
+            .collect();
+        assert_eq!(
+            items,
+            vec![
+                (ItemKind::Struct, "A"),
+                (ItemKind::Enum, "B"),
+                (ItemKind::Const, "C"),
+                (ItemKind::Interface, "D"),
+                (ItemKind::Handle, "E"),
+            ]
+        );
+        assert_eq!(hir.modules().len(), 2);
+        assert_eq!(hir.item_path(hir.items()[4].id), "m::E");
+        assert_eq!(
+            hir.resolve(hir.root(), &["m", "B"]),
+            Some(Resolution::Item(hir.items()[1].id))
+        );
+    }
+
+    #[test]
+    fn test_types() {
+        let resolution = resolve_alias("struct A {}\ntype B = A");
+        assert!(matches!(resolution, Some(Resolution::Item(_))));
+
+        let resolution = resolve_alias("type B = u8");
+        assert_eq!(resolution, Some(Resolution::Primitive(Primitive::U8)));
+
+        // Items shadow primitives and outer modules
+        let resolution = resolve_alias("mod m {\nstruct u8 {}\ntype B = u8\n}");
+        assert!(matches!(resolution, Some(Resolution::Item(_))));
+
+        let resolution = resolve_alias("struct A {}\nmod m {\nmod n {\ntype B = A\n}\n}");
+        assert!(matches!(resolution, Some(Resolution::Item(_))));
+
+        let resolution = resolve_alias("mod m {\nhandle H\n}\ntype B = recv m::H");
+        assert!(matches!(resolution, Some(Resolution::Item(_))));
+    }
+
+    #[test]
+    fn test_uses() {
+        let input =
+            "mod a {\nmod b {\nstruct C {}\nstruct D {}\n}\n}\nuse a::b::{C, D}\ntype E = D";
+        let hir = lower(input);
+        assert!(hir.errors().is_empty());
+        assert_eq!(
+            hir.resolve(hir.root(), &["C"]),
+            Some(Resolution::Item(hir.items()[0].id))
+        );
+
+        let resolution = resolve_alias(input);
+        assert!(matches!(resolution, Some(Resolution::Item(id)) if id == hir.items()[1].id));
+
+        let resolution = resolve_alias("mod a {\nstruct S {}\n}\nmod m {\nuse a\ntype B = a::S\n}");
+        assert!(matches!(resolution, Some(Resolution::Item(_))));
+    }
+
+    #[test]
+    fn test_imports() {
+        let input = "import \"other.yip\" as other\nuse other::x::Y\ntype B = Y";
+        let hir = lower(input);
+        let import = hir.imports()[0].id;
+        assert_eq!(hir.imports()[0].alias.as_deref(), Some("other"));
+
+        let resolution = resolve_alias(input);
+        assert_eq!(
+            resolution,
+            Some(Resolution::Imported(import, vec!["x".into(), "Y".into()]))
+        );
+    }
+
+    #[test]
+    fn test_loaded_imports() {
+        let base = lower("struct S {}");
+        let other = parsing::parse_file(
+            "import \"base.yip\" as base\nmod x {\nstruct Y {}\n}\nconst C = 1",
+        );
+        let other =
+            Hir::lower_with_imports(other.file(), &[ImportTarget::File(FileId(0))], &[base]);
+        assert!(other.errors().is_empty());
+        let files = [lower("struct S {}"), other];
+
+        let input = "import \"other.yip\" as other\nuse other::x::Y\nstruct A {\na: Y\nb: other::base::S\nc: other::x::Z\nd: other::C\n}";
+        let parse = parsing::parse_file(input);
+        let hir = Hir::lower_with_imports(parse.file(), &[ImportTarget::File(FileId(1))], &files);
+        let errors: Vec<_> = hir.errors().iter().map(|x| x.to_string()).collect();
+        assert_eq!(
+            errors,
+            vec!["cannot find `other::x::Z`", "`other::C` is not a type"]
+        );
+
+        let import = hir.imports()[0].id;
+        let path = ["base".to_string(), "S".to_string()];
+        assert_eq!(
+            hir.resolve_imported(&files, import, &path),
+            Some((FileId(0), Resolution::Item(files[0].items()[0].id)))
+        );
+
+        let parse = parsing::parse_file("import \"a.yip\" as a\nimport \"b.yip\"");
+        let targets = [
+            ImportTarget::Failed("not found".into()),
+            ImportTarget::Cycle(vec!["b.yip".into(), "main.yip".into(), "b.yip".into()]),
+        ];
+        let hir = Hir::lower_with_imports(parse.file(), &targets, &[]);
+        let errors: Vec<_> = hir.errors().iter().map(|x| x.to_string()).collect();
+        assert_eq!(errors, vec!["can't load `a.yip`", "`b.yip` imports itself"]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let hir = lower("struct A {\na: B\nb: m::C\n}\nmod m {}\nuse m::{D, E}\ntype F = D");
+        let errors: Vec<_> = hir.errors().iter().map(|x| x.to_string()).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "cannot find `B`",
+                "cannot find `m::C`",
+                "cannot find `m::D`",
+                "cannot find `m::E`",
+            ]
+        );
+
+        let hir = lower(
+            "mod a {\nstruct S {}\n}\nmod b {\nstruct S {}\n}\nuse a::S\nuse b::S\ntype T = S",
+        );
+        assert!(matches!(
+            hir.errors(),
+            [HirError::AmbiguousName { candidates, .. }] if candidates.len() == 2
+        ));
+
+        let hir = lower("const A = 1\nmod m {}\nstruct S {\na: A\nb: ?m\n}");
+        assert_eq!(hir.errors().len(), 2);
+        assert!(matches!(hir.errors()[0], HirError::NotAType { .. }));
+        assert!(matches!(hir.errors()[1], HirError::NotAType { .. }));
+    }
+}
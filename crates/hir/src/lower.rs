@@ -0,0 +1,515 @@
+use crate::{cycles, duplicates, functions};
+use crate::{
+    Binding, FileId, Hir, HirError, Import, ImportId, ImportTarget, Item, ItemId, ItemKind, Module,
+    ModuleId, Resolution,
+};
+use ast::items::{File, Item as AstItem, UnionMember, UseDirective};
+use ast::types::Type;
+use ast::SyntaxToken;
+use rowan::ast::{AstChildren, AstNode};
+use std::collections::HashMap;
+use text_size::TextRange;
+use type_check::Primitive;
+
+/// The segments of a path, with the range each was written at.
+type Segments<'a> = [(&'a str, TextRange)];
+
+pub(crate) fn lower_file(file: &File, imports: &[ImportTarget], files: &[Hir]) -> Hir {
+    let mut lowering = Lowering {
+        hir: Hir {
+            file: FileId(files.len() as u32),
+            modules: Vec::new(),
+            items: Vec::new(),
+            imports: Vec::new(),
+            types: HashMap::new(),
+            functions: HashMap::new(),
+            errors: Vec::new(),
+        },
+        module_ids: HashMap::new(),
+        uses: Vec::new(),
+        types: Vec::new(),
+        imports,
+        files,
+    };
+
+    let root = lowering.module_id(None, Vec::new());
+    lowering.collect(file.items(), root);
+    lowering.finish()
+}
+
+struct Lowering<'a> {
+    hir: Hir,
+    module_ids: HashMap<Vec<String>, ModuleId>,
+    /// `use` directives and types are resolved once every definition is known
+    uses: Vec<(ModuleId, UseDirective)>,
+    types: Vec<(ModuleId, Type)>,
+    imports: &'a [ImportTarget],
+    files: &'a [Hir],
+}
+
+impl Lowering<'_> {
+    fn collect(&mut self, items: AstChildren<AstItem>, module: ModuleId) {
+        for item in items {
+            let (kind, name) = match &item {
+                AstItem::Struct(x) => {
+                    for member in x.members() {
+                        self.types.push((module, member.ty()));
+                    }
+                    (ItemKind::Struct, x.name())
+                }
+                AstItem::Union(x) => {
+                    for member in x.members() {
+                        match member {
+                            UnionMember::Simple(member) => self.types.push((module, member.ty())),
+                            UnionMember::Anonymous(member) => {
+                                for member in member.members() {
+                                    self.types.push((module, member.ty()));
+                                }
+                            }
+                        }
+                    }
+                    (ItemKind::Union, x.name())
+                }
+                AstItem::Enum(x) => {
+                    self.types.extend(x.representation().map(|ty| (module, ty)));
+                    (ItemKind::Enum, x.name())
+                }
+                AstItem::Alias(x) => {
+                    self.types.push((module, x.ty()));
+                    (ItemKind::Alias, x.name())
+                }
+                AstItem::Interface(x) => {
+                    for function in x.functions() {
+                        for parameter in function.parameters() {
+                            self.types.push((module, parameter.ty()));
+                        }
+                        self.types
+                            .extend(function.return_type().map(|ty| (module, ty)));
+                    }
+                    (ItemKind::Interface, x.name())
+                }
+                AstItem::Handle(x) => (ItemKind::Handle, x.name()),
+                AstItem::Const(x) => {
+                    self.types.extend(x.ty().map(|ty| (module, ty)));
+                    (ItemKind::Const, x.name())
+                }
+                // Annotations are named with `@` and don't share the namespace of items
+                AstItem::Annotation(x) => {
+                    for parameter in x.parameters() {
+                        self.types.push((module, parameter.ty()));
+                    }
+                    self.hir.modules[module.0 as usize]
+                        .annotations
+                        .entry(x.name().text().to_string())
+                        .or_insert_with(|| x.clone());
+                    continue;
+                }
+                AstItem::Module(x) => {
+                    let name = x.name();
+                    let mut path = self.hir.module(module).path.clone();
+                    path.push(name.text().to_string());
+
+                    let child = self.module_id(Some(module), path);
+                    self.define(module, &name, Resolution::Module(child));
+                    self.collect(x.items(), child);
+                    continue;
+                }
+                AstItem::Import(x) => {
+                    let id = ImportId(self.hir.imports.len() as u32);
+                    let alias = x.alias();
+                    if let Some(alias) = &alias {
+                        self.define(module, alias, Resolution::Imported(id, Vec::new()));
+                    }
+
+                    let range = x.url_token().text_range();
+                    let url = || x.url().unwrap_or_else(|_| x.url_token().text().to_string());
+                    let file = match self.imports.get(id.0 as usize) {
+                        Some(ImportTarget::File(file)) => Some(*file),
+                        Some(ImportTarget::Failed(reason)) => {
+                            self.hir.errors.push(HirError::ImportFailed {
+                                url: url(),
+                                reason: reason.clone(),
+                                range,
+                            });
+                            None
+                        }
+                        Some(ImportTarget::Cycle(urls)) => {
+                            self.hir.errors.push(HirError::ImportCycle {
+                                urls: urls.clone(),
+                                range,
+                            });
+                            None
+                        }
+                        None => None,
+                    };
+
+                    self.hir.imports.push(Import {
+                        id,
+                        alias: alias.map(|x| x.text().to_string()),
+                        module,
+                        file,
+                        node: x.clone(),
+                    });
+                    continue;
+                }
+                AstItem::Use(x) => {
+                    self.uses.push((module, x.clone()));
+                    continue;
+                }
+                AstItem::Plugin(_) => continue,
+            };
+
+            let id = ItemId(self.hir.items.len() as u32);
+            self.define(module, &name, Resolution::Item(id));
+            self.hir.items.push(Item {
+                id,
+                kind,
+                name: name.text().to_string(),
+                name_range: name.text_range(),
+                module,
+                node: item,
+            });
+        }
+    }
+
+    /// The module at `path`, created if it wasn't declared before.
+    fn module_id(&mut self, parent: Option<ModuleId>, path: Vec<String>) -> ModuleId {
+        if let Some(id) = self.module_ids.get(&path) {
+            return *id;
+        }
+
+        let id = ModuleId(self.hir.modules.len() as u32);
+        self.module_ids.insert(path.clone(), id);
+        self.hir.modules.push(Module {
+            id,
+            parent,
+            path,
+            definitions: HashMap::new(),
+            uses: HashMap::new(),
+            annotations: HashMap::new(),
+        });
+        id
+    }
+
+    fn define(&mut self, module: ModuleId, name: &SyntaxToken, resolution: Resolution) {
+        self.hir.modules[module.0 as usize]
+            .definitions
+            .entry(name.text().to_string())
+            .or_default()
+            .push(Binding {
+                resolution: Some(resolution),
+                range: name.text_range(),
+            });
+    }
+
+    fn finish(mut self) -> Hir {
+        // Imports that couldn't be loaded were reported while collecting
+        let mut errors = std::mem::take(&mut self.hir.errors);
+
+        // Uses are resolved from the definitions alone, so their order doesn't matter
+        let mut uses = Vec::new();
+        for (module, directive) in &self.uses {
+            for path in directive.paths() {
+                let Some(name) = path.last() else {
+                    continue;
+                };
+
+                let segments: Vec<_> = path.iter().map(|x| (x.text(), x.text_range())).collect();
+                let binding = Binding {
+                    resolution: resolve_use(&self.hir, self.files, &segments, &mut errors),
+                    range: name.text_range(),
+                };
+                uses.push((*module, name.text().to_string(), binding));
+            }
+        }
+        for (module, name, binding) in uses {
+            self.hir.modules[module.0 as usize]
+                .uses
+                .entry(name)
+                .or_default()
+                .push(binding);
+        }
+
+        let mut types = HashMap::new();
+        for (module, ty) in self.types {
+            resolve_type(&self.hir, self.files, module, ty, &mut types, &mut errors);
+        }
+
+        self.hir.types = types;
+        duplicates::check_duplicates(&self.hir, &mut errors);
+        cycles::check_cycles(&self.hir, &mut errors);
+        self.hir.functions = functions::assign_ids(&self.hir, &mut errors);
+
+        errors.sort_by_key(|x| x.range().start());
+        self.hir.errors = errors;
+        self.hir
+    }
+}
+
+fn resolve_type(
+    hir: &Hir,
+    files: &[Hir],
+    module: ModuleId,
+    ty: Type,
+    types: &mut HashMap<TextRange, Resolution>,
+    errors: &mut Vec<HirError>,
+) {
+    let (range, segments): (_, Vec<_>) = match ty {
+        Type::Named(x) => (x.syntax().text_range(), x.segments().collect()),
+        Type::Borrow(x) => (x.syntax().text_range(), x.segments().collect()),
+        Type::Own(x) => (x.syntax().text_range(), x.segments().collect()),
+        Type::Result(x) => {
+            resolve_type(hir, files, module, x.inner(), types, errors);
+            if let Some(error) = x.error() {
+                resolve_type(hir, files, module, error, types, errors);
+            }
+            return;
+        }
+        Type::Option(x) => return resolve_type(hir, files, module, x.inner(), types, errors),
+        Type::List(x) => return resolve_type(hir, files, module, x.inner(), types, errors),
+        Type::Tuple(x) => {
+            for ty in x.types() {
+                resolve_type(hir, files, module, ty, types, errors);
+            }
+            return;
+        }
+    };
+
+    let segments: Vec<_> = segments
+        .iter()
+        .map(|x| (x.text(), x.text_range()))
+        .collect();
+    let Some(resolution) = resolve_path(hir, files, module, &segments, errors) else {
+        return;
+    };
+
+    let is_type = match &resolution {
+        Resolution::Module(_) => false,
+        Resolution::Item(id) => hir.item(*id).kind != ItemKind::Const,
+        Resolution::Primitive(_) => true,
+        Resolution::Imported(import, path) => match resolve_imported(hir, files, *import, path) {
+            Some((file, Resolution::Item(id))) => {
+                files[file.0 as usize].item(id).kind != ItemKind::Const
+            }
+            Some((_, Resolution::Module(_))) => false,
+            // Anything in a file that isn't loaded is taken to be a type
+            _ => true,
+        },
+    };
+    if !is_type {
+        errors.push(HirError::NotAType {
+            name: path_name(&segments),
+            range,
+        });
+        return;
+    }
+
+    types.insert(range, resolution);
+}
+
+/// What looking up a name in a single module found.
+enum Lookup {
+    Found(Resolution),
+    /// The name is only brought in by a `use` that failed to resolve
+    Broken,
+    /// The name refers to several different things, these are the ranges defining them
+    Ambiguous(Vec<TextRange>),
+    Missing,
+}
+
+fn lookup<'a>(bindings: impl Iterator<Item = &'a Binding>) -> Lookup {
+    let mut found: Vec<&Binding> = Vec::new();
+    let mut broken = false;
+
+    for binding in bindings {
+        match &binding.resolution {
+            Some(resolution) => {
+                // A module declared twice is the same module
+                if !found
+                    .iter()
+                    .any(|x| x.resolution.as_ref() == Some(resolution))
+                {
+                    found.push(binding);
+                }
+            }
+            None => broken = true,
+        }
+    }
+
+    match found.as_slice() {
+        [] if broken => Lookup::Broken,
+        [] => Lookup::Missing,
+        [binding] => Lookup::Found(binding.resolution.clone().unwrap()),
+        bindings => Lookup::Ambiguous(bindings.iter().map(|x| x.range).collect()),
+    }
+}
+
+impl Module {
+    /// The first definition of `name`. Later ones are reported as duplicates, so they are ignored
+    /// instead of making the name ambiguous.
+    fn definition(&self, name: &str) -> Option<&Binding> {
+        self.definitions.get(name)?.first()
+    }
+
+    fn uses(&self, name: &str) -> impl Iterator<Item = &Binding> {
+        self.uses.get(name).into_iter().flatten()
+    }
+}
+
+/// Resolves a path written in `module`. The first segment is looked up in `module`, then in each
+/// enclosing module. Primitive types are only used if no module defines their name.
+pub(crate) fn resolve_path(
+    hir: &Hir,
+    files: &[Hir],
+    module: ModuleId,
+    segments: &Segments,
+    errors: &mut Vec<HirError>,
+) -> Option<Resolution> {
+    let (name, _) = *segments.first()?;
+
+    let first = std::iter::successors(Some(module), |x| hir.module(*x).parent)
+        .map(|x| {
+            let module = hir.module(x);
+            lookup(module.definition(name).into_iter().chain(module.uses(name)))
+        })
+        .find(|x| !matches!(x, Lookup::Missing))
+        .unwrap_or(Lookup::Missing);
+
+    if let (Lookup::Missing, Some(primitive), 1) =
+        (&first, Primitive::from_name(name), segments.len())
+    {
+        return Some(Resolution::Primitive(primitive));
+    }
+
+    follow(hir, files, first, segments, errors)
+}
+
+/// Resolves the path of a `use`, which always starts at the top level of the file.
+fn resolve_use(
+    hir: &Hir,
+    files: &[Hir],
+    segments: &Segments,
+    errors: &mut Vec<HirError>,
+) -> Option<Resolution> {
+    let (name, _) = *segments.first()?;
+    let first = lookup(hir.module(hir.root()).definition(name).into_iter());
+    follow(hir, files, first, segments, errors)
+}
+
+/// Follows `path` from the top level of the file loaded for `import` of `hir`. Only definitions
+/// are visible from other files, `use` directives aren't.
+pub(crate) fn resolve_imported(
+    hir: &Hir,
+    files: &[Hir],
+    import: ImportId,
+    path: &[String],
+) -> Option<(FileId, Resolution)> {
+    let file = hir.import(import).file?;
+    let mut current = files.get(file.0 as usize)?;
+    let mut found = (file, Resolution::Module(current.root()));
+
+    for name in path {
+        let Resolution::Module(module) = found.1 else {
+            return None;
+        };
+        found.1 = current
+            .module(module)
+            .definition(name)?
+            .resolution
+            .clone()?;
+
+        // Aliases of files imported by the imported file
+        if let Resolution::Imported(import, path) = &found.1 {
+            found = resolve_imported(current, files, *import, path)?;
+            current = &files[found.0 .0 as usize];
+        }
+    }
+
+    Some(found)
+}
+
+/// Follows the segments after the first one, which was looked up as `first`. Paths into loaded
+/// files are checked against the items of those files, but still resolve to
+/// [`Resolution::Imported`].
+fn follow(
+    hir: &Hir,
+    files: &[Hir],
+    first: Lookup,
+    segments: &Segments,
+    errors: &mut Vec<HirError>,
+) -> Option<Resolution> {
+    let mut found = first;
+    // The file the segments are looked up in, and the import of `hir` the path entered it through
+    // with the path up to the segment naming it
+    let mut current = hir;
+    let mut entered: Option<(ImportId, Vec<String>, usize)> = None;
+    let imported = |entered: Option<(ImportId, Vec<String>, usize)>| {
+        entered.map(|(import, mut path, idx)| {
+            path.extend(segments[idx + 1..].iter().map(|x| x.0.to_string()));
+            Resolution::Imported(import, path)
+        })
+    };
+
+    for (idx, (_, range)) in segments.iter().enumerate() {
+        let resolution = match found {
+            Lookup::Found(resolution) => resolution,
+            Lookup::Broken => return None,
+            Lookup::Ambiguous(candidates) => {
+                errors.push(HirError::AmbiguousName {
+                    name: path_name(&segments[..=idx]),
+                    range: *range,
+                    candidates,
+                });
+                return None;
+            }
+            Lookup::Missing => {
+                errors.push(HirError::UnresolvedName {
+                    name: path_name(&segments[..=idx]),
+                    range: *range,
+                });
+                return None;
+            }
+        };
+
+        let Some((next, _)) = segments.get(idx + 1) else {
+            return imported(entered).or(Some(resolution));
+        };
+
+        found = match resolution {
+            Resolution::Module(module) => {
+                lookup(current.module(module).definition(next).into_iter())
+            }
+            Resolution::Imported(import, mut path) => {
+                match resolve_imported(current, files, import, &path) {
+                    Some((file, target)) => {
+                        if entered.is_none() {
+                            entered = Some((import, path, idx));
+                        }
+                        current = &files[file.0 as usize];
+                        match target {
+                            Resolution::Module(module) => {
+                                lookup(current.module(module).definition(next).into_iter())
+                            }
+                            _ => Lookup::Missing,
+                        }
+                    }
+                    // The rest of the path is in a file that isn't loaded
+                    None => {
+                        if entered.is_some() {
+                            return imported(entered);
+                        }
+                        path.extend(segments[idx + 1..].iter().map(|x| x.0.to_string()));
+                        return Some(Resolution::Imported(import, path));
+                    }
+                }
+            }
+            Resolution::Item(_) | Resolution::Primitive(_) => Lookup::Missing,
+        };
+    }
+
+    None
+}
+
+fn path_name(segments: &Segments) -> String {
+    segments.iter().map(|x| x.0).collect::<Vec<_>>().join("::")
+}
@@ -0,0 +1,108 @@
+use crate::{Hir, ModuleId, Resolution};
+use ast::items::{AnnotationDefinition, Item as AstItem};
+use ast::types::NamedType;
+use rowan::ast::AstNode;
+use type_check::{Definition, Resolver, ScopeId};
+
+/// Resolves the paths in constant expressions and annotations for the type checker, like the paths
+/// of types are resolved. Scopes of other files are those of the models a [`Hir`] was lowered
+/// with, so paths through imports are followed into them.
+pub struct Names<'a> {
+    hir: &'a Hir,
+    files: &'a [Hir],
+}
+
+impl<'a> Names<'a> {
+    pub(crate) fn new(hir: &'a Hir, files: &'a [Hir]) -> Self {
+        Names { hir, files }
+    }
+
+    /// The model of `file`, the one names are resolved for or one of those it was lowered with.
+    fn hir(&self, file: u32) -> Option<&'a Hir> {
+        if file == self.hir.file.0 {
+            Some(self.hir)
+        } else {
+            self.files.get(file as usize)
+        }
+    }
+
+    /// What `resolution`, found in `hir`, refers to. Paths into imported files are followed to the
+    /// file defining them.
+    fn definition(&self, hir: &'a Hir, resolution: Resolution) -> Option<Definition> {
+        let (hir, resolution) = match resolution {
+            Resolution::Imported(import, path) => {
+                let (file, resolution) = hir.resolve_imported(self.files, import, &path)?;
+                (self.hir(file.0)?, resolution)
+            }
+            resolution => (hir, resolution),
+        };
+
+        Some(match resolution {
+            Resolution::Primitive(primitive) => Definition::Primitive(primitive),
+            Resolution::Module(module) => Definition::Module(hir.scope(module)),
+            Resolution::Item(id) => {
+                let item = hir.item(id);
+                let scope = hir.scope(item.module);
+                match &item.node {
+                    AstItem::Const(x) => Definition::Const(x.clone(), scope),
+                    AstItem::Enum(x) => Definition::Enum(x.clone(), scope),
+                    AstItem::Alias(x) => Definition::Alias(x.ty(), scope),
+                    _ => Definition::Item,
+                }
+            }
+            // Imports of files that weren't loaded
+            Resolution::Imported(..) => return None,
+        })
+    }
+}
+
+impl Resolver for Names<'_> {
+    fn resolve(&self, scope: ScopeId, path: &[&str]) -> Option<Definition> {
+        let hir = self.hir(scope.file)?;
+        let resolution = hir.resolve(ModuleId(scope.module), path)?;
+        self.definition(hir, resolution)
+    }
+
+    /// Types are looked up in the resolutions recorded while lowering the file they are written in.
+    fn resolve_type(&self, scope: ScopeId, ty: &NamedType) -> Option<Definition> {
+        let hir = self.hir(scope.file)?;
+        let resolution = hir.type_resolution(ty.syntax().text_range())?.clone();
+        self.definition(hir, resolution)
+    }
+
+    fn module(&self, scope: ScopeId, name: &str) -> Option<ScopeId> {
+        let hir = self.hir(scope.file)?;
+        let module = hir.modules().iter().find(|x| {
+            x.parent == Some(ModuleId(scope.module)) && x.path.last().is_some_and(|x| x == name)
+        })?;
+        Some(hir.scope(module.id))
+    }
+
+    /// Annotations without a path are looked up in `scope`, then in each enclosing module.
+    fn resolve_annotation(
+        &self,
+        scope: ScopeId,
+        path: &[&str],
+    ) -> Option<(AnnotationDefinition, ScopeId)> {
+        let (name, modules) = path.split_last()?;
+        let hir = self.hir(scope.file)?;
+
+        if modules.is_empty() {
+            return std::iter::successors(Some(ModuleId(scope.module)), |x| hir.module(*x).parent)
+                .find_map(|x| {
+                    let annotation = hir.module(x).annotations.get(*name)?;
+                    Some((annotation.clone(), hir.scope(x)))
+                });
+        }
+
+        let Some(Definition::Module(scope)) = self.resolve(scope, modules) else {
+            return None;
+        };
+        let annotation = self
+            .hir(scope.file)?
+            .module(ModuleId(scope.module))
+            .annotations
+            .get(*name)?;
+        Some((annotation.clone(), scope))
+    }
+}
@@ -0,0 +1,379 @@
+//! ## Yip Import Loading
+//!
+//! This crate loads a `.yip` file together with every file it imports, directly or through other
+//! imports. Files are identified by absolute URLs, the URLs of imports are joined with the URL of
+//! the importing file, so relative paths work the same locally and on a remote host.
+//!
+//! Where the text of a file comes from is up to an [`ImportResolver`]. [`FileResolver`] reads
+//! `file://` URLs and [`CacheResolver`] everything else from a cache directory, which is all that
+//! is needed to work offline. Only files loaded from `file://` URLs can import `file://` URLs, so a
+//! remote file can't read local ones.
+//!
+//! Each file is parsed and lowered into a [`Hir`] after the files it imports, so paths through an
+//! `import ... as alias` resolve to the items of the imported file. Imports that can't be loaded
+//! and import cycles are reported by the [`Hir`] of the importing file.
+//!
+mod resolver;
+mod url;
+
+pub use resolver::{CacheResolver, FileResolver, ImportResolver, ResolveError, Resolvers};
+pub use url::{file_path, file_url, join};
+
+use ast::items::{File, ImportDirective};
+use hir::{FileId, Hir, ImportTarget, Resolution};
+use parsing::Parse;
+use rowan::ast::AstNode;
+use std::collections::HashMap;
+
+/// A loaded file.
+pub struct SourceFile {
+    pub url: String,
+    pub text: String,
+    pub parse: Parse,
+    /// The semantic model, if the file was parsed without errors
+    pub hir: Option<FileId>,
+}
+
+/// A file and every file it imports.
+pub struct Workspace {
+    files: Vec<SourceFile>,
+    hirs: Vec<Hir>,
+}
+
+impl Workspace {
+    /// Loads the file at `url` with the text `text` and every file it imports.
+    pub fn load(url: &str, text: String, resolver: &dyn ImportResolver) -> Self {
+        let mut loader = Loader {
+            resolver,
+            files: Vec::new(),
+            hirs: Vec::new(),
+            loaded: HashMap::new(),
+            stack: Vec::new(),
+        };
+        loader.visit(url.to_string(), text);
+
+        Workspace {
+            files: loader.files,
+            hirs: loader.hirs,
+        }
+    }
+
+    /// The file that was loaded first.
+    pub fn root(&self) -> &SourceFile {
+        &self.files[0]
+    }
+
+    /// Every loaded file, the root first and the others in the order their imports were found.
+    pub fn files(&self) -> &[SourceFile] {
+        &self.files
+    }
+
+    pub fn hir(&self, id: FileId) -> &Hir {
+        &self.hirs[id.0 as usize]
+    }
+
+    /// The models of every file parsed without errors, which is what the [`Hir`] of each file was
+    /// lowered with.
+    pub fn hirs(&self) -> &[Hir] {
+        &self.hirs
+    }
+
+    /// Follows a resolution found in `file` through imports to the file defining it.
+    pub fn target(&self, file: FileId, resolution: &Resolution) -> Option<(FileId, Resolution)> {
+        match resolution {
+            Resolution::Imported(import, path) => {
+                self.hir(file).resolve_imported(&self.hirs, *import, path)
+            }
+            _ => Some((file, resolution.clone())),
+        }
+    }
+}
+
+struct Loader<'a> {
+    resolver: &'a dyn ImportResolver,
+    files: Vec<SourceFile>,
+    hirs: Vec<Hir>,
+    /// What importing each URL gives, files still being loaded are on `stack`
+    loaded: HashMap<String, ImportTarget>,
+    stack: Vec<String>,
+}
+
+impl Loader<'_> {
+    /// Parses a file, loads its imports and lowers it once they are.
+    fn visit(&mut self, url: String, text: String) -> ImportTarget {
+        let parse = parsing::parse_file(&text);
+        let file = parse.file().clone();
+        let valid = parse.errors().is_empty();
+
+        let index = self.files.len();
+        self.files.push(SourceFile {
+            url: url.clone(),
+            text,
+            parse,
+            hir: None,
+        });
+
+        // Lowering expects a complete tree, importers report the file as broken instead
+        let target = if valid {
+            self.stack.push(url.clone());
+            let imports = self.imports(&url, &file);
+            self.stack.pop();
+
+            let id = FileId(self.hirs.len() as u32);
+            self.hirs
+                .push(Hir::lower_with_imports(&file, &imports, &self.hirs));
+            self.files[index].hir = Some(id);
+            ImportTarget::File(id)
+        } else {
+            ImportTarget::Failed("the file contains errors".to_string())
+        };
+
+        self.loaded.insert(url, target.clone());
+        target
+    }
+
+    /// Loads what the `import` directives of `file` refer to, in the order they appear, which is
+    /// the order lowering assigns their IDs in.
+    fn imports(&mut self, url: &str, file: &File) -> Vec<ImportTarget> {
+        let directives = file
+            .syntax()
+            .descendants()
+            .filter_map(ImportDirective::cast);
+        let mut targets = Vec::new();
+
+        for directive in directives {
+            let target = match directive.url() {
+                Ok(import) => match join(url, &import) {
+                    // Only local files may read other local files, whatever the resolver allows
+                    Some(import) if is_local(&import) && !is_local(url) => {
+                        ImportTarget::Failed("only local files can import local files".to_string())
+                    }
+                    Some(import) => self.import(import),
+                    None => ImportTarget::Failed("the path leaves the root directory".to_string()),
+                },
+                Err(_) => ImportTarget::Failed("the URL contains an invalid escape".to_string()),
+            };
+            targets.push(target);
+        }
+        targets
+    }
+
+    fn import(&mut self, url: String) -> ImportTarget {
+        if let Some(start) = self.stack.iter().position(|x| *x == url) {
+            let mut urls = self.stack[start..].to_vec();
+            urls.push(url);
+            return ImportTarget::Cycle(urls);
+        }
+        if let Some(target) = self.loaded.get(&url) {
+            return target.clone();
+        }
+
+        match self.resolver.load(&url) {
+            Ok(text) => self.visit(url, text),
+            Err(err) => {
+                let target = ImportTarget::Failed(err.to_string());
+                self.loaded.insert(url, target.clone());
+                target
+            }
+        }
+    }
+}
+
+fn is_local(url: &str) -> bool {
+    url.starts_with("file://")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{ImportResolver, ResolveError, Workspace};
+    use hir::{HirError, Resolution};
+    use std::collections::HashMap;
+    use type_check::Scopes;
+
+    /// Serves files from memory, as `mem:///<name>`.
+    struct Memory(HashMap<&'static str, &'static str>);
+
+    impl Memory {
+        fn new(files: &[(&'static str, &'static str)]) -> Self {
+            Memory(files.iter().copied().collect())
+        }
+
+        fn load_root(&self, name: &str) -> Workspace {
+            let url = format!("mem:///{}", name);
+            Workspace::load(&url, self.load(&url).unwrap(), self)
+        }
+    }
+
+    impl ImportResolver for Memory {
+        fn load(&self, url: &str) -> Result<String, ResolveError> {
+            let name = url
+                .strip_prefix("mem:///")
+                .ok_or(ResolveError::Unsupported)?;
+            self.0
+                .get(name)
+                .map(|x| x.to_string())
+                .ok_or(ResolveError::NotCached)
+        }
+    }
+
+    /// The messages of the hir errors of every file.
+    fn errors(workspace: &Workspace) -> Vec<(String, String)> {
+        workspace
+            .files()
+            .iter()
+            .filter_map(|file| Some((file, workspace.hir(file.hir?))))
+            .flat_map(|(file, hir)| {
+                hir.errors()
+                    .iter()
+                    .map(|x| (file.url.clone(), x.to_string()))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_load() {
+        let memory = Memory::new(&[
+            (
+                "main.yip",
+                "import \"lib/types.yip\" as types\nimport \"lib/common.yip\" as common\nstruct A {\na: types::T\nb: common::C\n}",
+            ),
+            ("lib/types.yip", "import \"common.yip\" as common\nstruct T {\nc: common::C\n}"),
+            ("lib/common.yip", "struct C {}"),
+        ]);
+        let workspace = memory.load_root("main.yip");
+        assert!(errors(&workspace).is_empty());
+
+        // Files imported twice are loaded once
+        let urls: Vec<_> = workspace.files().iter().map(|x| x.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec![
+                "mem:///main.yip",
+                "mem:///lib/types.yip",
+                "mem:///lib/common.yip"
+            ]
+        );
+
+        let root = workspace.root().hir.unwrap();
+        let hir = workspace.hir(root);
+        let a = hir.items()[0].id;
+        let Some(Resolution::Imported(import, path)) = hir.resolve(hir.root(), &["types", "T"])
+        else {
+            panic!("Expected an imported type");
+        };
+        let (file, resolution) = workspace
+            .target(root, &Resolution::Imported(import, path))
+            .unwrap();
+        assert_eq!(
+            workspace.hir(file).item_path(match resolution {
+                Resolution::Item(id) => id,
+                _ => panic!("Expected an item"),
+            }),
+            "T"
+        );
+        assert_eq!(
+            workspace.target(root, &Resolution::Item(a)),
+            Some((root, Resolution::Item(a)))
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let memory = Memory::new(&[
+            (
+                "main.yip",
+                "import \"missing.yip\" as missing\nimport \"broken.yip\" as broken\nimport \"other.yip\" as other\ntype A = other::Missing",
+            ),
+            ("broken.yip", "struct {"),
+            ("other.yip", "import \"file:///etc/passwd\" as local\nstruct B {}"),
+        ]);
+        let workspace = memory.load_root("main.yip");
+        let errors: Vec<_> = errors(&workspace).into_iter().map(|x| x.1).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "can't load `missing.yip`",
+                "can't load `broken.yip`",
+                "cannot find `other::Missing`",
+                "can't load `file:///etc/passwd`",
+            ]
+        );
+        let other = workspace.hir(workspace.files()[2].hir.unwrap());
+        let HirError::ImportFailed { reason, .. } = &other.errors()[0] else {
+            panic!("Expected a failed import");
+        };
+        assert_eq!(reason, "only local files can import local files");
+
+        let broken = &workspace.files()[1];
+        assert_eq!(broken.url, "mem:///broken.yip");
+        assert!(broken.hir.is_none() && !broken.parse.errors().is_empty());
+    }
+
+    #[test]
+    fn test_type_check() {
+        let memory = Memory::new(&[
+            (
+                "main.yip",
+                "import \"lib.yip\" as lib\nconst X: u8 = lib::MAX\nconst Y: u8 = lib::Color::Red\nconst Z: u8 = lib::MAX + 1\n@lib::tag(lib::MAX)\nstruct A {}\n@lib::missing\nstruct B {}",
+            ),
+            (
+                "lib.yip",
+                "const MAX = 255\nenum Color: u8 {\nRed = 1\n}\nannotation tag(x: u8) on struct",
+            ),
+        ]);
+        let workspace = memory.load_root("main.yip");
+        assert!(errors(&workspace).is_empty());
+
+        let root = workspace.root().hir.unwrap();
+        let hir = workspace.hir(root);
+        let names = hir.names(workspace.hirs());
+        let scopes = Scopes::new(&names);
+        let diagnostics = type_check::check_file(
+            workspace.root().parse.file(),
+            &scopes.scope(hir.scope(hir.root())),
+        );
+
+        let errors: Vec<_> = diagnostics.iter().map(|x| x.error.to_string()).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "integer out of range for `u8`",
+                "cannot find annotation `@lib::missing`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cycles() {
+        let memory = Memory::new(&[
+            ("a.yip", "import \"b.yip\" as b\nstruct A {\nb: ?b::B\n}"),
+            ("b.yip", "import \"c.yip\" as c\nstruct B {}"),
+            ("c.yip", "import \"a.yip\" as a\nimport \"c.yip\""),
+        ]);
+        let workspace = memory.load_root("a.yip");
+        let errors = errors(&workspace);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            errors[0],
+            (
+                "mem:///c.yip".to_string(),
+                "`mem:///a.yip` imports itself".to_string()
+            )
+        );
+        assert_eq!(errors[1].1, "`mem:///c.yip` imports itself");
+
+        let c = workspace.hir(workspace.files()[2].hir.unwrap());
+        let HirError::ImportCycle { urls, .. } = &c.errors()[0] else {
+            panic!("Expected an import cycle");
+        };
+        assert_eq!(
+            urls,
+            &[
+                "mem:///a.yip",
+                "mem:///b.yip",
+                "mem:///c.yip",
+                "mem:///a.yip"
+            ]
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use crate::url::file_path;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Loads the text of imported files. Implement this to import from other places, like a registry
+/// over the network, and combine several resolvers with [`Resolvers`].
+pub trait ImportResolver {
+    /// The text of the file at the absolute `url`, or [`ResolveError::Unsupported`] if this
+    /// resolver doesn't handle URLs like it.
+    fn load(&self, url: &str) -> Result<String, ResolveError>;
+}
+
+#[derive(Debug)]
+pub enum ResolveError {
+    /// No resolver handles the URL
+    Unsupported,
+    /// The file isn't in the cache and there is nothing to fetch it with
+    NotCached,
+    Io(io::Error),
+}
+
+impl Display for ResolveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ResolveError::Unsupported => write!(f, "no resolver handles this URL"),
+            ResolveError::NotCached => write!(f, "the file isn't in the cache"),
+            ResolveError::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for ResolveError {}
+
+/// Loads `file://` URLs from the local file system.
+pub struct FileResolver;
+
+impl ImportResolver for FileResolver {
+    fn load(&self, url: &str) -> Result<String, ResolveError> {
+        let path = file_path(url).ok_or(ResolveError::Unsupported)?;
+        fs::read_to_string(path).map_err(ResolveError::Io)
+    }
+}
+
+/// Loads every URL other than `file://` from a cache directory, at `<dir>/<scheme>/<host>/<path>`.
+/// Files missing from the cache are fetched with the fallback resolver and stored, without one
+/// only cached files can be imported, which makes it work fully offline.
+pub struct CacheResolver {
+    dir: PathBuf,
+    fallback: Option<Box<dyn ImportResolver>>,
+}
+
+impl CacheResolver {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        CacheResolver {
+            dir: dir.into(),
+            fallback: None,
+        }
+    }
+
+    /// Fetches files that aren't cached yet with `fallback`.
+    pub fn with_fallback(mut self, fallback: impl ImportResolver + 'static) -> Self {
+        self.fallback = Some(Box::new(fallback));
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Where the file at `url` is cached. URLs are normalized before they get here, so they
+    /// can't contain `..` segments leaving the cache.
+    fn path(&self, url: &str) -> Option<PathBuf> {
+        let (scheme, rest) = url.split_once("://")?;
+        if scheme == "file" {
+            return None;
+        }
+
+        let mut path = self.dir.join(scheme);
+        path.extend(rest.split('/').filter(|x| !x.is_empty()));
+        Some(path)
+    }
+}
+
+impl ImportResolver for CacheResolver {
+    fn load(&self, url: &str) -> Result<String, ResolveError> {
+        let path = self.path(url).ok_or(ResolveError::Unsupported)?;
+
+        match fs::read_to_string(&path) {
+            Ok(text) => Ok(text),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                let fallback = self.fallback.as_ref().ok_or(ResolveError::NotCached)?;
+                let text = fallback.load(url)?;
+
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent).map_err(ResolveError::Io)?;
+                }
+                fs::write(&path, &text).map_err(ResolveError::Io)?;
+                Ok(text)
+            }
+            Err(err) => Err(ResolveError::Io(err)),
+        }
+    }
+}
+
+/// Tries each resolver in turn, until one handles the URL.
+pub struct Resolvers(pub Vec<Box<dyn ImportResolver>>);
+
+impl ImportResolver for Resolvers {
+    fn load(&self, url: &str) -> Result<String, ResolveError> {
+        for resolver in &self.0 {
+            match resolver.load(url) {
+                Err(ResolveError::Unsupported) => continue,
+                result => return result,
+            }
+        }
+        Err(ResolveError::Unsupported)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{CacheResolver, FileResolver, ImportResolver, ResolveError, Resolvers};
+    use crate::url::file_url;
+    use std::fs;
+    use std::path::PathBuf;
+
+    /// An empty directory for a test to work in.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("yip-loader-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Answers every URL with the same text.
+    struct Remote(&'static str);
+
+    impl ImportResolver for Remote {
+        fn load(&self, _: &str) -> Result<String, ResolveError> {
+            Ok(self.0.to_string())
+        }
+    }
+
+    #[test]
+    fn test_files() {
+        let dir = temp_dir("files");
+        fs::write(dir.join("a.yip"), "struct A {}").unwrap();
+
+        let url = file_url(&dir.join("a.yip")).unwrap();
+        assert_eq!(FileResolver.load(&url).unwrap(), "struct A {}");
+        assert!(matches!(
+            FileResolver.load("https://example.com/a.yip"),
+            Err(ResolveError::Unsupported)
+        ));
+        assert!(matches!(
+            FileResolver.load(&format!("{}.missing", url)),
+            Err(ResolveError::Io(_))
+        ));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_cache() {
+        let dir = temp_dir("cache");
+        let url = "https://example.com/lib/a.yip";
+
+        let offline = CacheResolver::new(&dir);
+        assert!(matches!(offline.load(url), Err(ResolveError::NotCached)));
+        assert!(matches!(
+            offline.load("file:///a.yip"),
+            Err(ResolveError::Unsupported)
+        ));
+
+        // Fetched files are stored, after which they load without the fallback
+        let online = CacheResolver::new(&dir).with_fallback(Remote("struct A {}"));
+        assert_eq!(online.load(url).unwrap(), "struct A {}");
+        assert!(dir.join("https/example.com/lib/a.yip").is_file());
+        assert_eq!(offline.load(url).unwrap(), "struct A {}");
+
+        let resolvers = Resolvers(vec![Box::new(FileResolver), Box::new(offline)]);
+        assert_eq!(resolvers.load(url).unwrap(), "struct A {}");
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,91 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The absolute URL of an import written in the file at `base`. Relative paths are taken from the
+/// directory of `base`, absolute ones from the root of its host. Returns `None` if the path leaves
+/// the root with `..`.
+pub fn join(base: &str, url: &str) -> Option<String> {
+    if url.contains("://") {
+        let (prefix, path) = split(url)?;
+        return Some(format!("{}{}", prefix, normalize(path)?));
+    }
+
+    let (prefix, path) = split(base)?;
+    let path = if url.starts_with('/') {
+        url.to_string()
+    } else {
+        let directory = &path[..path.rfind('/').unwrap_or(0)];
+        format!("{}/{}", directory, url)
+    };
+    Some(format!("{}{}", prefix, normalize(&path)?))
+}
+
+/// Splits `url` into its scheme and host, and its path.
+fn split(url: &str) -> Option<(&str, &str)> {
+    let start = url.find("://")? + 3;
+    let path = url[start..].find('/').map_or(url.len(), |x| start + x);
+    Some(url.split_at(path))
+}
+
+/// Removes `.` and `..` segments and repeated slashes from `path`.
+fn normalize(path: &str) -> Option<String> {
+    let mut segments = Vec::new();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop()?;
+            }
+            _ => segments.push(segment),
+        }
+    }
+    Some(format!("/{}", segments.join("/")))
+}
+
+/// The `file://` URL of the file at `path`.
+pub fn file_url(path: &Path) -> io::Result<String> {
+    let path = path.canonicalize()?;
+    Ok(format!("file://{}", path.display()))
+}
+
+/// The path of a `file://` URL.
+pub fn file_path(url: &str) -> Option<PathBuf> {
+    url.strip_prefix("file://").map(PathBuf::from)
+}
+
+#[cfg(test)]
+mod test {
+    use super::join;
+
+    #[test]
+    fn test_join() {
+        let base = "file:///schemas/app/main.yip";
+        assert_eq!(
+            join(base, "types.yip").as_deref(),
+            Some("file:///schemas/app/types.yip")
+        );
+        assert_eq!(
+            join(base, "./common/../../shared.yip").as_deref(),
+            Some("file:///schemas/shared.yip")
+        );
+        assert_eq!(
+            join(base, "/other.yip").as_deref(),
+            Some("file:///other.yip")
+        );
+        assert_eq!(join(base, "../../../x.yip"), None);
+
+        let base = "https://yap.microdragon.rs/microdragon/rust-backend/0.0.0/index.yip";
+        assert_eq!(
+            join(base, "types.yip").as_deref(),
+            Some("https://yap.microdragon.rs/microdragon/rust-backend/0.0.0/types.yip")
+        );
+        assert_eq!(
+            join(base, "/index.yip").as_deref(),
+            Some("https://yap.microdragon.rs/index.yip")
+        );
+        assert_eq!(
+            join(base, "file:///a/./b.yip").as_deref(),
+            Some("file:///a/b.yip")
+        );
+    }
+}
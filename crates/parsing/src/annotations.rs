@@ -1,39 +1,111 @@
-use crate::{expression, utils, LexerToken, Parser};
+use crate::{expression, utils, ErrorCode, LexerToken, Parser};
 use ast::SyntaxKind;
 
+/// Parses the annotations and doc comments preceding an item, member or function.
 pub fn parse(p: &mut Parser) {
-    while p.at(LexerToken::AtSign) {
-        parse_annotation(p);
+    loop {
+        match p.current() {
+            LexerToken::AtSign => parse_annotation(p),
+            LexerToken::DocComment => p.bump(LexerToken::DocComment),
+            _ => break,
+        }
+
         utils::whitespace_newline(p);
     }
 }
 
+/// Looks past any annotations and comments at the current position and returns the token
+/// following them, without consuming anything.
+pub fn lookahead(p: &Parser) -> LexerToken {
+    let mut n = 0;
+
+    loop {
+        match p.nth(n) {
+            LexerToken::AtSign => n = skip_annotation(p, n),
+            LexerToken::Whitespace
+            | LexerToken::Newline
+            | LexerToken::Comment
+            | LexerToken::BlockComment
+            | LexerToken::DocComment => n += 1,
+            token => return token,
+        }
+    }
+}
+
+fn skip_annotation(p: &Parser, mut n: usize) -> usize {
+    n += 1;
+    while matches!(
+        p.nth(n),
+        LexerToken::Whitespace | LexerToken::Identifier | LexerToken::ColonColon
+    ) {
+        n += 1;
+    }
+
+    if p.nth(n) == LexerToken::LParentheses {
+        let mut depth = 0;
+        loop {
+            match p.nth(n) {
+                LexerToken::LParentheses => depth += 1,
+                LexerToken::RParentheses => depth -= 1,
+                LexerToken::EndOfFile => return n,
+                _ => {}
+            }
+
+            n += 1;
+            if depth == 0 {
+                break;
+            }
+        }
+    }
+
+    n
+}
+
 fn parse_annotation(p: &mut Parser) {
     let stub = p.start();
 
     p.bump(LexerToken::AtSign);
     p.eat(LexerToken::Whitespace);
-    p.expect(LexerToken::Identifier);
+    utils::path(p);
 
     if p.at(LexerToken::LParentheses)
         || (p.at(LexerToken::Whitespace) && p.peek_at(LexerToken::LParentheses))
     {
         p.eat(LexerToken::Whitespace);
         p.bump(LexerToken::LParentheses);
-        p.eat(LexerToken::Whitespace);
+        utils::list(
+            p,
+            LexerToken::RParentheses,
+            expression::EXPRESSION_START,
+            utils::LIST_RECOVERY,
+            ErrorCode::ExpectedExpression,
+            annotation_argument,
+        );
+    }
 
-        while !p.eat(LexerToken::RParentheses) {
-            expression::parse(p);
-            p.eat(LexerToken::Whitespace);
+    stub.complete(p, SyntaxKind::Annotation);
+}
 
-            if !p.at(LexerToken::RParentheses) {
-                p.expect(LexerToken::Comma);
-                p.eat(LexerToken::Whitespace);
-            }
-        }
+/// Parses a positional argument, or a named one of the form `name = value`.
+fn annotation_argument(p: &mut Parser) {
+    let named = p.at(LexerToken::Identifier)
+        && (p.peek_at(LexerToken::Equals)
+            || (p.peek_at(LexerToken::Whitespace) && p.nth(2) == LexerToken::Equals));
+
+    if !named {
+        expression::parse(p);
+        return;
     }
 
-    stub.complete(p, SyntaxKind::Annotation);
+    let stub = p.start();
+
+    p.bump(LexerToken::Identifier);
+    p.eat(LexerToken::Whitespace);
+    p.bump(LexerToken::Equals);
+    p.eat(LexerToken::Whitespace);
+    expression::parse(p);
+
+    stub.complete(p, SyntaxKind::AnnotationArgument);
 }
 
 #[cfg(test)]
@@ -94,4 +166,46 @@ mod test {
             finish
         );
     }
+
+    #[test]
+    fn test_parse_path() {
+        expect_events!(
+            "@rust::derive",
+            (start SyntaxKind::Annotation),
+            (token SyntaxKind::Punctuation),
+            (token SyntaxKind::Identifier),
+            (token SyntaxKind::Punctuation),
+            (token SyntaxKind::Identifier),
+            finish
+        );
+    }
+
+    #[test]
+    fn test_parse_named_arguments() {
+        expect_events!(
+            "@since(1, version = 2)",
+            (start SyntaxKind::Annotation),
+            (token SyntaxKind::Punctuation),
+            (token SyntaxKind::Identifier),
+            (token SyntaxKind::Punctuation),
+            stub,
+            (start SyntaxKind::Literal),
+            (token SyntaxKind::Integer),
+            finish,
+            (token SyntaxKind::Punctuation),
+            (token SyntaxKind::Whitespace),
+            (start SyntaxKind::AnnotationArgument),
+            (token SyntaxKind::Identifier),
+            (token SyntaxKind::Whitespace),
+            (token SyntaxKind::Punctuation),
+            (token SyntaxKind::Whitespace),
+            stub,
+            (start SyntaxKind::Literal),
+            (token SyntaxKind::Integer),
+            finish,
+            finish,
+            (token SyntaxKind::Punctuation),
+            finish
+        );
+    }
 }
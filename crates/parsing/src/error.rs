@@ -0,0 +1,126 @@
+use crate::lexer::{LexerToken, LexerTokenSet};
+use report::Report;
+use text_size::TextRange;
+
+/// Stable identifiers for the kinds of errors reported by the parser.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    /// The input contains characters that do not form any token.
+    UnknownToken,
+    /// A specific token was expected, but a different one was found.
+    UnexpectedToken,
+    /// The start of an item was expected.
+    ExpectedItem,
+    /// The start of a type was expected.
+    ExpectedType,
+    /// The start of an expression was expected.
+    ExpectedExpression,
+    /// A block comment is missing its closing `*/`.
+    UnterminatedBlockComment,
+}
+
+impl ErrorCode {
+    /// The code as shown to users. These never change once assigned.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::UnknownToken => "P0001",
+            ErrorCode::UnexpectedToken => "P0002",
+            ErrorCode::ExpectedItem => "P0003",
+            ErrorCode::ExpectedType => "P0004",
+            ErrorCode::ExpectedExpression => "P0005",
+            ErrorCode::UnterminatedBlockComment => "P0006",
+        }
+    }
+}
+
+/// An error encountered while parsing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// What kind of error this is.
+    pub code: ErrorCode,
+
+    /// Range in the input file that caused the error.
+    pub range: TextRange,
+
+    /// The tokens that would have been accepted at this position.
+    pub expected: LexerTokenSet,
+
+    /// The token that was actually found.
+    pub found: LexerToken,
+}
+
+impl ParseError {
+    /// A short description of what was expected, e.g. `Item` or `}`.
+    pub fn expected_str(&self) -> &'static str {
+        match self.code {
+            ErrorCode::UnknownToken => LexerToken::Error.expected_str(),
+            ErrorCode::ExpectedItem => "Item",
+            ErrorCode::ExpectedType => "Type",
+            ErrorCode::ExpectedExpression => "Expression",
+            ErrorCode::UnterminatedBlockComment => "*/",
+            ErrorCode::UnexpectedToken => {
+                let mut expected = self.expected.iter();
+                match (expected.next(), expected.next()) {
+                    (Some(token), None) => token.expected_str(),
+                    _ => "Token",
+                }
+            }
+        }
+    }
+
+    /// Converts the error into a [`Report`] that can be shown to users.
+    pub fn report(&self) -> Report {
+        let found = describe(self.found.expected_str());
+
+        let (message, label) = match self.code {
+            ErrorCode::UnknownToken => (
+                "unknown token".to_string(),
+                "not part of any token".to_string(),
+            ),
+            ErrorCode::UnterminatedBlockComment => {
+                return Report::error(self.code.as_str(), "unterminated block comment")
+                    .with_label(self.range, "comment starts here")
+                    .with_note("block comments end with `*/`");
+            }
+            ErrorCode::ExpectedItem => (
+                format!("expected an item, found {}", found),
+                "expected an item".to_string(),
+            ),
+            ErrorCode::ExpectedType => (
+                format!("expected a type, found {}", found),
+                "expected a type".to_string(),
+            ),
+            ErrorCode::ExpectedExpression => (
+                format!("expected an expression, found {}", found),
+                "expected an expression".to_string(),
+            ),
+            ErrorCode::UnexpectedToken => {
+                let expected: Vec<_> = self
+                    .expected
+                    .iter()
+                    .map(|x| describe(x.expected_str()))
+                    .collect();
+                let expected = match expected.as_slice() {
+                    [] => "a different token".to_string(),
+                    [token] => token.clone(),
+                    [tokens @ .., last] => format!("one of {} or {}", tokens.join(", "), last),
+                };
+                (
+                    format!("expected {}, found {}", expected, found),
+                    format!("expected {}", expected),
+                )
+            }
+        };
+
+        Report::error(self.code.as_str(), message).with_label(self.range, label)
+    }
+}
+
+/// Quotes tokens with a fixed spelling, and lowercases descriptions like `Identifier`.
+fn describe(token: &str) -> String {
+    if token.starts_with(char::is_uppercase) {
+        token.to_lowercase()
+    } else {
+        format!("`{}`", token)
+    }
+}
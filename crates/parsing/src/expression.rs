@@ -1,7 +1,37 @@
+use crate::error::ErrorCode;
 use crate::lexer::{LexerToken, LexerTokenSet};
 use crate::{utils, NodeStub, Parser};
 use ast::SyntaxKind;
 
+pub(crate) const EXPRESSION_START: LexerTokenSet = LexerTokenSet::new(&[
+    LexerToken::KeywordIf,
+    LexerToken::Plus,
+    LexerToken::Minus,
+    LexerToken::Bang,
+    LexerToken::KeywordError,
+    LexerToken::KeywordTrue,
+    LexerToken::KeywordFalse,
+    LexerToken::KeywordNone,
+    LexerToken::LParentheses,
+    LexerToken::LBrackets,
+    LexerToken::Integer,
+    LexerToken::Float,
+    LexerToken::String,
+    LexerToken::Identifier,
+]);
+
+/// Tokens that can follow an expression, left for the enclosing rule when an expression is
+/// missing.
+const EXPRESSION_RECOVERY: LexerTokenSet = utils::LIST_RECOVERY.union(LexerTokenSet::new(&[
+    LexerToken::Whitespace,
+    LexerToken::Newline,
+    LexerToken::Comma,
+    LexerToken::RParentheses,
+    LexerToken::RBrackets,
+    LexerToken::KeywordThen,
+    LexerToken::KeywordElse,
+]));
+
 const OPERATORS: LexerTokenSet = LexerTokenSet::new(&[
     LexerToken::QuestionQuestion,
     LexerToken::Ampersand,
@@ -123,7 +153,7 @@ fn unary_operator(p: &mut Parser) {
         LexerToken::Plus | LexerToken::Minus | LexerToken::Bang => {
             p.do_bump(current.into());
             p.eat(LexerToken::Whitespace);
-            literal(p, None);
+            unary_operator(p);
             stub.complete(p, SyntaxKind::UnaryOperator);
         }
         LexerToken::KeywordError => {
@@ -142,46 +172,49 @@ fn unary_operator(p: &mut Parser) {
         }
         LexerToken::LParentheses => {
             p.do_bump(current.into());
+            let shape = utils::list(
+                p,
+                LexerToken::RParentheses,
+                EXPRESSION_START,
+                utils::LIST_RECOVERY,
+                ErrorCode::ExpectedExpression,
+                parse,
+            );
 
-            p.eat(LexerToken::Whitespace);
-            while !p.eat(LexerToken::RParentheses) {
-                parse(p);
-                p.eat(LexerToken::Whitespace);
-                if !p.at(LexerToken::RParentheses) {
-                    p.expect(LexerToken::Comma);
-                    p.eat(LexerToken::Whitespace);
-                }
+            // A single expression without a comma is a group, `(a,)` is a tuple
+            if shape.elements == 1 && shape.commas == 0 {
+                stub.complete(p, SyntaxKind::GroupExpression);
+            } else {
+                stub.complete(p, SyntaxKind::TupleExpression);
             }
-
-            stub.complete(p, SyntaxKind::TupleExpression);
         }
         LexerToken::LBrackets => {
             p.do_bump(current.into());
-
-            p.eat(LexerToken::Whitespace);
-            while !p.eat(LexerToken::RBrackets) {
-                parse(p);
-                p.eat(LexerToken::Whitespace);
-                if !p.at(LexerToken::RBrackets) {
-                    p.expect(LexerToken::Comma);
-                    p.eat(LexerToken::Whitespace);
-                }
-            }
+            utils::list(
+                p,
+                LexerToken::RBrackets,
+                EXPRESSION_START,
+                utils::LIST_RECOVERY,
+                ErrorCode::ExpectedExpression,
+                parse,
+            );
 
             stub.complete(p, SyntaxKind::ListExpression);
         }
-        _ => literal(p, Some(stub)),
+        _ => literal(p, stub),
     }
 }
 
-fn literal(p: &mut Parser, stub: Option<NodeStub>) {
-    let stub = stub.unwrap_or_else(|| p.start());
-
+fn literal(p: &mut Parser, stub: NodeStub) {
     let current = p.current();
     match current {
         LexerToken::Integer | LexerToken::Float | LexerToken::String => p.do_bump(current.into()),
         LexerToken::Identifier => utils::path(p),
-        _ => p.error("Expression"),
+        _ => p.err_recover(
+            ErrorCode::ExpectedExpression,
+            EXPRESSION_START,
+            EXPRESSION_RECOVERY,
+        ),
     }
 
     stub.complete(p, SyntaxKind::Literal);
@@ -265,4 +298,71 @@ mod test {
             finish
         );
     }
+
+    #[test]
+    fn test_unary_operator() {
+        use super::unary_operator as parse;
+
+        expect_events!(
+            "-(1 + 2)",
+            (start SyntaxKind::UnaryOperator),
+            (token SyntaxKind::Punctuation),
+            (start SyntaxKind::GroupExpression),
+            (token SyntaxKind::Punctuation),
+            (start SyntaxKind::InfixOperator, 12),
+            (start SyntaxKind::Literal),
+            (token SyntaxKind::Integer),
+            finish,
+            (token SyntaxKind::Whitespace),
+            (token SyntaxKind::Punctuation),
+            (token SyntaxKind::Whitespace),
+            stub,
+            (start SyntaxKind::Literal),
+            (token SyntaxKind::Integer),
+            finish,
+            finish,
+            (token SyntaxKind::Punctuation),
+            finish,
+            finish
+        );
+
+        expect_events!(
+            "!!a",
+            (start SyntaxKind::UnaryOperator),
+            (token SyntaxKind::Punctuation),
+            (start SyntaxKind::UnaryOperator),
+            (token SyntaxKind::Punctuation),
+            (start SyntaxKind::Literal),
+            (token SyntaxKind::Identifier),
+            finish,
+            finish,
+            finish
+        );
+    }
+
+    #[test]
+    fn test_tuple_expression() {
+        use super::unary_operator as parse;
+
+        expect_events!(
+            "(1,)",
+            (start SyntaxKind::TupleExpression),
+            (token SyntaxKind::Punctuation),
+            stub,
+            (start SyntaxKind::Literal),
+            (token SyntaxKind::Integer),
+            finish,
+            (token SyntaxKind::Punctuation),
+            (token SyntaxKind::Punctuation),
+            finish
+        );
+
+        expect_events!(
+            "()",
+            (start SyntaxKind::TupleExpression),
+            (token SyntaxKind::Punctuation),
+            (token SyntaxKind::Punctuation),
+            finish
+        );
+    }
 }
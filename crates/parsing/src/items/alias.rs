@@ -7,7 +7,7 @@ pub fn parse(p: &mut Parser) {
 
     annotations::parse(p);
 
-    p.bump(LexerToken::KeywordType);
+    p.expect(LexerToken::KeywordType);
     p.expect(LexerToken::Whitespace);
 
     p.expect(LexerToken::Identifier);
@@ -44,7 +44,7 @@ mod test {
         );
 
         expect_events!(
-            "type Test = list bool",
+            "type Test = [bool]",
             (start SyntaxKind::AliasDefinition),
             (token SyntaxKind::Keyword),
             (token SyntaxKind::Whitespace),
@@ -53,11 +53,11 @@ mod test {
             (token SyntaxKind::Punctuation),
             (token SyntaxKind::Whitespace),
             (start SyntaxKind::ListType),
-            (token SyntaxKind::Keyword),
-            (token SyntaxKind::Whitespace),
+            (token SyntaxKind::Punctuation),
             (start SyntaxKind::NamedType),
             (token SyntaxKind::Identifier),
             finish,
+            (token SyntaxKind::Punctuation),
             finish,
             finish
         );
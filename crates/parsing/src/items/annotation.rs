@@ -0,0 +1,158 @@
+use super::ITEM_KEYWORDS;
+use crate::error::ErrorCode;
+use crate::lexer::{LexerToken, LexerTokenSet};
+use crate::{annotations, types, utils, Parser};
+use ast::SyntaxKind;
+
+const PARAMETER_START: LexerTokenSet = LexerTokenSet::new(&[LexerToken::Identifier]);
+
+/// A parameter list missing its `)` stops at the targets of the annotation.
+const PARAMETER_RECOVERY: LexerTokenSet =
+    utils::LIST_RECOVERY.union(LexerTokenSet::new(&[LexerToken::KeywordOn]));
+
+/// Targets are item keywords, `fn`, or identifiers naming members like `field`.
+const TARGET_START: LexerTokenSet = ITEM_KEYWORDS.union(LexerTokenSet::new(&[
+    LexerToken::KeywordFn,
+    LexerToken::Identifier,
+]));
+
+const TARGET_RECOVERY: LexerTokenSet =
+    LexerTokenSet::new(&[LexerToken::Newline, LexerToken::Comma, LexerToken::RBraces]);
+
+pub fn parse(p: &mut Parser) {
+    let stub = p.start();
+
+    annotations::parse(p);
+
+    p.expect(LexerToken::KeywordAnnotation);
+    p.expect(LexerToken::Whitespace);
+
+    p.expect(LexerToken::Identifier);
+
+    if p.at(LexerToken::LParentheses)
+        || (p.at(LexerToken::Whitespace) && p.peek_at(LexerToken::LParentheses))
+    {
+        p.eat(LexerToken::Whitespace);
+        p.bump(LexerToken::LParentheses);
+        utils::list(
+            p,
+            LexerToken::RParentheses,
+            PARAMETER_START,
+            PARAMETER_RECOVERY,
+            ErrorCode::UnexpectedToken,
+            annotation_parameter,
+        );
+    }
+
+    if p.at(LexerToken::KeywordOn)
+        || (p.at(LexerToken::Whitespace) && p.peek_at(LexerToken::KeywordOn))
+    {
+        p.eat(LexerToken::Whitespace);
+        p.bump(LexerToken::KeywordOn);
+        p.expect(LexerToken::Whitespace);
+
+        annotation_target(p);
+        while p.at(LexerToken::Comma)
+            || (p.at(LexerToken::Whitespace) && p.peek_at(LexerToken::Comma))
+        {
+            p.eat(LexerToken::Whitespace);
+            p.bump(LexerToken::Comma);
+            p.eat(LexerToken::Whitespace);
+            annotation_target(p);
+        }
+    }
+
+    stub.complete(p, SyntaxKind::AnnotationDefinition);
+}
+
+fn annotation_parameter(p: &mut Parser) {
+    let stub = p.start();
+
+    p.expect(LexerToken::Identifier);
+    p.eat(LexerToken::Whitespace);
+
+    p.expect(LexerToken::Colon);
+    p.eat(LexerToken::Whitespace);
+
+    types::parse(p);
+
+    stub.complete(p, SyntaxKind::AnnotationParameter);
+}
+
+fn annotation_target(p: &mut Parser) {
+    if !p.at_set(TARGET_START) {
+        p.err_recover(ErrorCode::UnexpectedToken, TARGET_START, TARGET_RECOVERY);
+        return;
+    }
+
+    let stub = p.start();
+    p.bump_any();
+    stub.complete(p, SyntaxKind::AnnotationTarget);
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse;
+    use crate::testing::expect_events;
+    use ast::SyntaxKind;
+
+    #[test]
+    fn test_parse() {
+        expect_events!(
+            "annotation inline",
+            (start SyntaxKind::AnnotationDefinition),
+            (token SyntaxKind::Keyword),
+            (token SyntaxKind::Whitespace),
+            (token SyntaxKind::Identifier),
+            finish
+        );
+
+        expect_events!(
+            "annotation deprecated(reason: string) on struct, fn",
+            (start SyntaxKind::AnnotationDefinition),
+            (token SyntaxKind::Keyword),
+            (token SyntaxKind::Whitespace),
+            (token SyntaxKind::Identifier),
+            (token SyntaxKind::Punctuation),
+            (start SyntaxKind::AnnotationParameter),
+            (token SyntaxKind::Identifier),
+            (token SyntaxKind::Punctuation),
+            (token SyntaxKind::Whitespace),
+            (start SyntaxKind::NamedType),
+            (token SyntaxKind::Identifier),
+            finish,
+            finish,
+            (token SyntaxKind::Punctuation),
+            (token SyntaxKind::Whitespace),
+            (token SyntaxKind::Keyword),
+            (token SyntaxKind::Whitespace),
+            (start SyntaxKind::AnnotationTarget),
+            (token SyntaxKind::Keyword),
+            finish,
+            (token SyntaxKind::Punctuation),
+            (token SyntaxKind::Whitespace),
+            (start SyntaxKind::AnnotationTarget),
+            (token SyntaxKind::Keyword),
+            finish,
+            finish
+        );
+    }
+
+    #[test]
+    fn test_parse_member_target() {
+        expect_events!(
+            "annotation id on field",
+            (start SyntaxKind::AnnotationDefinition),
+            (token SyntaxKind::Keyword),
+            (token SyntaxKind::Whitespace),
+            (token SyntaxKind::Identifier),
+            (token SyntaxKind::Whitespace),
+            (token SyntaxKind::Keyword),
+            (token SyntaxKind::Whitespace),
+            (start SyntaxKind::AnnotationTarget),
+            (token SyntaxKind::Identifier),
+            finish,
+            finish
+        );
+    }
+}
@@ -7,7 +7,7 @@ pub fn parse(p: &mut Parser) {
 
     annotations::parse(p);
 
-    p.bump(LexerToken::KeywordConst);
+    p.expect(LexerToken::KeywordConst);
     p.expect(LexerToken::Whitespace);
 
     p.expect(LexerToken::Identifier);
@@ -74,7 +74,7 @@ mod test {
         );
 
         expect_events!(
-            "const TEST: list bool = []",
+            "const TEST: [bool] = []",
             (start SyntaxKind::ConstDefinition),
             (token SyntaxKind::Keyword),
             (token SyntaxKind::Whitespace),
@@ -82,11 +82,11 @@ mod test {
             (token SyntaxKind::Punctuation),
             (token SyntaxKind::Whitespace),
             (start SyntaxKind::ListType),
-            (token SyntaxKind::Keyword),
-            (token SyntaxKind::Whitespace),
+            (token SyntaxKind::Punctuation),
             (start SyntaxKind::NamedType),
             (token SyntaxKind::Identifier),
             finish,
+            (token SyntaxKind::Punctuation),
             finish,
             (token SyntaxKind::Whitespace),
             (token SyntaxKind::Punctuation),
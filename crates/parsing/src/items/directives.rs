@@ -7,7 +7,7 @@ pub fn plugin_directive(p: &mut Parser) {
 
     annotations::parse(p);
 
-    p.bump(LexerToken::KeywordPlugin);
+    p.expect(LexerToken::KeywordPlugin);
     p.expect(LexerToken::Whitespace);
 
     p.expect(LexerToken::String);
@@ -20,7 +20,7 @@ pub fn import_directive(p: &mut Parser) {
 
     annotations::parse(p);
 
-    p.bump(LexerToken::KeywordImport);
+    p.expect(LexerToken::KeywordImport);
     p.expect(LexerToken::Whitespace);
 
     p.expect(LexerToken::String);
@@ -1,25 +1,40 @@
-use crate::lexer::LexerToken;
-use crate::{annotations, expression, utils, Parser};
+use super::ITEM_KEYWORDS;
+use crate::error::ErrorCode;
+use crate::lexer::{LexerToken, LexerTokenSet};
+use crate::{annotations, expression, types, utils, Parser};
 use ast::SyntaxKind;
 
+const MEMBER_START: LexerTokenSet = LexerTokenSet::new(&[
+    LexerToken::Identifier,
+    LexerToken::AtSign,
+    LexerToken::DocComment,
+]);
+
 pub fn parse(p: &mut Parser) {
     let stub = p.start();
 
     annotations::parse(p);
 
-    p.bump(LexerToken::KeywordEnum);
+    p.expect(LexerToken::KeywordEnum);
     p.expect(LexerToken::Whitespace);
 
     p.expect(LexerToken::Identifier);
-    utils::whitespace_newline(p);
+    p.eat(LexerToken::Whitespace);
 
-    p.expect(LexerToken::LBraces);
+    // The integer type the discriminants are stored as
+    if p.eat(LexerToken::Colon) {
+        p.eat(LexerToken::Whitespace);
+        types::parse(p);
+    }
     utils::whitespace_newline(p);
 
-    while !p.eat(LexerToken::RBraces) {
-        enum_member(p);
-        utils::whitespace_newline(p);
-    }
+    utils::block(
+        p,
+        MEMBER_START,
+        ITEM_KEYWORDS,
+        ErrorCode::UnexpectedToken,
+        enum_member,
+    );
 
     stub.complete(p, SyntaxKind::EnumDefinition);
 }
@@ -95,4 +110,24 @@ mod test {
             finish
         );
     }
+
+    #[test]
+    fn test_parse_representation() {
+        expect_events!(
+            "enum Test: u8 {}",
+            (start SyntaxKind::EnumDefinition),
+            (token SyntaxKind::Keyword),
+            (token SyntaxKind::Whitespace),
+            (token SyntaxKind::Identifier),
+            (token SyntaxKind::Punctuation),
+            (token SyntaxKind::Whitespace),
+            (start SyntaxKind::NamedType),
+            (token SyntaxKind::Identifier),
+            finish,
+            (token SyntaxKind::Whitespace),
+            (token SyntaxKind::Punctuation),
+            (token SyntaxKind::Punctuation),
+            finish
+        );
+    }
 }
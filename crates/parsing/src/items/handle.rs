@@ -0,0 +1,49 @@
+use crate::lexer::LexerToken;
+use crate::{annotations, Parser};
+use ast::SyntaxKind;
+
+pub fn parse(p: &mut Parser) {
+    let stub = p.start();
+
+    annotations::parse(p);
+
+    p.expect(LexerToken::KeywordHandle);
+    p.expect(LexerToken::Whitespace);
+
+    p.expect(LexerToken::Identifier);
+
+    stub.complete(p, SyntaxKind::HandleDefinition);
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse;
+    use crate::testing::expect_events;
+    use ast::SyntaxKind;
+
+    #[test]
+    fn test_parse() {
+        expect_events!(
+            "handle Test",
+            (start SyntaxKind::HandleDefinition),
+            (token SyntaxKind::Keyword),
+            (token SyntaxKind::Whitespace),
+            (token SyntaxKind::Identifier),
+            finish
+        );
+
+        expect_events!(
+            "@Kernel handle Test",
+            (start SyntaxKind::HandleDefinition),
+            (start SyntaxKind::Annotation),
+            (token SyntaxKind::Punctuation),
+            (token SyntaxKind::Identifier),
+            finish,
+            (token SyntaxKind::Whitespace),
+            (token SyntaxKind::Keyword),
+            (token SyntaxKind::Whitespace),
+            (token SyntaxKind::Identifier),
+            finish
+        );
+    }
+}
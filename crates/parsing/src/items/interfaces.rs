@@ -1,25 +1,43 @@
-use crate::lexer::LexerToken;
+use super::ITEM_KEYWORDS;
+use crate::error::ErrorCode;
+use crate::lexer::{LexerToken, LexerTokenSet};
 use crate::{annotations, types, utils, Parser};
 use ast::SyntaxKind;
 
+const FUNCTION_START: LexerTokenSet = LexerTokenSet::new(&[
+    LexerToken::KeywordFn,
+    LexerToken::AtSign,
+    LexerToken::DocComment,
+]);
+
+const PARAMETER_START: LexerTokenSet = LexerTokenSet::new(&[
+    LexerToken::Identifier,
+    LexerToken::AtSign,
+    LexerToken::DocComment,
+]);
+
+/// A parameter list missing its `)` stops at the return type or ID of the function.
+const PARAMETER_RECOVERY: LexerTokenSet =
+    utils::LIST_RECOVERY.union(LexerTokenSet::new(&[LexerToken::Colon, LexerToken::Equals]));
+
 pub fn parse(p: &mut Parser) {
     let stub = p.start();
 
     annotations::parse(p);
 
-    p.bump(LexerToken::KeywordInterface);
+    p.expect(LexerToken::KeywordInterface);
     p.expect(LexerToken::Whitespace);
 
     p.expect(LexerToken::Identifier);
     utils::whitespace_newline(p);
 
-    p.expect(LexerToken::LBraces);
-    utils::whitespace_newline(p);
-
-    while !p.eat(LexerToken::RBraces) {
-        interface_function(p);
-        utils::whitespace_newline(p);
-    }
+    utils::block(
+        p,
+        FUNCTION_START,
+        ITEM_KEYWORDS,
+        ErrorCode::UnexpectedToken,
+        interface_function,
+    );
 
     stub.complete(p, SyntaxKind::InterfaceDefinition);
 }
@@ -35,17 +53,15 @@ fn interface_function(p: &mut Parser) {
     p.expect(LexerToken::Identifier);
     p.eat(LexerToken::Whitespace);
 
-    p.expect(LexerToken::LParentheses);
-    p.eat(LexerToken::Whitespace);
-
-    while !p.eat(LexerToken::RParentheses) {
-        interface_function_parameter(p);
-        p.eat(LexerToken::Whitespace);
-
-        if !p.at(LexerToken::RParentheses) {
-            p.expect(LexerToken::Comma);
-            p.eat(LexerToken::Whitespace);
-        }
+    if p.expect(LexerToken::LParentheses) {
+        utils::list(
+            p,
+            LexerToken::RParentheses,
+            PARAMETER_START,
+            PARAMETER_RECOVERY,
+            ErrorCode::UnexpectedToken,
+            interface_function_parameter,
+        );
     }
 
     if p.at(LexerToken::Colon) || (p.at(LexerToken::Whitespace) && p.peek_at(LexerToken::Colon)) {
@@ -1,18 +1,45 @@
 pub mod alias;
+pub mod annotation;
 pub mod constants;
 pub mod directives;
 pub mod enums;
+pub mod handle;
 pub mod interfaces;
 pub mod structure;
 pub mod unions;
 pub mod uses;
 
-use crate::lexer::LexerToken;
-use crate::{utils, Parser, annotations};
+use crate::error::ErrorCode;
+use crate::lexer::{LexerToken, LexerTokenSet};
+use crate::{annotations, utils, Parser};
 use ast::SyntaxKind;
 
+/// Keywords starting an item. Blocks missing their closing `}` stop at these.
+pub(crate) const ITEM_KEYWORDS: LexerTokenSet = LexerTokenSet::new(&[
+    LexerToken::KeywordImport,
+    LexerToken::KeywordPlugin,
+    LexerToken::KeywordStruct,
+    LexerToken::KeywordUnion,
+    LexerToken::KeywordEnum,
+    LexerToken::KeywordType,
+    LexerToken::KeywordInterface,
+    LexerToken::KeywordHandle,
+    LexerToken::KeywordAnnotation,
+    LexerToken::KeywordConst,
+    LexerToken::KeywordMod,
+    LexerToken::KeywordUse,
+]);
+
+const ITEM_START: LexerTokenSet = ITEM_KEYWORDS.union(LexerTokenSet::new(&[
+    LexerToken::AtSign,
+    LexerToken::DocComment,
+]));
+
+const MODULE_MEMBER_START: LexerTokenSet =
+    ITEM_START.union(LexerTokenSet::new(&[LexerToken::InnerDocComment]));
+
 pub fn parse(p: &mut Parser) {
-    match p.current() {
+    match annotations::lookahead(p) {
         LexerToken::KeywordImport => directives::import_directive(p),
         LexerToken::KeywordPlugin => directives::plugin_directive(p),
         LexerToken::KeywordStruct => structure::parse(p),
@@ -20,10 +47,15 @@ pub fn parse(p: &mut Parser) {
         LexerToken::KeywordEnum => enums::parse(p),
         LexerToken::KeywordType => alias::parse(p),
         LexerToken::KeywordInterface => interfaces::parse(p),
+        LexerToken::KeywordHandle => handle::parse(p),
+        LexerToken::KeywordAnnotation => annotation::parse(p),
         LexerToken::KeywordConst => constants::parse(p),
         LexerToken::KeywordMod => parse_module(p),
         LexerToken::KeywordUse => uses::parse(p),
-        _ => p.error("Item"),
+        _ => {
+            let stop = MODULE_MEMBER_START.union(LexerTokenSet::new(&[LexerToken::Newline]));
+            p.err_and_skip(ErrorCode::ExpectedItem, ITEM_START, stop)
+        }
     }
 }
 
@@ -33,7 +65,7 @@ pub fn parse_file(p: &mut Parser) {
     utils::whitespace_newline(p);
 
     while !p.at(LexerToken::EndOfFile) {
-        parse(p);
+        parse_item_or_inner_doc_comment(p);
         utils::whitespace_newline(p);
     }
 
@@ -45,21 +77,28 @@ fn parse_module(p: &mut Parser) {
 
     annotations::parse(p);
 
-    p.bump(LexerToken::KeywordMod);
+    p.expect(LexerToken::KeywordMod);
     p.expect(LexerToken::Whitespace);
 
     p.expect(LexerToken::Identifier);
     utils::whitespace_newline(p);
 
-    p.expect(LexerToken::LBraces);
-    utils::whitespace_newline(p);
+    utils::block(
+        p,
+        MODULE_MEMBER_START,
+        LexerTokenSet::EMPTY,
+        ErrorCode::ExpectedItem,
+        parse_item_or_inner_doc_comment,
+    );
 
-    while !p.eat(LexerToken::RBraces) {
+    stub.complete(p, SyntaxKind::ModuleDefinition);
+}
+
+/// Inner doc comments (`//!`) document the file or module they are placed in.
+fn parse_item_or_inner_doc_comment(p: &mut Parser) {
+    if !p.eat(LexerToken::InnerDocComment) {
         parse(p);
-        utils::whitespace_newline(p);
     }
-
-    stub.complete(p, SyntaxKind::ModuleDefinition);
 }
 
 #[cfg(test)]
@@ -182,4 +221,85 @@ mod test {
             finish
         );
     }
+
+    #[test]
+    fn test_parse_annotated() {
+        use super::parse;
+
+        expect_events!(
+            "@Kernel(1)
+            handle Yap",
+            (start SyntaxKind::HandleDefinition),
+            (start SyntaxKind::Annotation),
+            (token SyntaxKind::Punctuation),
+            (token SyntaxKind::Identifier),
+            (token SyntaxKind::Punctuation),
+            stub,
+            (start SyntaxKind::Literal),
+            (token SyntaxKind::Integer),
+            finish,
+            (token SyntaxKind::Punctuation),
+            finish,
+            (token SyntaxKind::Newline),
+            (token SyntaxKind::Whitespace),
+            (token SyntaxKind::Keyword),
+            (token SyntaxKind::Whitespace),
+            (token SyntaxKind::Identifier),
+            finish
+        );
+    }
+
+    #[test]
+    fn test_parse_comments() {
+        use super::parse_file as parse;
+
+        expect_events!(
+            "//! File docs
+            // Just a comment
+            /// Handle docs
+            handle Yap // trailing",
+            (start SyntaxKind::File),
+            (token SyntaxKind::DocComment),
+            (token SyntaxKind::Newline),
+            (token SyntaxKind::Whitespace),
+            (token SyntaxKind::Comment),
+            (token SyntaxKind::Newline),
+            (token SyntaxKind::Whitespace),
+            (start SyntaxKind::HandleDefinition),
+            (token SyntaxKind::DocComment),
+            (token SyntaxKind::Newline),
+            (token SyntaxKind::Whitespace),
+            (token SyntaxKind::Keyword),
+            (token SyntaxKind::Whitespace),
+            (token SyntaxKind::Identifier),
+            finish,
+            (token SyntaxKind::Whitespace),
+            (token SyntaxKind::Comment),
+            finish
+        );
+    }
+
+    #[test]
+    fn test_parse_unknown_token() {
+        use super::parse_file as parse;
+
+        expect_events!(
+            "$ enum Test {#}",
+            (start SyntaxKind::File),
+            (error "Unknown Token"),
+            (token SyntaxKind::Error),
+            (token SyntaxKind::Whitespace),
+            (start SyntaxKind::EnumDefinition),
+            (token SyntaxKind::Keyword),
+            (token SyntaxKind::Whitespace),
+            (token SyntaxKind::Identifier),
+            (token SyntaxKind::Whitespace),
+            (token SyntaxKind::Punctuation),
+            (error "Unknown Token"),
+            (token SyntaxKind::Error),
+            (token SyntaxKind::Punctuation),
+            finish,
+            finish
+        );
+    }
 }
@@ -1,13 +1,21 @@
-use crate::lexer::LexerToken;
+use super::ITEM_KEYWORDS;
+use crate::error::ErrorCode;
+use crate::lexer::{LexerToken, LexerTokenSet};
 use crate::{types, utils, Parser, annotations};
 use ast::SyntaxKind;
 
+const MEMBER_START: LexerTokenSet = LexerTokenSet::new(&[
+    LexerToken::Identifier,
+    LexerToken::AtSign,
+    LexerToken::DocComment,
+]);
+
 pub fn parse(p: &mut Parser) {
     let stub = p.start();
 
     annotations::parse(p);
 
-    p.bump(LexerToken::KeywordStruct);
+    p.expect(LexerToken::KeywordStruct);
     p.expect(LexerToken::Whitespace);
 
     p.expect(LexerToken::Identifier);
@@ -19,13 +27,13 @@ pub fn parse(p: &mut Parser) {
 }
 
 pub(crate) fn struct_members(p: &mut Parser) {
-    p.expect(LexerToken::LBraces);
-    utils::whitespace_newline(p);
-
-    while !p.eat(LexerToken::RBraces) {
-        struct_member(p);
-        utils::whitespace_newline(p);
-    }
+    utils::block(
+        p,
+        MEMBER_START,
+        ITEM_KEYWORDS,
+        ErrorCode::UnexpectedToken,
+        struct_member,
+    );
 }
 
 fn struct_member(p: &mut Parser) {
@@ -101,4 +109,74 @@ mod test {
             finish
         );
     }
+
+    #[test]
+    fn test_parse_doc_comments() {
+        expect_events!(
+            "/// Docs
+            struct Test {
+                /// Member docs
+                a: bool
+            }",
+            (start SyntaxKind::StructDefinition),
+            (token SyntaxKind::DocComment),
+            (token SyntaxKind::Newline),
+            (token SyntaxKind::Whitespace),
+            (token SyntaxKind::Keyword),
+            (token SyntaxKind::Whitespace),
+            (token SyntaxKind::Identifier),
+            (token SyntaxKind::Whitespace),
+            (token SyntaxKind::Punctuation),
+            (token SyntaxKind::Newline),
+            (token SyntaxKind::Whitespace),
+            (start SyntaxKind::StructMember),
+            (token SyntaxKind::DocComment),
+            (token SyntaxKind::Newline),
+            (token SyntaxKind::Whitespace),
+            (token SyntaxKind::Identifier),
+            (token SyntaxKind::Punctuation),
+            (token SyntaxKind::Whitespace),
+            (start SyntaxKind::NamedType),
+            (token SyntaxKind::Identifier),
+            finish,
+            finish,
+            (token SyntaxKind::Newline),
+            (token SyntaxKind::Whitespace),
+            (token SyntaxKind::Punctuation),
+            finish
+        );
+    }
+
+    #[test]
+    fn test_parse_recovery() {
+        expect_events!(
+            "struct Test { 5 }",
+            (start SyntaxKind::StructDefinition),
+            (token SyntaxKind::Keyword),
+            (token SyntaxKind::Whitespace),
+            (token SyntaxKind::Identifier),
+            (token SyntaxKind::Whitespace),
+            (token SyntaxKind::Punctuation),
+            (token SyntaxKind::Whitespace),
+            (error "Token"),
+            (start SyntaxKind::Error),
+            (token SyntaxKind::Integer),
+            finish,
+            (token SyntaxKind::Whitespace),
+            (token SyntaxKind::Punctuation),
+            finish
+        );
+
+        expect_events!(
+            "struct Test {",
+            (start SyntaxKind::StructDefinition),
+            (token SyntaxKind::Keyword),
+            (token SyntaxKind::Whitespace),
+            (token SyntaxKind::Identifier),
+            (token SyntaxKind::Whitespace),
+            (token SyntaxKind::Punctuation),
+            (error "}"),
+            finish
+        );
+    }
 }
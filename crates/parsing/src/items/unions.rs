@@ -1,26 +1,33 @@
-use super::structure;
-use crate::lexer::LexerToken;
+use super::{structure, ITEM_KEYWORDS};
+use crate::error::ErrorCode;
+use crate::lexer::{LexerToken, LexerTokenSet};
 use crate::{annotations, types, utils, NodeStub, Parser};
 use ast::SyntaxKind;
 
+const MEMBER_START: LexerTokenSet = LexerTokenSet::new(&[
+    LexerToken::Identifier,
+    LexerToken::AtSign,
+    LexerToken::DocComment,
+]);
+
 pub fn parse(p: &mut Parser) {
     let stub = p.start();
 
     annotations::parse(p);
 
-    p.bump(LexerToken::KeywordUnion);
+    p.expect(LexerToken::KeywordUnion);
     p.expect(LexerToken::Whitespace);
 
     p.expect(LexerToken::Identifier);
     utils::whitespace_newline(p);
 
-    p.expect(LexerToken::LBraces);
-    utils::whitespace_newline(p);
-
-    while !p.eat(LexerToken::RBraces) {
-        union_member_or_anonymous_struct(p);
-        utils::whitespace_newline(p);
-    }
+    utils::block(
+        p,
+        MEMBER_START,
+        ITEM_KEYWORDS,
+        ErrorCode::UnexpectedToken,
+        union_member_or_anonymous_struct,
+    );
 
     stub.complete(p, SyntaxKind::UnionDefinition);
 }
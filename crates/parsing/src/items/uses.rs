@@ -1,32 +1,30 @@
-use crate::lexer::LexerToken;
+use crate::error::ErrorCode;
+use crate::lexer::{LexerToken, LexerTokenSet};
 use crate::{utils, Parser, annotations};
 use ast::SyntaxKind;
 
+const IDENTIFIER: LexerTokenSet = LexerTokenSet::new(&[LexerToken::Identifier]);
+
 pub fn parse(p: &mut Parser) {
     let stub = p.start();
 
     annotations::parse(p);
 
-    p.bump(LexerToken::KeywordUse);
+    p.expect(LexerToken::KeywordUse);
     p.expect(LexerToken::Whitespace);
 
     loop {
         p.eat(LexerToken::Whitespace);
 
         if p.eat(LexerToken::LBraces) {
-            while !p.at(LexerToken::RBraces) {
-                utils::whitespace_newline(p);
-                p.expect(LexerToken::Identifier);
-
-                utils::whitespace_newline(p);
-                if !p.at(LexerToken::RBraces) {
-                    p.expect(LexerToken::Comma);
-                    utils::whitespace_newline(p);
-                }
-            }
-
-            p.eat(LexerToken::Whitespace);
-            p.bump(LexerToken::RBraces);
+            utils::list(
+                p,
+                LexerToken::RBraces,
+                IDENTIFIER,
+                utils::LIST_RECOVERY,
+                ErrorCode::UnexpectedToken,
+                |p| p.bump(LexerToken::Identifier),
+            );
             break;
         }
 
@@ -1,20 +1,33 @@
 use ast::SyntaxKind;
-use logos::Logos;
+use logos::{Lexer, Logos};
 
-#[derive(Logos, Debug, Clone, Copy, PartialEq, Eq)]
-#[logos(skip r"//[^\n]*\n?")]
+#[derive(Logos, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
 pub enum LexerToken {
     EndOfFile,
+    Error,
     #[regex(r"[ \t]+")]
     Whitespace,
     #[regex(r"\n")]
     Newline,
+    #[regex(r"//[^\n]*")]
+    Comment,
+    #[regex(r"///([^/\n][^\n]*)?")]
+    DocComment,
+    #[regex(r"//![^\n]*")]
+    InnerDocComment,
+    #[token("/*", block_comment)]
+    BlockComment,
+    UnterminatedBlockComment,
     #[regex(r"[a-zA-Z_][a-zA-Z0-9_]*")]
     Identifier,
-    #[regex(r#""(?:[^"\\]|\\"|\\\\|\\u[0-9a-fA-F]{4})*""#)]
-    #[regex(r"'(?:[^'\\]|\\'|\\\\|\\u[0-9a-fA-F]{4})*'")]
+    #[regex(r#""(?:[^"\\]|\\.)*""#)]
+    #[regex(r"'(?:[^'\\]|\\.)*'")]
     String,
-    #[regex(r"[0-9]+")]
+    #[regex(r"[0-9][0-9_]*")]
+    #[regex(r"0x_*[0-9a-fA-F][0-9a-fA-F_]*")]
+    #[regex(r"0o_*[0-7][0-7_]*")]
+    #[regex(r"0b_*[01][01_]*")]
     Integer,
     #[regex(r"[0-9]+[.][0-9]+(?:[eE][+-]?[0-9]+)?")]
     Float,
@@ -114,6 +127,12 @@ pub enum LexerToken {
     KeywordRecv,
     #[token("interface")]
     KeywordInterface,
+    #[token("handle")]
+    KeywordHandle,
+    #[token("annotation")]
+    KeywordAnnotation,
+    #[token("on")]
+    KeywordOn,
     #[token("fn")]
     KeywordFn,
     #[token("const")]
@@ -132,12 +151,47 @@ pub enum LexerToken {
     KeywordNone,
 }
 
+/// Lexes the rest of a block comment after the opening `/*`, respecting nested comments.
+fn block_comment(lex: &mut Lexer<LexerToken>) -> LexerToken {
+    let remainder = lex.remainder().as_bytes();
+    let mut depth = 1;
+    let mut idx = 0;
+
+    while idx + 1 < remainder.len() {
+        match &remainder[idx..idx + 2] {
+            b"/*" => {
+                depth += 1;
+                idx += 2;
+            }
+            b"*/" => {
+                depth -= 1;
+                idx += 2;
+
+                if depth == 0 {
+                    lex.bump(idx);
+                    return LexerToken::BlockComment;
+                }
+            }
+            _ => idx += 1,
+        }
+    }
+
+    lex.bump(remainder.len());
+    LexerToken::UnterminatedBlockComment
+}
+
 impl LexerToken {
     pub fn expected_str(self) -> &'static str {
         match self {
             LexerToken::EndOfFile => "End of File",
+            LexerToken::Error => "Unknown Token",
             LexerToken::Whitespace => "Spacing",
             LexerToken::Newline => "Newline",
+            LexerToken::Comment => "Comment",
+            LexerToken::DocComment => "Doc Comment",
+            LexerToken::InnerDocComment => "Inner Doc Comment",
+            LexerToken::BlockComment => "Block Comment",
+            LexerToken::UnterminatedBlockComment => "Unterminated Block Comment",
             LexerToken::Identifier => "Identifier",
             LexerToken::String => "String",
             LexerToken::Integer => "Integer",
@@ -189,6 +243,9 @@ impl LexerToken {
             LexerToken::KeywordSend => "send",
             LexerToken::KeywordRecv => "recv",
             LexerToken::KeywordInterface => "interface",
+            LexerToken::KeywordHandle => "handle",
+            LexerToken::KeywordAnnotation => "annotation",
+            LexerToken::KeywordOn => "on",
             LexerToken::KeywordFn => "fn",
             LexerToken::KeywordConst => "const",
             LexerToken::KeywordMod => "mod",
@@ -205,8 +262,13 @@ impl From<LexerToken> for SyntaxKind {
     fn from(value: LexerToken) -> Self {
         match value {
             LexerToken::EndOfFile => SyntaxKind::EndOfFile,
+            LexerToken::Error => SyntaxKind::Error,
             LexerToken::Whitespace => SyntaxKind::Whitespace,
             LexerToken::Newline => SyntaxKind::Newline,
+            LexerToken::Comment
+            | LexerToken::BlockComment
+            | LexerToken::UnterminatedBlockComment => SyntaxKind::Comment,
+            LexerToken::DocComment | LexerToken::InnerDocComment => SyntaxKind::DocComment,
             LexerToken::Identifier => SyntaxKind::Identifier,
             LexerToken::String => SyntaxKind::String,
             LexerToken::Integer => SyntaxKind::Integer,
@@ -258,6 +320,9 @@ impl From<LexerToken> for SyntaxKind {
             | LexerToken::KeywordSend
             | LexerToken::KeywordRecv
             | LexerToken::KeywordInterface
+            | LexerToken::KeywordHandle
+            | LexerToken::KeywordAnnotation
+            | LexerToken::KeywordOn
             | LexerToken::KeywordFn
             | LexerToken::KeywordConst
             | LexerToken::KeywordMod
@@ -270,7 +335,8 @@ impl From<LexerToken> for SyntaxKind {
     }
 }
 
-pub struct LexerTokenSet(u64);
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LexerTokenSet(u128);
 
 impl LexerTokenSet {
     pub const EMPTY: LexerTokenSet = LexerTokenSet(0);
@@ -287,12 +353,35 @@ impl LexerTokenSet {
         LexerTokenSet(result)
     }
 
+    pub const fn union(self, other: LexerTokenSet) -> Self {
+        LexerTokenSet(self.0 | other.0)
+    }
+
     pub const fn contains(&self, token: LexerToken) -> bool {
         (self.0 & mask(token)) != 0
     }
+
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = LexerToken> {
+        let bits = self.0;
+
+        (0..u128::BITS as u8)
+            .filter(move |idx| bits & (1 << idx) != 0)
+            // Only bits of existing tokens can be set, see `mask`
+            .map(|idx| unsafe { std::mem::transmute::<u8, LexerToken>(idx) })
+    }
 }
 
-const fn mask(token: LexerToken) -> u64 {
+impl std::fmt::Debug for LexerTokenSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+const fn mask(token: LexerToken) -> u128 {
     1 << token as usize
 }
 
@@ -317,7 +406,51 @@ mod test {
 
     #[test]
     fn test_comment() {
-        expect_tokens!("//this is a comment\ntest", [LexerToken::Identifier]);
+        expect_tokens!(
+            "//this is a comment\ntest",
+            [
+                LexerToken::Comment,
+                LexerToken::Newline,
+                LexerToken::Identifier
+            ]
+        );
+        expect_tokens!("//// not a doc comment", [LexerToken::Comment]);
+    }
+
+    #[test]
+    fn test_block_comment() {
+        expect_tokens!(
+            "/* comment */test",
+            [LexerToken::BlockComment, LexerToken::Identifier]
+        );
+        expect_tokens!(
+            "/* outer /* inner */ still outer */ test",
+            [
+                LexerToken::BlockComment,
+                LexerToken::Whitespace,
+                LexerToken::Identifier
+            ]
+        );
+        expect_tokens!("/* multi\nline */", [LexerToken::BlockComment]);
+        expect_tokens!(
+            "/* outer /* inner */ test",
+            [LexerToken::UnterminatedBlockComment]
+        );
+        expect_tokens!("/*", [LexerToken::UnterminatedBlockComment]);
+    }
+
+    #[test]
+    fn test_doc_comment() {
+        expect_tokens!(
+            "/// outer\n//! inner\n///",
+            [
+                LexerToken::DocComment,
+                LexerToken::Newline,
+                LexerToken::InnerDocComment,
+                LexerToken::Newline,
+                LexerToken::DocComment
+            ]
+        );
     }
 
     #[test]
@@ -348,6 +481,11 @@ mod test {
             "'This is a also string with a \\u0000!'",
             [LexerToken::String]
         );
+
+        expect_tokens!(
+            "\"Escapes \\n \\t \\r \\0 \\u{1F600} \\q\"",
+            [LexerToken::String]
+        );
     }
 
     #[test]
@@ -364,6 +502,22 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_integer_radix() {
+        expect_tokens!(
+            "0xFF_00 0o777 0b1010_1010 1_000_000",
+            [
+                LexerToken::Integer,
+                LexerToken::Whitespace,
+                LexerToken::Integer,
+                LexerToken::Whitespace,
+                LexerToken::Integer,
+                LexerToken::Whitespace,
+                LexerToken::Integer
+            ]
+        );
+    }
+
     #[test]
     fn test_float() {
         expect_tokens!(
@@ -455,7 +609,7 @@ mod test {
     #[test]
     fn test_keyword() {
         expect_tokens!(
-            "true false import plugin type struct union enum result error send recv interface fn const mod use if then else none",
+            "true false import plugin type struct union enum result error send recv interface handle annotation on fn const mod use if then else none",
             [
                 LexerToken::KeywordTrue,
                 LexerToken::Whitespace,
@@ -483,6 +637,12 @@ mod test {
                 LexerToken::Whitespace,
                 LexerToken::KeywordInterface,
                 LexerToken::Whitespace,
+                LexerToken::KeywordHandle,
+                LexerToken::Whitespace,
+                LexerToken::KeywordAnnotation,
+                LexerToken::Whitespace,
+                LexerToken::KeywordOn,
+                LexerToken::Whitespace,
                 LexerToken::KeywordFn,
                 LexerToken::Whitespace,
                 LexerToken::KeywordConst,
@@ -1,4 +1,5 @@
 pub mod annotations;
+mod error;
 pub mod expression;
 pub mod items;
 mod lexer;
@@ -8,5 +9,377 @@ mod testing;
 pub mod types;
 mod utils;
 
-pub use lexer::LexerToken;
+pub use error::*;
+pub use lexer::{LexerToken, LexerTokenSet};
 pub use parser::*;
+
+use ast::items::File;
+use rowan::ast::AstNode;
+
+/// The result of parsing a file: the syntax tree and every error encountered along the way.
+pub struct Parse {
+    file: File,
+    errors: Vec<ParseError>,
+}
+
+impl Parse {
+    pub fn file(&self) -> &File {
+        &self.file
+    }
+
+    pub fn errors(&self) -> &[ParseError] {
+        &self.errors
+    }
+
+    pub fn into_parts(self) -> (File, Vec<ParseError>) {
+        (self.file, self.errors)
+    }
+}
+
+/// Parses a whole `.yip` file.
+///
+/// Parsing always produces a tree, even if the input contains errors.
+pub fn parse_file(input: &str) -> Parse {
+    let mut p = Parser::new(input);
+    items::parse_file(&mut p);
+
+    let (events, errors) = p.finish();
+    let node = ast::event::build_from_events(events, input);
+
+    Parse {
+        file: File::cast(node).unwrap(),
+        errors,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_file, ErrorCode, LexerToken, LexerTokenSet};
+    use ast::expression::{EscapeErrorKind, Expression, Literal};
+    use ast::items::Item;
+    use ast::types::Type;
+    use ast::SyntaxKind;
+    use rowan::ast::AstNode;
+    use text_size::{TextRange, TextSize};
+
+    #[test]
+    fn test_parse_file() {
+        let parse = parse_file("handle Yap\n\nenum Test {}\n");
+
+        assert!(parse.errors().is_empty());
+        assert_eq!(parse.file().items().count(), 2);
+        assert!(matches!(parse.file().items().next(), Some(Item::Handle(_))));
+    }
+
+    #[test]
+    fn test_parse_file_docs() {
+        let parse = parse_file(
+            "//! File docs
+            /// Interface docs
+            ///
+            ///second paragraph
+            interface Test {
+                // Not a doc comment
+                /// Function docs
+                fn test()
+            }",
+        );
+        assert!(parse.errors().is_empty());
+        assert_eq!(parse.file().docs().as_deref(), Some("File docs"));
+
+        let Some(Item::Interface(interface)) = parse.file().items().next() else {
+            panic!("Expected an interface");
+        };
+        assert_eq!(
+            interface.docs().as_deref(),
+            Some("Interface docs\n\nsecond paragraph")
+        );
+
+        let function = interface.functions().next().unwrap();
+        assert_eq!(function.docs().as_deref(), Some("Function docs"));
+    }
+
+    #[test]
+    fn test_parse_file_block_comments() {
+        let parse = parse_file(
+            "/*
+            handle Old /* nested */
+            */
+            handle /* inline */ Yap
+            /* unterminated",
+        );
+        let errors = parse.errors();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code, ErrorCode::UnterminatedBlockComment);
+        assert_eq!(errors[0].found, LexerToken::UnterminatedBlockComment);
+
+        let items: Vec<_> = parse.file().items().collect();
+        assert_eq!(items.len(), 1);
+        let Item::Handle(handle) = &items[0] else {
+            panic!("Expected a handle");
+        };
+        assert_eq!(handle.name().text(), "Yap");
+    }
+
+    #[test]
+    fn test_parse_file_integers() {
+        let parse = parse_file(
+            "enum Flags { A B = 0b1010_1010 C = 0xFFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF_F }
+            type Bytes = [u8; 0o20]
+            interface Test { fn test() = 1_000 }",
+        );
+        assert!(parse.errors().is_empty());
+
+        let mut items = parse.file().items();
+
+        let Some(Item::Enum(flags)) = items.next() else {
+            panic!("Expected an enum");
+        };
+        let members: Vec<_> = flags.members().collect();
+        assert_eq!(members[0].value(), None);
+        assert_eq!(members[1].value(), Some(Ok(0b1010_1010)));
+        assert!(matches!(members[2].value(), Some(Err(_))));
+
+        let Some(Item::Alias(bytes)) = items.next() else {
+            panic!("Expected an alias");
+        };
+        let Type::List(list) = bytes.ty() else {
+            panic!("Expected a list type");
+        };
+        assert_eq!(list.amount(), Some(Ok(16)));
+
+        let Some(Item::Interface(interface)) = items.next() else {
+            panic!("Expected an interface");
+        };
+        let function = interface.functions().next().unwrap();
+        assert_eq!(function.function_id(), Some(Ok(1000)));
+    }
+
+    #[test]
+    fn test_parse_file_strings() {
+        let input = r#"import "file:///yip\u{1F600}.yip"
+        plugin 'plugin.wasm'
+        const A = "\"\n\t\r\0\\é\u{1F600}\'"
+        const B = "bad \q"
+        const C = "bad \u{110000}"
+        const D = "bad \u12""#;
+        let parse = parse_file(input);
+        assert!(parse.errors().is_empty());
+
+        let items: Vec<_> = parse.file().items().collect();
+
+        let Item::Import(import) = &items[0] else {
+            panic!("Expected an import");
+        };
+        assert_eq!(import.url().unwrap(), "file:///yip\u{1F600}.yip");
+
+        let Item::Plugin(plugin) = &items[1] else {
+            panic!("Expected a plugin");
+        };
+        assert_eq!(plugin.url().unwrap(), "plugin.wasm");
+
+        let string = |item: &Item| {
+            let Item::Const(constant) = item else {
+                panic!("Expected a constant");
+            };
+            let Expression::Literal(Literal::String(string)) = constant.expression() else {
+                panic!("Expected a string literal");
+            };
+            string.value()
+        };
+
+        assert_eq!(string(&items[2]).unwrap(), "\"\n\t\r\0\\\u{e9}\u{1F600}'");
+
+        let error = string(&items[3]).unwrap_err();
+        assert_eq!(error.kind, EscapeErrorKind::UnknownEscape);
+        assert_eq!(&input[error.range], r"\q");
+
+        let error = string(&items[4]).unwrap_err();
+        assert_eq!(error.kind, EscapeErrorKind::InvalidCodePoint);
+        assert_eq!(&input[error.range], r"\u{110000}");
+
+        let error = string(&items[5]).unwrap_err();
+        assert_eq!(error.kind, EscapeErrorKind::MalformedUnicodeEscape);
+        assert_eq!(&input[error.range], r"\u12");
+    }
+
+    #[test]
+    fn test_parse_file_errors() {
+        let parse = parse_file("handle $\nconst A = ");
+        let errors = parse.errors();
+
+        assert_eq!(errors.len(), 3);
+
+        assert_eq!(errors[0].code, ErrorCode::UnknownToken);
+        assert_eq!(errors[0].found, LexerToken::Error);
+        assert_eq!(
+            errors[0].range,
+            TextRange::new(TextSize::new(7), TextSize::new(8))
+        );
+
+        assert_eq!(errors[1].code, ErrorCode::UnexpectedToken);
+        assert_eq!(
+            errors[1].expected,
+            LexerTokenSet::new(&[LexerToken::Identifier])
+        );
+        assert_eq!(errors[1].found, LexerToken::Newline);
+
+        assert_eq!(errors[2].code, ErrorCode::ExpectedExpression);
+        assert!(errors[2].expected.contains(LexerToken::Integer));
+        assert_eq!(errors[2].found, LexerToken::EndOfFile);
+        assert_eq!(errors[2].range, TextRange::empty(TextSize::new(19)));
+
+        assert_eq!(parse.file().items().count(), 2);
+    }
+
+    #[test]
+    fn test_parse_file_recovery() {
+        let parse = parse_file(
+            "struct A {
+                a: bool
+            enum B { A = }
+            interface C { fn c(a: bool, 1 }
+            const D: (bool, = [1, 2",
+        );
+        let errors = parse.errors();
+
+        assert_eq!(errors.len(), 6);
+        assert_eq!(errors[0].found, LexerToken::KeywordEnum);
+        assert_eq!(errors[1].code, ErrorCode::ExpectedExpression);
+        assert_eq!(errors[2].found, LexerToken::Integer);
+        assert_eq!(errors[3].found, LexerToken::RBraces);
+        assert_eq!(errors[4].found, LexerToken::Equals);
+        assert_eq!(errors[5].found, LexerToken::EndOfFile);
+
+        assert_eq!(parse.file().items().count(), 4);
+    }
+
+    #[test]
+    fn test_parse_file_skipped_tokens() {
+        // Runs of unexpected tokens are reported once and wrapped in a single error node
+        for input in [
+            "interface I {\nfn get(a: u8) -> P = 1\n}",
+            "interface I {\nfn get(a: u8, 1 + 2, b: u8)\n}",
+            "handle A\n1 + 2\nhandle B",
+        ] {
+            let parse = parse_file(input);
+            assert_eq!(parse.errors().len(), 1, "{}", input);
+
+            let errors: Vec<_> = parse
+                .file()
+                .syntax()
+                .descendants()
+                .filter(|x| x.kind() == SyntaxKind::Error)
+                .collect();
+            assert_eq!(errors.len(), 1, "{}", input);
+            assert_eq!(
+                errors[0].text_range().start(),
+                parse.errors()[0].range.start()
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_error_report() {
+        let parse = parse_file("struct A {\n    a bool\n}");
+        let errors = parse.errors();
+        assert_eq!(errors.len(), 1);
+
+        let report = errors[0].report();
+        assert_eq!(report.code, "P0002");
+        assert_eq!(report.message, "expected `:`, found identifier");
+        assert_eq!(report.labels[0].range, errors[0].range);
+    }
+
+    #[test]
+    fn test_parse_file_terminates() {
+        let input = "@Test(1, [2]) mod A {
+            use a::{b, c}
+            struct B { a: (bool, ?[u8; 4]) }
+            union C { A(bool) B { b: bool } }
+            enum D { A = if a then (1 + 2) else -3 }
+            interface E { fn e(a: bool): result bool error bool = 1 }
+            const F: bool = true
+        }";
+
+        // Every prefix of a valid file is a half-typed file
+        for (end, _) in input.char_indices() {
+            parse_file(&input[..end]);
+        }
+
+        for input in [
+            "}}) ] , = @ @(",
+            "struct { enum { interface { fn (",
+            "use a::{struct",
+            "@a(@b(",
+        ] {
+            parse_file(input);
+        }
+    }
+
+    #[test]
+    fn test_parse_file_multiline_lists() {
+        let parse = parse_file(
+            "use yip::{
+                a,
+                b,
+            }
+
+            @Test(
+                1,
+                (2, 3),
+            )
+            interface Test {
+                fn test(
+                    a: (
+                        bool,
+                        bool,
+                    ),
+                    b: bool,
+                ): bool
+            }
+
+            const A = [
+                1,
+                2,
+            ]",
+        );
+        assert!(parse.errors().is_empty());
+
+        let Some(Item::Interface(interface)) = parse.file().items().nth(1) else {
+            panic!("Expected an interface");
+        };
+        let function = interface.functions().next().unwrap();
+        assert_eq!(function.parameters().count(), 2);
+    }
+
+    #[test]
+    fn test_parse_file_annotations() {
+        let parse = parse_file(
+            "@rust::derive(Debug, Clone)
+            @since(1, version = 2)
+            handle Yap",
+        );
+        assert!(parse.errors().is_empty());
+
+        let Some(Item::Handle(handle)) = parse.file().items().next() else {
+            panic!("Expected a handle");
+        };
+        let annotations: Vec<_> = handle.annotations().collect();
+
+        assert_eq!(annotations[0].name(), "rust::derive");
+        assert_eq!(annotations[0].arguments().count(), 2);
+        assert_eq!(annotations[0].named_arguments().count(), 0);
+
+        assert_eq!(annotations[1].name(), "since");
+        assert_eq!(annotations[1].arguments().count(), 1);
+        let Some(Expression::Literal(Literal::Integer(version))) =
+            annotations[1].named_argument("version")
+        else {
+            panic!("Expected an integer");
+        };
+        assert_eq!(version.value(), Ok(2));
+        assert!(annotations[1].named_argument("until").is_none());
+    }
+}
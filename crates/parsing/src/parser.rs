@@ -1,3 +1,4 @@
+use crate::error::{ErrorCode, ParseError};
 use crate::lexer::{LexerToken, LexerTokenSet};
 use ast::event::Event;
 use ast::SyntaxKind;
@@ -9,24 +10,39 @@ pub struct Parser {
     tokens: Vec<(LexerToken, Span)>,
     position: usize,
     events: Vec<Event>,
+    errors: Vec<ParseError>,
+    len: usize,
 }
 
 impl Parser {
     pub fn new(input: &str) -> Self {
-        let tokens = LexerToken::lexer(input)
-            .spanned()
-            .map(|(token, span)| (token.unwrap(), span))
-            .collect();
+        let mut tokens: Vec<(LexerToken, Span)> = Vec::new();
+
+        for (token, span) in LexerToken::lexer(input).spanned() {
+            let token = token.unwrap_or(LexerToken::Error);
+
+            // Merge consecutive unrecognized characters into a single error token
+            match tokens.last_mut() {
+                Some((LexerToken::Error, last))
+                    if token == LexerToken::Error && last.end == span.start =>
+                {
+                    last.end = span.end
+                }
+                _ => tokens.push((token, span)),
+            }
+        }
 
         Parser {
             tokens,
             position: 0,
             events: Vec::new(),
+            errors: Vec::new(),
+            len: input.len(),
         }
     }
 
-    pub fn finish(self) -> Vec<Event> {
-        self.events
+    pub fn finish(self) -> (Vec<Event>, Vec<ParseError>) {
+        (self.events, self.errors)
     }
 
     pub fn current(&self) -> LexerToken {
@@ -37,8 +53,12 @@ impl Parser {
     }
 
     pub fn peek(&self) -> LexerToken {
+        self.nth(1)
+    }
+
+    pub fn nth(&self, n: usize) -> LexerToken {
         self.tokens
-            .get(self.position + 1)
+            .get(self.position + n)
             .map(|(token, _)| *token)
             .unwrap_or(LexerToken::EndOfFile)
     }
@@ -48,7 +68,7 @@ impl Parser {
             .get(self.position)
             .map(|(_, span)| span)
             .cloned()
-            .unwrap_or_default()
+            .unwrap_or(self.len..self.len)
     }
 
     pub fn at(&self, token: LexerToken) -> bool {
@@ -91,6 +111,7 @@ impl Parser {
     pub fn start(&mut self) -> NodeStub {
         let position = self.events.len();
         self.events.push(Event::Stub);
+        self.skip_trivia();
 
         NodeStub::new(position)
     }
@@ -106,21 +127,79 @@ impl Parser {
         }
     }
 
-    pub fn error(&mut self, expected: &'static str) {
+    pub fn error(&mut self, code: ErrorCode, expected: LexerTokenSet) {
         let span = self.span();
         let range = TextRange::new(
             TextSize::new(span.start as u32),
             TextSize::new(span.end as u32),
         );
 
-        self.events.push(Event::Error { expected, range })
+        let error = ParseError {
+            code,
+            range,
+            expected,
+            found: self.current(),
+        };
+
+        self.events.push(Event::Error {
+            expected: error.expected_str(),
+            range,
+        });
+        self.errors.push(error);
+    }
+
+    /// Reports an error and wraps the current token in an error node, unless it is in `recovery`.
+    /// Tokens in `recovery` are left for an enclosing rule to handle.
+    pub fn err_recover(
+        &mut self,
+        code: ErrorCode,
+        expected: LexerTokenSet,
+        recovery: LexerTokenSet,
+    ) {
+        if self.at_set(recovery) {
+            self.error(code, expected);
+        } else {
+            self.err_and_bump(code, expected);
+        }
+    }
+
+    /// Reports an error and wraps the current token in an error node, guaranteeing progress.
+    pub fn err_and_bump(&mut self, code: ErrorCode, expected: LexerTokenSet) {
+        self.error(code, expected);
+
+        if !self.at(LexerToken::EndOfFile) {
+            let stub = self.start();
+            self.bump_any();
+            stub.complete(self, SyntaxKind::Error);
+        }
+    }
+
+    /// Reports an error once and wraps the current token and every token up to one in `stop` in a
+    /// single error node, leaving out whitespace before that token. At least one token is
+    /// consumed, guaranteeing progress.
+    pub fn err_and_skip(&mut self, code: ErrorCode, expected: LexerTokenSet, stop: LexerTokenSet) {
+        self.error(code, expected);
+
+        if !self.at(LexerToken::EndOfFile) {
+            let stub = self.start();
+            self.bump_any();
+            while !self.at_set(stop) && !self.at(LexerToken::EndOfFile) {
+                if self.at(LexerToken::Whitespace)
+                    && (self.peek_at_set(stop) || self.peek_at(LexerToken::EndOfFile))
+                {
+                    break;
+                }
+                self.bump_any();
+            }
+            stub.complete(self, SyntaxKind::Error);
+        }
     }
 
     pub fn expect(&mut self, token: LexerToken) -> bool {
         if self.eat(token) {
             true
         } else {
-            self.error(token.expected_str());
+            self.error(ErrorCode::UnexpectedToken, LexerTokenSet::new(&[token]));
             false
         }
     }
@@ -133,7 +212,46 @@ impl Parser {
         );
 
         self.position += 1;
-        self.events.push(Event::Token { kind, range })
+        self.events.push(Event::Token { kind, range });
+        self.skip_trivia();
+    }
+
+    /// Moves comments and unrecognized input into the tree, so the grammar never sees them.
+    fn skip_trivia(&mut self) {
+        loop {
+            let token = self.current();
+            let kind = match token {
+                LexerToken::Comment | LexerToken::BlockComment => SyntaxKind::Comment,
+                LexerToken::UnterminatedBlockComment => {
+                    self.error(ErrorCode::UnterminatedBlockComment, LexerTokenSet::EMPTY);
+                    SyntaxKind::Comment
+                }
+                LexerToken::Error => {
+                    self.error(ErrorCode::UnknownToken, LexerTokenSet::EMPTY);
+                    SyntaxKind::Error
+                }
+                _ => break,
+            };
+
+            let span = self.span();
+            let range = TextRange::new(
+                TextSize::new(span.start as u32),
+                TextSize::new(span.end as u32),
+            );
+
+            self.position += 1;
+            self.events.push(Event::Token { kind, range });
+
+            // A block comment surrounded by spacing reads as a single space,
+            // so `a /* b */ c` parses the same as `a c`
+            if token == LexerToken::BlockComment
+                && self.at(LexerToken::Whitespace)
+                && self.position >= 2
+                && self.tokens[self.position - 2].0 == LexerToken::Whitespace
+            {
+                self.do_bump(SyntaxKind::Whitespace);
+            }
+        }
     }
 }
 
@@ -20,6 +20,12 @@ macro_rules! make_event {
             range: Default::default(),
         }
     };
+    ((error $expected:expr)) => {
+        ast::event::Event::Error {
+            expected: $expected,
+            range: Default::default(),
+        }
+    };
     (stub) => {
         ast::event::Event::Stub
     };
@@ -29,7 +35,7 @@ macro_rules! expect_events {
     ($input:literal, $( $event:tt ),+ $(,)? ) => {{
         let mut p = $crate::Parser::new($input);
         parse(&mut p);
-        for (idx, (actual, expected)) in p.finish().into_iter().zip([
+        for (idx, (actual, expected)) in p.finish().0.into_iter().zip([
             $( $crate::testing::make_event!($event) ),+
         ].into_iter()).enumerate() {
             match (actual, expected) {
@@ -39,6 +45,12 @@ macro_rules! expect_events {
                 ) => {
                     assert_eq!(actual, expected, "At Item: {}", idx)
                 },
+                (
+                    ast::event::Event::Error { expected: actual, .. },
+                    ast::event::Event::Error { expected, .. },
+                ) => {
+                    assert_eq!(actual, expected, "At Item: {}", idx)
+                },
                 (actual, expected) => assert_eq!(actual, expected, "At Item: {}", idx),
             }
         }
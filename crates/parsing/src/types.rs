@@ -1,7 +1,34 @@
-use crate::lexer::LexerToken;
+use crate::error::ErrorCode;
+use crate::lexer::{LexerToken, LexerTokenSet};
 use crate::{utils, Parser};
 use ast::SyntaxKind;
 
+pub(crate) const TYPE_START: LexerTokenSet = LexerTokenSet::new(&[
+    LexerToken::KeywordRecv,
+    LexerToken::KeywordSend,
+    LexerToken::Question,
+    LexerToken::KeywordResult,
+    LexerToken::LParentheses,
+    LexerToken::LBrackets,
+    LexerToken::Identifier,
+]);
+
+/// Tokens that can follow a type, left for the enclosing rule when a type is missing.
+const TYPE_RECOVERY: LexerTokenSet = utils::LIST_RECOVERY.union(LexerTokenSet::new(&[
+    LexerToken::Whitespace,
+    LexerToken::Newline,
+    LexerToken::Comma,
+    LexerToken::Semicolon,
+    LexerToken::Equals,
+    LexerToken::RParentheses,
+    LexerToken::RBrackets,
+    LexerToken::KeywordError,
+]));
+
+/// A tuple type missing its `)` stops at the value of a constant or the ID of a function.
+const TUPLE_RECOVERY: LexerTokenSet =
+    utils::LIST_RECOVERY.union(LexerTokenSet::new(&[LexerToken::Equals]));
+
 pub fn parse(p: &mut Parser) {
     match p.current() {
         LexerToken::KeywordRecv => recv_type(p),
@@ -11,7 +38,7 @@ pub fn parse(p: &mut Parser) {
         LexerToken::LParentheses => tuple_type(p),
         LexerToken::LBrackets => list_type(p),
         LexerToken::Identifier => named_type(p),
-        _ => p.error("Type"),
+        _ => p.err_recover(ErrorCode::ExpectedType, TYPE_START, TYPE_RECOVERY),
     }
 }
 
@@ -66,14 +93,14 @@ fn tuple_type(p: &mut Parser) {
     let stub = p.start();
 
     p.bump(LexerToken::LParentheses);
-    while !p.eat(LexerToken::RParentheses) {
-        p.eat(LexerToken::Whitespace);
-        parse(p);
-        p.eat(LexerToken::Whitespace);
-        if !p.at(LexerToken::RParentheses) {
-            p.expect(LexerToken::Comma);
-        }
-    }
+    utils::list(
+        p,
+        LexerToken::RParentheses,
+        TYPE_START,
+        TUPLE_RECOVERY,
+        ErrorCode::ExpectedType,
+        parse,
+    );
 
     stub.complete(p, SyntaxKind::TupleType);
 }
@@ -219,6 +246,21 @@ mod test {
             (token SyntaxKind::Punctuation),
             finish
         );
+
+        expect_events!(
+            "(\n    bool,\n)",
+            (start SyntaxKind::TupleType),
+            (token SyntaxKind::Punctuation),
+            (token SyntaxKind::Newline),
+            (token SyntaxKind::Whitespace),
+            (start SyntaxKind::NamedType),
+            (token SyntaxKind::Identifier),
+            finish,
+            (token SyntaxKind::Punctuation),
+            (token SyntaxKind::Newline),
+            (token SyntaxKind::Punctuation),
+            finish
+        );
     }
 
     #[test]
@@ -228,8 +270,9 @@ mod test {
             (start SyntaxKind::ListType),
             (token SyntaxKind::Punctuation),
             (start SyntaxKind::NamedType),
-            (token SyntaxKind::Punctuation),
+            (token SyntaxKind::Identifier),
             finish,
+            (token SyntaxKind::Punctuation),
             finish
         );
 
@@ -1,9 +1,20 @@
+use crate::error::ErrorCode;
+use crate::items::ITEM_KEYWORDS;
 use crate::lexer::{LexerToken, LexerTokenSet};
 use crate::Parser;
 
 const WHITESPACE_NEWLINE: LexerTokenSet =
     LexerTokenSet::new(&[LexerToken::Whitespace, LexerToken::Newline]);
 
+/// Tokens that never appear inside a parenthesized or bracketed list. A list missing its closing
+/// delimiter stops at these, so the enclosing rule can pick up from there. Rules can extend this
+/// with the tokens that may follow them.
+pub const LIST_RECOVERY: LexerTokenSet = ITEM_KEYWORDS.union(LexerTokenSet::new(&[
+    LexerToken::KeywordFn,
+    LexerToken::LBraces,
+    LexerToken::RBraces,
+]));
+
 pub fn whitespace_newline(p: &mut Parser) {
     while p.eat_set(WHITESPACE_NEWLINE) {}
 }
@@ -20,3 +31,96 @@ pub fn path(p: &mut Parser) {
         p.expect(LexerToken::Identifier);
     }
 }
+
+/// How many elements and separators a list contained.
+pub struct ListShape {
+    pub elements: usize,
+    pub commas: usize,
+}
+
+/// Parses a comma separated list up to and including `close`, after the opening delimiter has
+/// been consumed. Elements may be spread over several lines and followed by a trailing comma.
+///
+/// `element` is only called at tokens in `first`, anything else up to the next element, comma or
+/// `close` is wrapped in a single error node. The list ends early at the end of the file or at a
+/// token in `recovery`.
+pub fn list(
+    p: &mut Parser,
+    close: LexerToken,
+    first: LexerTokenSet,
+    recovery: LexerTokenSet,
+    code: ErrorCode,
+    mut element: impl FnMut(&mut Parser),
+) -> ListShape {
+    let at_end = |p: &Parser| p.at(close) || p.at(LexerToken::EndOfFile) || p.at_set(recovery);
+    let mut shape = ListShape {
+        elements: 0,
+        commas: 0,
+    };
+
+    loop {
+        whitespace_newline(p);
+        if at_end(p) {
+            break;
+        }
+
+        if !p.at_set(first) {
+            let stop = first
+                .union(recovery)
+                .union(LexerTokenSet::new(&[close, LexerToken::Comma]));
+            p.err_and_skip(code, first, stop);
+
+            // The skipped tokens take the place of an element, so a comma may follow them
+            whitespace_newline(p);
+            p.eat(LexerToken::Comma);
+            continue;
+        }
+
+        element(p);
+        shape.elements += 1;
+        whitespace_newline(p);
+
+        if !at_end(p) && p.expect(LexerToken::Comma) {
+            shape.commas += 1;
+        }
+    }
+
+    p.expect(close);
+    shape
+}
+
+/// Parses a `{}` delimited block of members, one per line.
+///
+/// Members are only parsed at tokens in `first`, anything else up to the end of the line is wrapped
+/// in a single error node. The block ends early at the end of the file or at a token in
+/// `recovery`, so a missing `}` doesn't swallow the items following it.
+pub fn block(
+    p: &mut Parser,
+    first: LexerTokenSet,
+    recovery: LexerTokenSet,
+    code: ErrorCode,
+    mut member: impl FnMut(&mut Parser),
+) {
+    if !p.expect(LexerToken::LBraces)
+        && (p.at(LexerToken::RBraces) || p.at(LexerToken::EndOfFile) || p.at_set(recovery))
+    {
+        return;
+    }
+    whitespace_newline(p);
+
+    while !p.at(LexerToken::RBraces) && !p.at(LexerToken::EndOfFile) && !p.at_set(recovery) {
+        if p.at_set(first) {
+            member(p);
+        } else {
+            let stop = first.union(recovery).union(LexerTokenSet::new(&[
+                LexerToken::RBraces,
+                LexerToken::Newline,
+            ]));
+            p.err_and_skip(code, first, stop);
+        }
+
+        whitespace_newline(p);
+    }
+
+    p.expect(LexerToken::RBraces);
+}
@@ -0,0 +1,90 @@
+use crate::{Position, Report, Source};
+use std::fmt::Write;
+
+/// Renders a report as a single line JSON object. The file and position of the first primary
+/// label are repeated at the top level for tools that only show one location.
+pub fn render(report: &Report, sources: &[Source]) -> String {
+    let primary = report
+        .labels
+        .iter()
+        .find(|x| x.primary)
+        .or(report.labels.first());
+    let source = &sources[primary.map_or(0, |x| x.source)];
+
+    let mut out = String::new();
+    write!(
+        out,
+        r#"{{"severity":{},"code":{},"message":{},"file":{}"#,
+        string(report.severity.as_str()),
+        string(report.code),
+        string(&report.message),
+        string(source.name()),
+    )
+    .unwrap();
+
+    if let Some(primary) = primary {
+        let position = source.position(primary.range.start());
+        write!(
+            out,
+            r#","line":{},"column":{}"#,
+            position.line, position.column
+        )
+        .unwrap();
+    }
+
+    out.push_str(r#","labels":["#);
+    for (idx, label) in report.labels.iter().enumerate() {
+        if idx > 0 {
+            out.push(',');
+        }
+        let source = &sources[label.source];
+        write!(
+            out,
+            r#"{{"primary":{},"message":{},"file":{},"start":{},"end":{}}}"#,
+            label.primary,
+            string(&label.message),
+            string(source.name()),
+            position(source.position(label.range.start())),
+            position(source.position(label.range.end())),
+        )
+        .unwrap();
+    }
+
+    out.push_str(r#"],"notes":["#);
+    for (idx, note) in report.notes.iter().enumerate() {
+        if idx > 0 {
+            out.push(',');
+        }
+        out.push_str(&string(note));
+    }
+    out.push_str("]}");
+
+    out
+}
+
+fn position(position: Position) -> String {
+    format!(
+        r#"{{"offset":{},"line":{},"column":{}}}"#,
+        u32::from(position.offset),
+        position.line,
+        position.column
+    )
+}
+
+fn string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
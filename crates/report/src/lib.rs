@@ -0,0 +1,312 @@
+//! ## Yip Diagnostic Reports
+//!
+//! This crate renders errors found in `.yip` files, independent of the stage that found them.
+//! The parser and the type checker convert their errors into [`Report`]s, which can then be shown
+//! to users as plain text with source snippets, or as JSON for tools.
+//!
+//! A report can also point into several files, like one comparing two versions of a schema. Its
+//! labels then name the source they belong to and it is rendered with [`render_sources`].
+//!
+mod json;
+mod plain;
+
+use text_size::{TextRange, TextSize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+    /// Something worth knowing that isn't a problem
+    Note,
+}
+
+impl Severity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "note",
+        }
+    }
+}
+
+/// A message attached to a range of the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Label {
+    pub range: TextRange,
+    pub message: String,
+    /// Primary labels point at the cause of the report, secondary ones add context
+    pub primary: bool,
+    /// The index of the source the range is in, when rendered with [`render_sources`]
+    pub source: usize,
+}
+
+/// A problem found in a source file, ready to be rendered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub severity: Severity,
+    /// A stable identifier for the kind of problem, like `P0002`
+    pub code: &'static str,
+    pub message: String,
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+}
+
+impl Report {
+    pub fn new(severity: Severity, code: &'static str, message: impl Into<String>) -> Self {
+        Report {
+            severity,
+            code,
+            message: message.into(),
+            labels: Vec::new(),
+            notes: Vec::new(),
+        }
+    }
+
+    pub fn error(code: &'static str, message: impl Into<String>) -> Self {
+        Report::new(Severity::Error, code, message)
+    }
+
+    pub fn with_label(self, range: TextRange, message: impl Into<String>) -> Self {
+        self.with_label_in(0, range, message)
+    }
+
+    pub fn with_secondary_label(self, range: TextRange, message: impl Into<String>) -> Self {
+        self.with_secondary_label_in(0, range, message)
+    }
+
+    /// Adds a primary label to the source with the index `source`.
+    pub fn with_label_in(
+        mut self,
+        source: usize,
+        range: TextRange,
+        message: impl Into<String>,
+    ) -> Self {
+        self.labels.push(Label {
+            range,
+            message: message.into(),
+            primary: true,
+            source,
+        });
+        self
+    }
+
+    /// Adds a secondary label to the source with the index `source`.
+    pub fn with_secondary_label_in(
+        mut self,
+        source: usize,
+        range: TextRange,
+        message: impl Into<String>,
+    ) -> Self {
+        self.labels.push(Label {
+            range,
+            message: message.into(),
+            primary: false,
+            source,
+        });
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+}
+
+/// How reports are written out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Human readable text with source snippets
+    Plain,
+    /// One JSON object per report
+    Json,
+}
+
+/// Renders `report` about `source` in the given format. Plain reports end with an empty line,
+/// JSON reports are a single line.
+pub fn render(report: &Report, source: &Source, format: Format) -> String {
+    render_sources(report, std::slice::from_ref(source), format)
+}
+
+/// Renders a report with labels in several sources, each label's [`Label::source`] is an index
+/// into `sources`.
+pub fn render_sources(report: &Report, sources: &[Source], format: Format) -> String {
+    match format {
+        Format::Plain => plain::render(report, sources),
+        Format::Json => json::render(report, sources),
+    }
+}
+
+/// A file reports are rendered against.
+pub struct Source<'a> {
+    name: &'a str,
+    text: &'a str,
+    /// Offsets at which each line starts
+    lines: Vec<TextSize>,
+}
+
+/// A position in a [`Source`], with one-based line and column numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub offset: TextSize,
+    pub line: usize,
+    /// Counted in characters, not bytes
+    pub column: usize,
+}
+
+impl<'a> Source<'a> {
+    pub fn new(name: &'a str, text: &'a str) -> Self {
+        let lines = std::iter::once(TextSize::new(0))
+            .chain(
+                text.match_indices('\n')
+                    .map(|(idx, _)| TextSize::new(idx as u32 + 1)),
+            )
+            .collect();
+
+        Source { name, text, lines }
+    }
+
+    pub fn name(&self) -> &str {
+        self.name
+    }
+
+    pub fn position(&self, offset: TextSize) -> Position {
+        let offset = offset.min(TextSize::of(self.text));
+        let line = self.lines.partition_point(|x| *x <= offset) - 1;
+        let start = usize::from(self.lines[line]);
+
+        Position {
+            offset,
+            line: line + 1,
+            column: self.text[start..usize::from(offset)].chars().count() + 1,
+        }
+    }
+
+    /// The text of the one-based line `line`, without its line break.
+    fn line(&self, line: usize) -> &str {
+        let start = usize::from(self.lines[line - 1]);
+        let end = self
+            .lines
+            .get(line)
+            .map(|x| usize::from(*x))
+            .unwrap_or(self.text.len());
+
+        self.text[start..end].trim_end_matches(['\n', '\r'])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{render, render_sources, Format, Report, Severity, Source};
+    use text_size::{TextRange, TextSize};
+
+    fn range(start: u32, end: u32) -> TextRange {
+        TextRange::new(TextSize::new(start), TextSize::new(end))
+    }
+
+    #[test]
+    fn test_position() {
+        let source = Source::new("test.yip", "ab\r\ncä\n\nd");
+
+        let position = source.position(TextSize::new(7));
+        assert_eq!((position.line, position.column), (2, 3));
+
+        let position = source.position(TextSize::new(9));
+        assert_eq!((position.line, position.column), (4, 1));
+
+        let position = source.position(TextSize::new(100));
+        assert_eq!((position.line, position.column), (4, 2));
+    }
+
+    #[test]
+    fn test_plain() {
+        let source = Source::new("test.yip", "const A: bool = \"yes\"\nconst B = 1");
+        let report = Report::error("T0002", "expected `bool`, found `string`")
+            .with_label(range(16, 21), "expected `bool`")
+            .with_secondary_label(range(9, 13), "declared here")
+            .with_note("strings can't be converted");
+
+        assert_eq!(
+            render(&report, &source, Format::Plain),
+            concat!(
+                "error[T0002]: expected `bool`, found `string`\n",
+                " --> test.yip:1:17\n",
+                "  |\n",
+                "1 | const A: bool = \"yes\"\n",
+                "  |                 ^^^^^ expected `bool`\n",
+                "  |          ---- declared here\n",
+                "  |\n",
+                "  = note: strings can't be converted\n",
+                "\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_plain_lines() {
+        let source = Source::new("test.yip", "a\n\tb\nc");
+        let report = Report::error("P0002", "expected `}`")
+            .with_label(range(3, 4), "here")
+            .with_secondary_label(range(0, 1), "opened here");
+
+        assert_eq!(
+            render(&report, &source, Format::Plain),
+            concat!(
+                "error[P0002]: expected `}`\n",
+                " --> test.yip:2:2\n",
+                "  |\n",
+                "1 | a\n",
+                "  | - opened here\n",
+                "2 |     b\n",
+                "  |     ^ here\n",
+                "\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let source = Source::new("dir\\\"a\".yip", "x\ny");
+        let report = Report::error("P0001", "unknown token")
+            .with_label(range(2, 3), "not a \"token\"")
+            .with_note("a\nb");
+
+        assert_eq!(
+            render(&report, &source, Format::Json),
+            concat!(
+                r#"{"severity":"error","code":"P0001","message":"unknown token","#,
+                r#""file":"dir\\\"a\".yip","line":2,"column":1,"#,
+                r#""labels":[{"primary":true,"message":"not a \"token\"","file":"dir\\\"a\".yip","#,
+                r#""start":{"offset":2,"line":2,"column":1},"#,
+                r#""end":{"offset":3,"line":2,"column":2}}],"#,
+                r#""notes":["a\nb"]}"#,
+            )
+        );
+    }
+
+    #[test]
+    fn test_sources() {
+        let old = Source::new("old.yip", "struct S {\na: u8\n}");
+        let new = Source::new("new.yip", "struct S {\na: u16\n}");
+        let report = Report::new(Severity::Note, "C0013", "type changed")
+            .with_label_in(1, range(14, 17), "now `u16`")
+            .with_secondary_label_in(0, range(14, 16), "was `u8`");
+
+        assert_eq!(
+            render_sources(&report, &[old, new], Format::Plain),
+            concat!(
+                "note[C0013]: type changed\n",
+                " --> new.yip:2:4\n",
+                "  |\n",
+                "2 | a: u16\n",
+                "  |    ^^^ now `u16`\n",
+                "  |\n",
+                " ::: old.yip:2:4\n",
+                "  |\n",
+                "2 | a: u8\n",
+                "  |    -- was `u8`\n",
+                "\n",
+            )
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{Label, Report, Source};
+use std::fmt::Write;
+
+const TAB_WIDTH: usize = 4;
+
+/// Renders a report in the style of rustc, with each label underlining its range in a snippet
+/// of the source. Labels in other sources than the primary one follow in snippets of their own.
+pub fn render(report: &Report, sources: &[Source]) -> String {
+    let mut out = String::new();
+    writeln!(
+        out,
+        "{}[{}]: {}",
+        report.severity.as_str(),
+        report.code,
+        report.message
+    )
+    .unwrap();
+
+    let width = report
+        .labels
+        .iter()
+        .map(|x| sources[x.source].position(x.range.start()).line)
+        .max()
+        .map(|x| x.to_string().len())
+        .unwrap_or(1);
+    let gutter = " ".repeat(width);
+
+    let primary = report
+        .labels
+        .iter()
+        .find(|x| x.primary)
+        .or(report.labels.first());
+
+    // The source of the primary label comes first
+    let mut order: Vec<_> = report.labels.iter().map(|x| x.source).collect();
+    order.sort_unstable_by_key(|x| (Some(*x) != primary.map(|x| x.source), *x));
+    order.dedup();
+
+    for (idx, index) in order.into_iter().enumerate() {
+        let source = &sources[index];
+        let labels: Vec<_> = report.labels.iter().filter(|x| x.source == index).collect();
+
+        let mut lines: Vec<_> = labels
+            .iter()
+            .map(|x| source.position(x.range.start()).line)
+            .collect();
+        lines.sort_unstable();
+        lines.dedup();
+
+        let first = labels.iter().find(|x| x.primary).unwrap_or(&labels[0]);
+        let position = source.position(first.range.start());
+        if idx > 0 {
+            writeln!(out, "{} |", gutter).unwrap();
+        }
+        writeln!(
+            out,
+            "{}{} {}:{}:{}",
+            gutter,
+            if idx == 0 { "-->" } else { ":::" },
+            source.name(),
+            position.line,
+            position.column
+        )
+        .unwrap();
+        writeln!(out, "{} |", gutter).unwrap();
+
+        for line in lines {
+            let text = source.line(line);
+            writeln!(out, "{:>width$} | {}", line, expand_tabs(text)).unwrap();
+
+            for label in &labels {
+                if source.position(label.range.start()).line == line {
+                    writeln!(out, "{} | {}", gutter, underline(label, source, text)).unwrap();
+                }
+            }
+        }
+    }
+
+    if !report.notes.is_empty() {
+        if !report.labels.is_empty() {
+            writeln!(out, "{} |", gutter).unwrap();
+        }
+        for note in &report.notes {
+            writeln!(out, "{} = note: {}", gutter, note).unwrap();
+        }
+    }
+
+    out.push('\n');
+    out
+}
+
+/// The markers under a label's range, followed by its message. Ranges spanning several lines
+/// are underlined up to the end of their first line.
+fn underline(label: &Label, source: &Source, text: &str) -> String {
+    let start = source.position(label.range.start());
+    let end = source.position(label.range.end());
+
+    let prefix: String = text.chars().take(start.column - 1).collect();
+    let covered: String = if end.line == start.line {
+        text.chars()
+            .skip(start.column - 1)
+            .take(end.column - start.column)
+            .collect()
+    } else {
+        text.chars().skip(start.column - 1).collect()
+    };
+
+    let marker = if label.primary { "^" } else { "-" };
+    let mut out = " ".repeat(display_width(&prefix));
+    out.push_str(&marker.repeat(display_width(&covered).max(1)));
+
+    if !label.message.is_empty() {
+        out.push(' ');
+        out.push_str(&label.message);
+    }
+    out
+}
+
+fn expand_tabs(text: &str) -> String {
+    text.replace('\t', &" ".repeat(TAB_WIDTH))
+}
+
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|x| if x == '\t' { TAB_WIDTH } else { 1 })
+        .sum()
+}
@@ -0,0 +1,402 @@
+use crate::scope::Scope;
+use crate::{check_expression, lower_type, Diagnostic, Type, TypeError};
+use ast::annotations::Annotation;
+use ast::items::{AnnotationDefinition, File, Item, UnionMember};
+use ast::Yip;
+use rowan::ast::{support, AstChildren, AstNode};
+
+/// The kinds of items and members an annotation can be placed on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnnotationTarget {
+    Import,
+    Plugin,
+    Struct,
+    Union,
+    Enum,
+    Alias,
+    Handle,
+    Annotation,
+    Interface,
+    Const,
+    Module,
+    Use,
+    Function,
+    Parameter,
+    Field,
+    Variant,
+}
+
+impl AnnotationTarget {
+    /// Parses a target as written after `on` in an annotation declaration.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "import" => AnnotationTarget::Import,
+            "plugin" => AnnotationTarget::Plugin,
+            "struct" => AnnotationTarget::Struct,
+            "union" => AnnotationTarget::Union,
+            "enum" => AnnotationTarget::Enum,
+            "type" => AnnotationTarget::Alias,
+            "handle" => AnnotationTarget::Handle,
+            "annotation" => AnnotationTarget::Annotation,
+            "interface" => AnnotationTarget::Interface,
+            "const" => AnnotationTarget::Const,
+            "mod" => AnnotationTarget::Module,
+            "use" => AnnotationTarget::Use,
+            "fn" => AnnotationTarget::Function,
+            "param" => AnnotationTarget::Parameter,
+            "field" => AnnotationTarget::Field,
+            "variant" => AnnotationTarget::Variant,
+            _ => return None,
+        })
+    }
+
+    /// The name of the target as written after `on`.
+    pub fn name(self) -> &'static str {
+        match self {
+            AnnotationTarget::Import => "import",
+            AnnotationTarget::Plugin => "plugin",
+            AnnotationTarget::Struct => "struct",
+            AnnotationTarget::Union => "union",
+            AnnotationTarget::Enum => "enum",
+            AnnotationTarget::Alias => "type",
+            AnnotationTarget::Handle => "handle",
+            AnnotationTarget::Annotation => "annotation",
+            AnnotationTarget::Interface => "interface",
+            AnnotationTarget::Const => "const",
+            AnnotationTarget::Module => "mod",
+            AnnotationTarget::Use => "use",
+            AnnotationTarget::Function => "fn",
+            AnnotationTarget::Parameter => "param",
+            AnnotationTarget::Field => "field",
+            AnnotationTarget::Variant => "variant",
+        }
+    }
+}
+
+struct Declaration {
+    parameters: Vec<(String, Type)>,
+    /// Empty if the annotation is allowed everywhere
+    targets: Vec<AnnotationTarget>,
+}
+
+impl Declaration {
+    /// The parameters and targets of `definition`, whose types are resolved from `scope`. Unknown
+    /// targets are reported with the declaration and left out here.
+    fn new(definition: &AnnotationDefinition, scope: &Scope) -> Self {
+        Declaration {
+            parameters: definition
+                .parameters()
+                .map(|x| (x.name().text().to_string(), lower_type(x.ty(), scope)))
+                .collect(),
+            targets: definition
+                .targets()
+                .filter_map(|x| AnnotationTarget::from_name(x.token().text()))
+                .collect(),
+        }
+    }
+}
+
+/// Checks every annotation in `file` against the `annotation` declarations it refers to.
+///
+/// Annotations are looked up relative to the module they are used in, then in each enclosing
+/// module, so `@rust::derive` finds `annotation derive` declared in `mod rust`. Paths are resolved
+/// from `scope`, the top level of the file, and may lead into imported files.
+pub fn check_annotations(file: &File, scope: &Scope) -> Vec<Diagnostic> {
+    let mut checker = Checker {
+        diagnostics: Vec::new(),
+    };
+
+    checker.check_declarations(file.items());
+    checker.check_items(file.items(), scope);
+
+    checker.diagnostics
+}
+
+struct Checker {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Checker {
+    fn check_declarations(&mut self, items: AstChildren<Item>) {
+        for item in items {
+            match item {
+                Item::Annotation(definition) => {
+                    for target in definition.targets() {
+                        let name = target.token().text().to_string();
+                        if AnnotationTarget::from_name(&name).is_none() {
+                            self.diagnostics.push(Diagnostic {
+                                error: TypeError::UnknownAnnotationTarget {
+                                    name,
+                                    range: target.text_range(),
+                                },
+                                item: definition.syntax().text_range(),
+                            });
+                        }
+                    }
+                }
+                Item::Module(module) => self.check_declarations(module.items()),
+                _ => {}
+            }
+        }
+    }
+
+    fn check_items(&mut self, items: AstChildren<Item>, scope: &Scope) {
+        for item in items {
+            match item {
+                Item::Import(x) => self.check(&x, AnnotationTarget::Import, scope),
+                Item::Plugin(x) => self.check(&x, AnnotationTarget::Plugin, scope),
+                Item::Struct(x) => {
+                    self.check(&x, AnnotationTarget::Struct, scope);
+                    for member in x.members() {
+                        self.check(&member, AnnotationTarget::Field, scope);
+                    }
+                }
+                Item::Union(x) => {
+                    self.check(&x, AnnotationTarget::Union, scope);
+                    for member in x.members() {
+                        self.check(&member, AnnotationTarget::Variant, scope);
+
+                        if let UnionMember::Anonymous(x) = member {
+                            for member in x.members() {
+                                self.check(&member, AnnotationTarget::Field, scope);
+                            }
+                        }
+                    }
+                }
+                Item::Enum(x) => {
+                    self.check(&x, AnnotationTarget::Enum, scope);
+                    for member in x.members() {
+                        self.check(&member, AnnotationTarget::Variant, scope);
+                    }
+                }
+                Item::Alias(x) => self.check(&x, AnnotationTarget::Alias, scope),
+                Item::Handle(x) => self.check(&x, AnnotationTarget::Handle, scope),
+                Item::Annotation(x) => self.check(&x, AnnotationTarget::Annotation, scope),
+                Item::Interface(x) => {
+                    self.check(&x, AnnotationTarget::Interface, scope);
+                    for function in x.functions() {
+                        self.check(&function, AnnotationTarget::Function, scope);
+                        for parameter in function.parameters() {
+                            self.check(&parameter, AnnotationTarget::Parameter, scope);
+                        }
+                    }
+                }
+                Item::Const(x) => self.check(&x, AnnotationTarget::Const, scope),
+                Item::Module(x) => {
+                    self.check(&x, AnnotationTarget::Module, scope);
+
+                    if let Some(scope) = scope.module(x.name().text()) {
+                        self.check_items(x.items(), &scope);
+                    }
+                }
+                Item::Use(x) => self.check(&x, AnnotationTarget::Use, scope),
+            }
+        }
+    }
+
+    /// Checks the annotations placed on `item`.
+    fn check(
+        &mut self,
+        item: &impl AstNode<Language = Yip>,
+        target: AnnotationTarget,
+        scope: &Scope,
+    ) {
+        let range = item.syntax().text_range();
+        for annotation in support::children::<Annotation>(item.syntax()) {
+            for error in self.check_annotation(annotation, target, scope) {
+                self.diagnostics.push(Diagnostic { error, item: range });
+            }
+        }
+    }
+
+    fn check_annotation(
+        &self,
+        annotation: Annotation,
+        target: AnnotationTarget,
+        scope: &Scope,
+    ) -> Vec<TypeError> {
+        let name = annotation.name();
+        let range = annotation.text_range();
+
+        let segments: Vec<_> = annotation.segments().collect();
+        let segments: Vec<_> = segments.iter().map(|x| x.text()).collect();
+        let Some((definition, declared)) = scope.annotation(&segments) else {
+            return vec![TypeError::UnknownAnnotation { name, range }];
+        };
+        let declaration = Declaration::new(&definition, &declared);
+
+        let mut errors = Vec::new();
+        if !declaration.targets.is_empty() && !declaration.targets.contains(&target) {
+            errors.push(TypeError::AnnotationNotAllowed {
+                name: name.clone(),
+                target,
+                range,
+            });
+        }
+
+        // Arguments are resolved from the module of the annotated item
+        let parameters = &declaration.parameters;
+        let mut bound = vec![false; parameters.len()];
+
+        let arguments: Vec<_> = annotation.arguments().collect();
+        let extra = arguments.get(parameters.len()..).unwrap_or_default();
+        if let (Some(first), Some(last)) = (extra.first(), extra.last()) {
+            errors.push(TypeError::TooManyAnnotationArguments {
+                expected: parameters.len(),
+                actual: arguments.len(),
+                range: first.text_range().cover(last.text_range()),
+            });
+        }
+
+        for (idx, argument) in arguments.into_iter().take(parameters.len()).enumerate() {
+            check_expression(argument, &parameters[idx].1, scope, &mut errors);
+            bound[idx] = true;
+        }
+
+        for argument in annotation.named_arguments() {
+            let name = argument.name().text().to_string();
+            let Some(idx) = parameters.iter().position(|(x, _)| *x == name) else {
+                errors.push(TypeError::UnknownAnnotationArgument {
+                    name,
+                    range: argument.text_range(),
+                });
+                continue;
+            };
+
+            if bound[idx] {
+                errors.push(TypeError::DuplicateAnnotationArgument {
+                    name,
+                    range: argument.text_range(),
+                });
+                continue;
+            }
+
+            if let Some(value) = argument.value() {
+                check_expression(value, &parameters[idx].1, scope, &mut errors);
+            }
+            bound[idx] = true;
+        }
+
+        // Optional parameters may be left out
+        for ((name, ty), bound) in parameters.iter().zip(bound) {
+            if !bound && !matches!(ty, Type::Option(_)) {
+                errors.push(TypeError::MissingAnnotationArgument {
+                    name: name.clone(),
+                    range,
+                });
+            }
+        }
+
+        errors
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{check_annotations, AnnotationTarget};
+    use crate::{with_root, TypeError};
+
+    fn check(input: &str) -> Vec<TypeError> {
+        with_root(input, |file, scope| {
+            check_annotations(file, scope)
+                .into_iter()
+                .map(|x| x.error)
+                .collect()
+        })
+    }
+
+    #[test]
+    fn test_valid() {
+        let errors = check(
+            r#"annotation deprecated(reason: string, since: ?u32) on struct, fn
+            annotation inline
+
+            mod rust {
+                annotation derive(a: string, b: ?string)
+            }
+
+            @deprecated("old", since = 2)
+            @rust::derive("Debug")
+            struct A {
+                @inline
+                a: bool
+            }
+
+            interface B {
+                @deprecated(reason = "old")
+                fn b()
+            }"#,
+        );
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn test_unknown() {
+        let errors = check("@deprecatd struct A {}\nannotation a on strct");
+
+        assert_eq!(errors.len(), 2);
+        assert!(
+            matches!(&errors[0], TypeError::UnknownAnnotationTarget { name, .. } if name == "strct")
+        );
+        assert!(
+            matches!(&errors[1], TypeError::UnknownAnnotation { name, .. } if name == "deprecatd")
+        );
+    }
+
+    #[test]
+    fn test_target() {
+        let errors = check("annotation a on struct\n@a enum A {}\n@a struct B {}");
+
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            errors[0],
+            TypeError::AnnotationNotAllowed {
+                target: AnnotationTarget::Enum,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_arguments() {
+        let errors = check(
+            r#"annotation a(b: string, c: ?bool)
+            @a("b", true, 1)
+            @a(c = true)
+            @a("b", d = 1, b = "b")
+            @a(1)
+            handle A"#,
+        );
+
+        assert_eq!(errors.len(), 5);
+        assert_eq!(
+            errors[0].to_string(),
+            "annotation takes 2 arguments, but 3 were given"
+        );
+        assert!(matches!(
+            errors[0],
+            TypeError::TooManyAnnotationArguments {
+                expected: 2,
+                actual: 3,
+                ..
+            }
+        ));
+        assert!(
+            matches!(&errors[1], TypeError::MissingAnnotationArgument { name, .. } if name == "b")
+        );
+        assert!(
+            matches!(&errors[2], TypeError::UnknownAnnotationArgument { name, .. } if name == "d")
+        );
+        assert!(
+            matches!(&errors[3], TypeError::DuplicateAnnotationArgument { name, .. } if name == "b")
+        );
+        assert!(matches!(errors[4], TypeError::UnexpectedType { .. }));
+
+        let errors = check("annotation a(x: u8)\n@a(1, 2, 3, 4)\nhandle A");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].to_string(),
+            "annotation takes 1 argument, but 4 were given"
+        );
+    }
+}
@@ -0,0 +1,370 @@
+use crate::eval::evaluate_within;
+use crate::lower::enum_representation;
+use crate::primitive::check_bounds;
+use crate::scope::Scope;
+use crate::{
+    check_annotations, check_expression, evaluate, infer_type, lower_type, ConstValue, Diagnostic,
+    Type, TypeError,
+};
+use ast::items::{ConstDefinition, EnumDefinition, File, Item};
+use rowan::ast::{AstChildren, AstNode};
+
+/// Checks every constant, enum and annotation in `file`.
+///
+/// Constants are checked against their declared type, or must at least have a type that can be
+/// inferred. Enum discriminants must fit the representation of their enum. Every error in an item
+/// is reported, not just the first. Diagnostics are sorted by the position of the item they were
+/// found in. Paths are resolved from `scope`, the top level of the file.
+pub fn check_file(file: &File, scope: &Scope) -> Vec<Diagnostic> {
+    let mut diagnostics = check_annotations(file, scope);
+
+    check_items(file.items(), scope, &mut diagnostics);
+
+    diagnostics.sort_by_key(|x| x.item.start());
+    diagnostics
+}
+
+fn check_items(items: AstChildren<Item>, scope: &Scope, diagnostics: &mut Vec<Diagnostic>) {
+    for item in items {
+        match item {
+            Item::Const(constant) => {
+                for error in check_const(&constant, scope) {
+                    diagnostics.push(Diagnostic {
+                        error,
+                        item: constant.syntax().text_range(),
+                    });
+                }
+            }
+            Item::Enum(definition) => {
+                for error in check_enum(&definition, scope) {
+                    diagnostics.push(Diagnostic {
+                        error,
+                        item: definition.syntax().text_range(),
+                    });
+                }
+            }
+            Item::Module(module) => {
+                if let Some(scope) = scope.module(module.name().text()) {
+                    check_items(module.items(), &scope, diagnostics);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn check_const(constant: &ConstDefinition, scope: &Scope) -> Vec<TypeError> {
+    let expression = constant.expression();
+    let mut errors = Vec::new();
+    match constant.ty() {
+        Some(ty) => {
+            let ty = lower_type(ty, scope);
+            check_expression(expression.clone(), &ty, scope, &mut errors)
+        }
+        None => infer_type(expression.clone(), scope, &mut errors),
+    };
+
+    // Catches errors only visible in the value, like a division by zero
+    if errors.is_empty() {
+        if let Err(err) = evaluate_within(expression, scope) {
+            errors.push(err);
+        }
+    }
+    errors
+}
+
+fn check_enum(definition: &EnumDefinition, scope: &Scope) -> Vec<TypeError> {
+    let representation = enum_representation(definition, scope);
+    let primitive = match representation {
+        Type::Primitive(primitive) if primitive.bounds().is_some() => primitive,
+        ty => {
+            let range = match definition.representation() {
+                Some(x) => x.syntax().text_range(),
+                None => definition.name().text_range(),
+            };
+            return vec![TypeError::InvalidEnumRepresentation { ty, range }];
+        }
+    };
+
+    let mut errors = Vec::new();
+    // The value of the previous member, or None if it couldn't be computed
+    let mut previous = Some(ConstValue::SignedInteger(-1));
+
+    for member in definition.members() {
+        // None if the value can't be known without reporting more errors
+        let value = match member.expression() {
+            Some(expression) => {
+                let count = errors.len();
+                check_expression(expression.clone(), &representation, scope, &mut errors);
+                if errors.len() > count {
+                    None
+                } else {
+                    evaluate_within(expression, scope).transpose()
+                }
+            }
+            // Members without a value follow the previous one
+            None => previous.map(|previous| {
+                let range = member.name().text_range();
+                let value = match previous {
+                    ConstValue::Integer(x) => x.checked_add(1).map(ConstValue::Integer),
+                    ConstValue::SignedInteger(x) => Some(ConstValue::signed(x + 1)),
+                    _ => None,
+                };
+
+                value
+                    .ok_or(TypeError::IntegerOutOfRange {
+                        ty: primitive,
+                        range,
+                    })
+                    .and_then(|value| {
+                        check_bounds(&value, &representation, range)?;
+                        Ok(value)
+                    })
+            }),
+        };
+
+        previous = match value {
+            Some(Ok(value)) => Some(value),
+            Some(Err(error)) => {
+                errors.push(error);
+                None
+            }
+            None => None,
+        };
+    }
+
+    errors
+}
+
+/// The discriminant of each member of an enum, or `None` where it can't be computed because the
+/// enum doesn't type check. Members without a value follow the previous one, starting at zero.
+pub fn discriminants(definition: &EnumDefinition, scope: &Scope) -> Vec<Option<ConstValue>> {
+    let mut previous = Some(ConstValue::SignedInteger(-1));
+
+    definition
+        .members()
+        .map(|member| {
+            let value = match member.expression() {
+                Some(expression) => evaluate(expression, scope).ok(),
+                None => match previous {
+                    Some(ConstValue::Integer(x)) => x.checked_add(1).map(ConstValue::Integer),
+                    Some(ConstValue::SignedInteger(x)) => Some(ConstValue::signed(x + 1)),
+                    _ => None,
+                },
+            };
+            previous = value.clone();
+            value
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{check_file, discriminants};
+    use crate::{with_root, ConstValue, Primitive, Type, TypeError};
+    use ast::items::Item;
+
+    fn check(input: &str) -> Vec<TypeError> {
+        with_root(input, |file, scope| {
+            check_file(file, scope)
+                .into_iter()
+                .map(|x| x.error)
+                .collect()
+        })
+    }
+
+    #[test]
+    fn test_valid() {
+        let errors = check(
+            r#"const A: u8 = 255
+            const B = A - 200
+            const C: ?string = none
+            const Z: bool = 1 < 2
+
+            mod m {
+                const D: [i8] = [-1, super_value]
+                const super_value = 2
+            }
+
+            enum E: u8 {
+                X = 254
+                Y
+            }
+
+            enum F: u8 {
+                X = A
+                Y = E::X
+            }
+
+            enum G {
+                X
+                Y = 5
+            }"#,
+        );
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn test_constants() {
+        let errors = check(
+            r#"const N: bool = "yes"
+            const M = 1 / 0
+            const O = 1 + "a"
+            const P: u16 = 70000"#,
+        );
+
+        assert_eq!(errors.len(), 4);
+        assert!(matches!(
+            errors[0],
+            TypeError::UnexpectedType {
+                expected: Type::Boolean,
+                actual: Type::String,
+                ..
+            }
+        ));
+        assert!(matches!(errors[1], TypeError::DivisionByZero { .. }));
+        assert!(matches!(errors[2], TypeError::NoCommonType { .. }));
+        assert!(matches!(
+            errors[3],
+            TypeError::IntegerOutOfRange {
+                ty: Primitive::U16,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_every_error() {
+        let errors = check(
+            r#"const A: [u8] = [1, "a", true, 2]
+            const B = A
+            const C = (1 + "a", -true)
+            const D = C"#,
+        );
+
+        assert_eq!(errors.len(), 4);
+        assert!(matches!(
+            errors[0],
+            TypeError::NoCommonType {
+                rhs: Type::String,
+                ..
+            }
+        ));
+        assert!(matches!(
+            errors[1],
+            TypeError::NoCommonType {
+                rhs: Type::Boolean,
+                ..
+            }
+        ));
+        assert!(matches!(errors[2], TypeError::NoCommonType { .. }));
+        assert!(matches!(errors[3], TypeError::UnexpectedTypes { .. }));
+    }
+
+    #[test]
+    fn test_enums() {
+        let errors = check(
+            r#"enum A: u8 {
+                X = 255
+                Y
+            }
+
+            enum B: i8 {
+                X = -129
+                Y
+                Z = true
+            }
+
+            enum C: string {}"#,
+        );
+
+        assert_eq!(errors.len(), 4);
+        assert!(matches!(
+            errors[0],
+            TypeError::IntegerOutOfRange {
+                ty: Primitive::U8,
+                ..
+            }
+        ));
+        assert!(matches!(
+            errors[1],
+            TypeError::IntegerOutOfRange {
+                ty: Primitive::I8,
+                ..
+            }
+        ));
+        assert!(matches!(errors[2], TypeError::UnexpectedType { .. }));
+        assert!(matches!(
+            errors[3],
+            TypeError::InvalidEnumRepresentation { .. }
+        ));
+    }
+
+    #[test]
+    fn test_aliases() {
+        let errors = check(
+            r#"type Byte = u8
+            type Bytes = [Byte]
+            const A: Byte = 255
+            const B: Bytes = [1, 2]
+
+            enum E: Byte {
+                X = 255
+            }
+
+            struct u16 {}
+            const C: u16 = "shadowed"
+
+            type Cycle = Loop
+            type Loop = Cycle
+            const D: Cycle = 1"#,
+        );
+        assert!(errors.is_empty());
+
+        let errors = check("type Byte = u8\nconst A: Byte = 300\nenum E: Byte {\nX = 256\n}");
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(
+            errors[0],
+            TypeError::IntegerOutOfRange {
+                ty: Primitive::U8,
+                ..
+            }
+        ));
+        assert!(matches!(
+            errors[1],
+            TypeError::IntegerOutOfRange {
+                ty: Primitive::U8,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_discriminants() {
+        let input = "const B = 4\nenum E: i8 {\nA = -2\nB\nC = B + 1\nD\n}";
+        let values = with_root(input, |file, scope| {
+            let Some(Item::Enum(definition)) = file.items().nth(1) else {
+                panic!("Expected an enum");
+            };
+            discriminants(&definition, scope)
+        });
+
+        assert_eq!(
+            values,
+            vec![
+                Some(ConstValue::SignedInteger(-2)),
+                Some(ConstValue::SignedInteger(-1)),
+                Some(ConstValue::Integer(5)),
+                Some(ConstValue::Integer(6)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_annotations() {
+        let errors = check("annotation a(b: u8)\n@a(256)\nconst A = 1");
+
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0], TypeError::IntegerOutOfRange { .. }));
+    }
+}
@@ -0,0 +1,549 @@
+use crate::scope::Scope;
+use crate::{Result, Type, TypeError};
+use ast::expression::{
+    Expression, IfExpression, InfixOperator, InfixOperatorType, Literal, PathLiteral,
+    UnaryOperator, UnaryOperatorType,
+};
+use rowan::ast::AstNode;
+use std::cmp::Ordering;
+use text_size::TextRange;
+
+const NUMBER_TYPES: &[Type] = &[Type::Integer, Type::SignedInteger, Type::Float];
+const INTEGER_TYPES: &[Type] = &[Type::Integer, Type::SignedInteger];
+const NOT_TYPES: &[Type] = &[Type::Boolean, Type::Integer, Type::SignedInteger];
+
+/// The value of a constant expression.
+///
+/// Integers are kept in [`ConstValue::Integer`] whenever they are non-negative, so
+/// [`ConstValue::SignedInteger`] always holds a negative number.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstValue {
+    Boolean(bool),
+    String(String),
+    Integer(u128),
+    SignedInteger(i128),
+    Float(f64),
+    None,
+    Error(Box<ConstValue>),
+    Tuple(Vec<ConstValue>),
+    List(Vec<ConstValue>),
+}
+
+impl ConstValue {
+    pub(crate) fn signed(value: i128) -> Self {
+        if value < 0 {
+            ConstValue::SignedInteger(value)
+        } else {
+            ConstValue::Integer(value as u128)
+        }
+    }
+
+    /// The type of the value, as [`crate::infer_type`] would infer it.
+    pub fn ty(&self) -> Type {
+        match self {
+            ConstValue::Boolean(_) => Type::Boolean,
+            ConstValue::String(_) => Type::String,
+            ConstValue::Integer(_) => Type::Integer,
+            ConstValue::SignedInteger(_) => Type::SignedInteger,
+            ConstValue::Float(_) => Type::Float,
+            ConstValue::None => Type::Option(Box::new(Type::Any)),
+            ConstValue::Error(x) => Type::Result(Box::new(Type::Any), Box::new(x.ty())),
+            ConstValue::Tuple(x) => Type::Tuple(x.iter().map(ConstValue::ty).collect()),
+            ConstValue::List(x) => {
+                Type::List(Box::new(x.first().map(ConstValue::ty).unwrap_or(Type::Any)))
+            }
+        }
+    }
+
+    fn as_i128(&self, range: TextRange) -> Result<i128> {
+        match *self {
+            ConstValue::Integer(x) => i128::try_from(x).map_err(|_| TypeError::Overflow { range }),
+            ConstValue::SignedInteger(x) => Ok(x),
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Reduces a constant expression to its value, resolving paths from `scope`.
+pub fn evaluate(expr: Expression, scope: &Scope) -> Result<ConstValue> {
+    Evaluator { scope: *scope }.evaluate(expr)
+}
+
+/// Evaluates `expr` like [`evaluate`], but ignores errors outside of it. Those are found in the
+/// constants `expr` refers to and are reported when those are checked, so `None` is returned.
+pub(crate) fn evaluate_within(expr: Expression, scope: &Scope) -> Result<Option<ConstValue>> {
+    let range = expr.text_range();
+    match evaluate(expr, scope) {
+        Ok(value) => Ok(Some(value)),
+        Err(err) if range.contains_range(err.range()) => Err(err),
+        Err(_) => Ok(None),
+    }
+}
+
+pub(crate) fn path_name(path: &PathLiteral) -> String {
+    path.segments()
+        .map(|x| x.text().to_string())
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+pub(crate) struct Evaluator<'a> {
+    pub(crate) scope: Scope<'a>,
+}
+
+impl Evaluator<'_> {
+    pub(crate) fn evaluate(&self, expr: Expression) -> Result<ConstValue> {
+        match expr {
+            Expression::If(expr) => self.evaluate_if(expr),
+            Expression::Infix(infix) => self.evaluate_infix(infix),
+            Expression::Unary(unary) => self.evaluate_unary(unary),
+            Expression::Error(error) => {
+                Ok(ConstValue::Error(Box::new(self.evaluate(error.inner())?)))
+            }
+            Expression::Tuple(tuple) => Ok(ConstValue::Tuple(
+                tuple
+                    .expressions()
+                    .map(|x| self.evaluate(x))
+                    .collect::<Result<_>>()?,
+            )),
+            Expression::Group(group) => self.evaluate(group.inner()),
+            Expression::List(list) => Ok(ConstValue::List(
+                list.expressions()
+                    .map(|x| self.evaluate(x))
+                    .collect::<Result<_>>()?,
+            )),
+            Expression::Literal(lit) => self.evaluate_literal(lit),
+        }
+    }
+
+    fn evaluate_literal(&self, lit: Literal) -> Result<ConstValue> {
+        match lit {
+            Literal::Boolean(x) => Ok(ConstValue::Boolean(x.value())),
+            Literal::None(_) => Ok(ConstValue::None),
+            Literal::String(x) => x
+                .value()
+                .map(ConstValue::String)
+                .map_err(|err| TypeError::InvalidEscape { range: err.range }),
+            Literal::Integer(x) => x
+                .value()
+                .map(ConstValue::Integer)
+                .map_err(|err| TypeError::Overflow { range: err.range }),
+            Literal::Float(x) => Ok(ConstValue::Float(x.value())),
+            Literal::Path(x) => self.scope.value_of(&x),
+        }
+    }
+
+    /// Only the branch selected by the condition is evaluated.
+    fn evaluate_if(&self, expr: IfExpression) -> Result<ConstValue> {
+        let condition = expr.condition();
+        let range = condition.text_range();
+
+        match self.evaluate(condition)? {
+            ConstValue::Boolean(true) => self.evaluate(expr.then()),
+            ConstValue::Boolean(false) => self.evaluate(expr.else_()),
+            actual => Err(TypeError::UnexpectedType {
+                expected: Type::Boolean,
+                actual: actual.ty(),
+                range,
+            }),
+        }
+    }
+
+    fn evaluate_unary(&self, unary: UnaryOperator) -> Result<ConstValue> {
+        let inner = unary.inner();
+        let inner_range = inner.text_range();
+        let range = unary.syntax().text_range();
+
+        let value = self.evaluate(inner)?;
+        match (unary.op_type(), value) {
+            (
+                UnaryOperatorType::Plus,
+                value @ (ConstValue::Integer(_)
+                | ConstValue::SignedInteger(_)
+                | ConstValue::Float(_)),
+            ) => Ok(value),
+            (UnaryOperatorType::Minus, ConstValue::Integer(x)) => 0i128
+                .checked_sub_unsigned(x)
+                .map(ConstValue::signed)
+                .ok_or(TypeError::Overflow { range }),
+            (UnaryOperatorType::Minus, ConstValue::SignedInteger(x)) => x
+                .checked_neg()
+                .map(ConstValue::signed)
+                .ok_or(TypeError::Overflow { range }),
+            (UnaryOperatorType::Minus, ConstValue::Float(x)) => Ok(ConstValue::Float(-x)),
+            (UnaryOperatorType::Not, ConstValue::Boolean(x)) => Ok(ConstValue::Boolean(!x)),
+            (UnaryOperatorType::Not, ConstValue::Integer(x)) => Ok(ConstValue::Integer(!x)),
+            (UnaryOperatorType::Not, ConstValue::SignedInteger(x)) => Ok(ConstValue::signed(!x)),
+            (UnaryOperatorType::Not, actual) => Err(TypeError::UnexpectedTypes {
+                expected: NOT_TYPES,
+                actual: actual.ty(),
+                range: inner_range,
+            }),
+            (_, actual) => Err(TypeError::UnexpectedTypes {
+                expected: NUMBER_TYPES,
+                actual: actual.ty(),
+                range: inner_range,
+            }),
+        }
+    }
+
+    fn evaluate_infix(&self, infix: InfixOperator) -> Result<ConstValue> {
+        let range = infix.text_range();
+
+        let lhs = infix.lhs();
+        let lhs_range = lhs.text_range();
+        let lhs = self.evaluate(lhs)?;
+
+        let rhs = infix.rhs();
+        let rhs_range = rhs.text_range();
+        let rhs = self.evaluate(rhs)?;
+
+        let op = infix.op_type();
+        match op {
+            InfixOperatorType::OptionCoalescing => match lhs {
+                ConstValue::None => Ok(rhs),
+                lhs => Ok(lhs),
+            },
+            InfixOperatorType::LogicalAnd | InfixOperatorType::LogicalOr => {
+                let lhs = expect_boolean(lhs, lhs_range)?;
+                let rhs = expect_boolean(rhs, rhs_range)?;
+                Ok(ConstValue::Boolean(
+                    if op == InfixOperatorType::LogicalAnd {
+                        lhs && rhs
+                    } else {
+                        lhs || rhs
+                    },
+                ))
+            }
+            InfixOperatorType::Equals => Ok(ConstValue::Boolean(lhs == rhs)),
+            InfixOperatorType::NotEquals => Ok(ConstValue::Boolean(lhs != rhs)),
+            InfixOperatorType::LessThan
+            | InfixOperatorType::GreaterThan
+            | InfixOperatorType::LessThanEquals
+            | InfixOperatorType::GreaterThanEquals => {
+                let ordering = compare(&lhs, &rhs, lhs_range, rhs_range, range)?;
+                Ok(ConstValue::Boolean(match op {
+                    InfixOperatorType::LessThan => ordering == Some(Ordering::Less),
+                    InfixOperatorType::GreaterThan => ordering == Some(Ordering::Greater),
+                    InfixOperatorType::LessThanEquals => {
+                        matches!(ordering, Some(Ordering::Less | Ordering::Equal))
+                    }
+                    _ => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+                }))
+            }
+            InfixOperatorType::BitwiseAnd
+            | InfixOperatorType::BitwiseOr
+            | InfixOperatorType::BitwiseXor => {
+                expect_types(&lhs, INTEGER_TYPES, lhs_range)?;
+                expect_types(&rhs, INTEGER_TYPES, rhs_range)?;
+
+                if let (ConstValue::Integer(lhs), ConstValue::Integer(rhs)) = (&lhs, &rhs) {
+                    return Ok(ConstValue::Integer(match op {
+                        InfixOperatorType::BitwiseAnd => lhs & rhs,
+                        InfixOperatorType::BitwiseOr => lhs | rhs,
+                        _ => lhs ^ rhs,
+                    }));
+                }
+
+                let lhs = lhs.as_i128(lhs_range)?;
+                let rhs = rhs.as_i128(rhs_range)?;
+                Ok(ConstValue::signed(match op {
+                    InfixOperatorType::BitwiseAnd => lhs & rhs,
+                    InfixOperatorType::BitwiseOr => lhs | rhs,
+                    _ => lhs ^ rhs,
+                }))
+            }
+            InfixOperatorType::ShiftLeft | InfixOperatorType::ShiftRight => {
+                expect_types(&lhs, INTEGER_TYPES, lhs_range)?;
+                let amount = match rhs {
+                    ConstValue::Integer(x) if x < 128 => x as u32,
+                    ConstValue::Integer(_) | ConstValue::SignedInteger(_) => {
+                        return Err(TypeError::ShiftOutOfRange { range: rhs_range })
+                    }
+                    actual => {
+                        return Err(TypeError::UnexpectedType {
+                            expected: Type::Integer,
+                            actual: actual.ty(),
+                            range: rhs_range,
+                        })
+                    }
+                };
+
+                let left = op == InfixOperatorType::ShiftLeft;
+                match lhs {
+                    ConstValue::Integer(x) if left => {
+                        let result = x << amount;
+                        if result >> amount != x {
+                            return Err(TypeError::Overflow { range });
+                        }
+                        Ok(ConstValue::Integer(result))
+                    }
+                    ConstValue::Integer(x) => Ok(ConstValue::Integer(x >> amount)),
+                    ConstValue::SignedInteger(x) if left => {
+                        let result = x << amount;
+                        if result >> amount != x {
+                            return Err(TypeError::Overflow { range });
+                        }
+                        Ok(ConstValue::signed(result))
+                    }
+                    ConstValue::SignedInteger(x) => Ok(ConstValue::signed(x >> amount)),
+                    _ => unreachable!(),
+                }
+            }
+            InfixOperatorType::Plus
+            | InfixOperatorType::Minus
+            | InfixOperatorType::Multiply
+            | InfixOperatorType::Divide
+            | InfixOperatorType::Modulo => {
+                if let (ConstValue::String(lhs), ConstValue::String(rhs)) = (&lhs, &rhs) {
+                    if op == InfixOperatorType::Plus {
+                        return Ok(ConstValue::String(format!("{}{}", lhs, rhs)));
+                    }
+                }
+
+                expect_types(&lhs, NUMBER_TYPES, lhs_range)?;
+                expect_types(&rhs, NUMBER_TYPES, rhs_range)?;
+                arithmetic(op, lhs, rhs, rhs_range, range)
+            }
+        }
+    }
+}
+
+fn expect_boolean(value: ConstValue, range: TextRange) -> Result<bool> {
+    match value {
+        ConstValue::Boolean(x) => Ok(x),
+        actual => Err(TypeError::UnexpectedType {
+            expected: Type::Boolean,
+            actual: actual.ty(),
+            range,
+        }),
+    }
+}
+
+fn expect_types(value: &ConstValue, expected: &'static [Type], range: TextRange) -> Result<()> {
+    let actual = value.ty();
+    if expected.contains(&actual) {
+        Ok(())
+    } else {
+        Err(TypeError::UnexpectedTypes {
+            expected,
+            actual,
+            range,
+        })
+    }
+}
+
+fn compare(
+    lhs: &ConstValue,
+    rhs: &ConstValue,
+    lhs_range: TextRange,
+    rhs_range: TextRange,
+    range: TextRange,
+) -> Result<Option<Ordering>> {
+    expect_types(lhs, NUMBER_TYPES, lhs_range)?;
+    expect_types(rhs, NUMBER_TYPES, rhs_range)?;
+
+    Ok(match (lhs, rhs) {
+        (ConstValue::Integer(lhs), ConstValue::Integer(rhs)) => Some(lhs.cmp(rhs)),
+        (ConstValue::SignedInteger(lhs), ConstValue::SignedInteger(rhs)) => Some(lhs.cmp(rhs)),
+        // Signed integers are always negative
+        (ConstValue::SignedInteger(_), ConstValue::Integer(_)) => Some(Ordering::Less),
+        (ConstValue::Integer(_), ConstValue::SignedInteger(_)) => Some(Ordering::Greater),
+        (ConstValue::Float(lhs), ConstValue::Float(rhs)) => lhs.partial_cmp(rhs),
+        (lhs, rhs) => {
+            return Err(TypeError::NoCommonType {
+                lhs: lhs.ty(),
+                rhs: rhs.ty(),
+                range,
+            })
+        }
+    })
+}
+
+fn arithmetic(
+    op: InfixOperatorType,
+    lhs: ConstValue,
+    rhs: ConstValue,
+    rhs_range: TextRange,
+    range: TextRange,
+) -> Result<ConstValue> {
+    let divides = matches!(op, InfixOperatorType::Divide | InfixOperatorType::Modulo);
+
+    match (lhs, rhs) {
+        (ConstValue::Float(lhs), ConstValue::Float(rhs)) => {
+            if divides && rhs == 0.0 {
+                return Err(TypeError::DivisionByZero { range: rhs_range });
+            }
+
+            let result = match op {
+                InfixOperatorType::Plus => lhs + rhs,
+                InfixOperatorType::Minus => lhs - rhs,
+                InfixOperatorType::Multiply => lhs * rhs,
+                InfixOperatorType::Divide => lhs / rhs,
+                _ => lhs % rhs,
+            };
+
+            if result.is_infinite() && lhs.is_finite() && rhs.is_finite() {
+                Err(TypeError::Overflow { range })
+            } else {
+                Ok(ConstValue::Float(result))
+            }
+        }
+        (ConstValue::Float(_), rhs) | (rhs, ConstValue::Float(_)) => Err(TypeError::NoCommonType {
+            lhs: Type::Float,
+            rhs: rhs.ty(),
+            range,
+        }),
+        (_, ConstValue::Integer(0)) if divides => {
+            Err(TypeError::DivisionByZero { range: rhs_range })
+        }
+        (ConstValue::Integer(lhs), ConstValue::Integer(rhs)) => {
+            let result = match op {
+                InfixOperatorType::Plus => lhs.checked_add(rhs),
+                // A negative difference becomes a signed integer
+                InfixOperatorType::Minus if lhs < rhs => {
+                    return 0i128
+                        .checked_sub_unsigned(rhs - lhs)
+                        .map(ConstValue::signed)
+                        .ok_or(TypeError::Overflow { range })
+                }
+                InfixOperatorType::Minus => lhs.checked_sub(rhs),
+                InfixOperatorType::Multiply => lhs.checked_mul(rhs),
+                InfixOperatorType::Divide => lhs.checked_div(rhs),
+                _ => lhs.checked_rem(rhs),
+            };
+
+            result
+                .map(ConstValue::Integer)
+                .ok_or(TypeError::Overflow { range })
+        }
+        (lhs, rhs) => {
+            let lhs = lhs.as_i128(range)?;
+            let rhs = rhs.as_i128(range)?;
+
+            let result = match op {
+                InfixOperatorType::Plus => lhs.checked_add(rhs),
+                InfixOperatorType::Minus => lhs.checked_sub(rhs),
+                InfixOperatorType::Multiply => lhs.checked_mul(rhs),
+                InfixOperatorType::Divide => lhs.checked_div(rhs),
+                _ => lhs.checked_rem(rhs),
+            };
+
+            result
+                .map(ConstValue::signed)
+                .ok_or(TypeError::Overflow { range })
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{evaluate, ConstValue};
+    use crate::{with_root, Result, TypeError};
+    use ast::items::Item;
+
+    fn eval(input: &str) -> Result<ConstValue> {
+        with_root(&format!("const A = {}", input), |file, scope| {
+            let Some(Item::Const(constant)) = file.items().next() else {
+                panic!("Expected a constant");
+            };
+            evaluate(constant.expression(), scope)
+        })
+    }
+
+    #[test]
+    fn test_literals() {
+        assert_eq!(eval("true").ok(), Some(ConstValue::Boolean(true)));
+        assert_eq!(eval("none").ok(), Some(ConstValue::None));
+        assert_eq!(
+            eval("\"a\\n\"").ok(),
+            Some(ConstValue::String("a\n".into()))
+        );
+        assert_eq!(eval("0xFF").ok(), Some(ConstValue::Integer(255)));
+        assert_eq!(eval("1.5").ok(), Some(ConstValue::Float(1.5)));
+        assert_eq!(
+            eval("(1, [2, 3], error 4)").ok(),
+            Some(ConstValue::Tuple(vec![
+                ConstValue::Integer(1),
+                ConstValue::List(vec![ConstValue::Integer(2), ConstValue::Integer(3)]),
+                ConstValue::Error(Box::new(ConstValue::Integer(4))),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_operators() {
+        assert_eq!(eval("1 + 2 * 3").ok(), Some(ConstValue::Integer(7)));
+        assert_eq!(eval("1 - 3").ok(), Some(ConstValue::SignedInteger(-2)));
+        assert_eq!(eval("-4 / 2").ok(), Some(ConstValue::SignedInteger(-2)));
+        assert_eq!(eval("-4 + 6").ok(), Some(ConstValue::Integer(2)));
+        assert_eq!(eval("7 % 4").ok(), Some(ConstValue::Integer(3)));
+        assert_eq!(eval("1 << 4 | 1").ok(), Some(ConstValue::Integer(17)));
+        assert_eq!(eval("-8 >> 1").ok(), Some(ConstValue::SignedInteger(-4)));
+        assert_eq!(eval("!0").ok(), Some(ConstValue::Integer(u128::MAX)));
+        assert_eq!(eval("-1 < 0").ok(), Some(ConstValue::Boolean(true)));
+        assert_eq!(
+            eval("2 >= 2 && 1 != 2").ok(),
+            Some(ConstValue::Boolean(true))
+        );
+        assert_eq!(eval("none ?? 1").ok(), Some(ConstValue::Integer(1)));
+        assert_eq!(
+            eval("\"a\" + \"b\"").ok(),
+            Some(ConstValue::String("ab".into()))
+        );
+        assert_eq!(eval("1.5 * 2.0").ok(), Some(ConstValue::Float(3.0)));
+        assert_eq!(eval("(1 + 2) * 3").ok(), Some(ConstValue::Integer(9)));
+        assert_eq!(eval("-(1 + 2)").ok(), Some(ConstValue::SignedInteger(-3)));
+        assert_eq!(
+            eval("!(true && false)").ok(),
+            Some(ConstValue::Boolean(true))
+        );
+        assert_eq!(eval("--1").ok(), Some(ConstValue::Integer(1)));
+        assert_eq!(
+            eval("(1,)").ok(),
+            Some(ConstValue::Tuple(vec![ConstValue::Integer(1)]))
+        );
+        assert_eq!(
+            eval("if 1 > 2 then 1 / 0 else 3").ok(),
+            Some(ConstValue::Integer(3))
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(matches!(
+            eval("0xFFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF + 1"),
+            Err(TypeError::Overflow { .. })
+        ));
+        assert!(matches!(
+            eval("1 << 127 << 1"),
+            Err(TypeError::Overflow { .. })
+        ));
+        assert!(matches!(
+            eval("1 / 0"),
+            Err(TypeError::DivisionByZero { .. })
+        ));
+        assert!(matches!(
+            eval("1.0 % 0.0"),
+            Err(TypeError::DivisionByZero { .. })
+        ));
+        assert!(matches!(
+            eval("1 << 128"),
+            Err(TypeError::ShiftOutOfRange { .. })
+        ));
+        assert!(matches!(
+            eval("1 >> -1"),
+            Err(TypeError::ShiftOutOfRange { .. })
+        ));
+        assert!(matches!(
+            eval("1 + true"),
+            Err(TypeError::UnexpectedTypes { .. })
+        ));
+        assert!(matches!(eval("B"), Err(TypeError::UnknownName { .. })));
+
+        let Err(TypeError::DivisionByZero { range }) = eval("10 / (2 - 2)") else {
+            panic!("Expected a division by zero");
+        };
+        assert_eq!(u32::from(range.start()), 15);
+    }
+}
@@ -1,16 +1,32 @@
-use crate::{common_type, infer_type, Result, Type, TypeError};
+use crate::scope::Scope;
+use crate::{common_type, infer_type, recover, Result, Type, TypeError};
 use ast::expression::{InfixOperator, InfixOperatorType};
 use text_size::TextRange;
 
-pub fn infer_infix(infix: InfixOperator) -> Result<Type> {
+const NUMBER_TYPES: &[Type] = &[Type::Integer, Type::SignedInteger, Type::Float];
+
+pub fn infer_infix(infix: InfixOperator, scope: &Scope, errors: &mut Vec<TypeError>) -> Type {
     let lhs = infix.lhs();
     let lhs_range = lhs.text_range();
-    let lhs = infer_type(lhs)?;
+    let lhs = infer_type(lhs, scope, errors);
 
     let rhs = infix.rhs();
     let rhs_range = rhs.text_range();
-    let rhs = infer_type(rhs)?;
+    let rhs = infer_type(rhs, scope, errors);
+
+    recover(
+        infer_operator(&infix, lhs, rhs, lhs_range, rhs_range),
+        errors,
+    )
+}
 
+fn infer_operator(
+    infix: &InfixOperator,
+    lhs: Type,
+    rhs: Type,
+    lhs_range: TextRange,
+    rhs_range: TextRange,
+) -> Result<Type> {
     match infix.op_type() {
         InfixOperatorType::OptionCoalescing => {
             infer_option_coalescing(lhs, rhs, lhs_range, rhs_range)
@@ -34,13 +50,18 @@ pub fn infer_infix(infix: InfixOperator) -> Result<Type> {
         InfixOperatorType::LessThan
         | InfixOperatorType::GreaterThan
         | InfixOperatorType::LessThanEquals
-        | InfixOperatorType::GreaterThanEquals
-        | InfixOperatorType::Minus
+        | InfixOperatorType::GreaterThanEquals => {
+            let lhs = lhs.requires_types(NUMBER_TYPES, lhs_range)?;
+            let rhs = rhs.requires_types(NUMBER_TYPES, rhs_range)?;
+            common_type(lhs, rhs, infix.text_range())?;
+            Ok(Type::Boolean)
+        }
+        InfixOperatorType::Minus
+        | InfixOperatorType::Multiply
         | InfixOperatorType::Divide
         | InfixOperatorType::Modulo => {
-            const TYPES: &[Type] = &[Type::Integer, Type::SignedInteger, Type::Float];
-            let lhs = lhs.requires_types(TYPES, lhs_range)?;
-            let rhs = rhs.requires_types(TYPES, rhs_range)?;
+            let lhs = lhs.requires_types(NUMBER_TYPES, lhs_range)?;
+            let rhs = rhs.requires_types(NUMBER_TYPES, rhs_range)?;
             common_type(lhs, rhs, infix.text_range())
         }
         InfixOperatorType::ShiftLeft | InfixOperatorType::ShiftRight => {
@@ -59,7 +80,6 @@ pub fn infer_infix(infix: InfixOperator) -> Result<Type> {
             let rhs = rhs.requires_types(TYPES, rhs_range)?;
             common_type(lhs, rhs, infix.text_range())
         }
-        InfixOperatorType::Multiply => todo!(),
     }
 }
 
@@ -69,8 +89,15 @@ fn infer_option_coalescing(
     lhs_range: TextRange,
     rhs_range: TextRange,
 ) -> Result<Type> {
+    if lhs == Type::Error {
+        return Ok(Type::Error);
+    }
     let Type::Option(inner) = lhs else {
-        return Err(TypeError::UnexpectedType { expected: Type::Option(Box::new(Type::Any)), actual: lhs, range: lhs_range });
+        return Err(TypeError::UnexpectedType {
+            expected: Type::Option(Box::new(Type::Any)),
+            actual: lhs,
+            range: lhs_range,
+        });
     };
 
     common_type(*inner, rhs, rhs_range)
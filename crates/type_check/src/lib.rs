@@ -1,10 +1,29 @@
+mod annotations;
+mod check;
+mod eval;
 mod infix;
 mod literal;
+mod lower;
+mod primitive;
+mod render;
+mod resolve;
+mod scope;
+#[cfg(test)]
+mod stub;
 mod unary;
 
+pub use annotations::{check_annotations, AnnotationTarget};
+pub use check::{check_file, discriminants};
+pub use eval::{evaluate, ConstValue};
+pub use lower::lower_type;
+pub use primitive::{check_expression, Primitive};
+pub use resolve::{Definition, Resolver, ScopeId};
+pub use scope::{Scope, Scopes};
+
 use ast::expression::{Expression, IfExpression};
 use text_size::TextRange;
 
+#[derive(Debug)]
 pub enum TypeError {
     NoCommonType {
         lhs: Type,
@@ -21,18 +40,93 @@ pub enum TypeError {
         actual: Type,
         range: TextRange,
     },
+    UnknownAnnotation {
+        name: String,
+        range: TextRange,
+    },
+    UnknownAnnotationTarget {
+        name: String,
+        range: TextRange,
+    },
+    AnnotationNotAllowed {
+        name: String,
+        target: AnnotationTarget,
+        range: TextRange,
+    },
+    TooManyAnnotationArguments {
+        expected: usize,
+        actual: usize,
+        range: TextRange,
+    },
+    MissingAnnotationArgument {
+        name: String,
+        range: TextRange,
+    },
+    UnknownAnnotationArgument {
+        name: String,
+        range: TextRange,
+    },
+    DuplicateAnnotationArgument {
+        name: String,
+        range: TextRange,
+    },
+    Overflow {
+        range: TextRange,
+    },
+    DivisionByZero {
+        range: TextRange,
+    },
+    ShiftOutOfRange {
+        range: TextRange,
+    },
+    InvalidEscape {
+        range: TextRange,
+    },
+    UnknownName {
+        name: String,
+        range: TextRange,
+    },
+    NotAValue {
+        name: String,
+        range: TextRange,
+    },
+    CyclicConstant {
+        name: String,
+        range: TextRange,
+    },
+    IntegerOutOfRange {
+        ty: Primitive,
+        range: TextRange,
+    },
+    InvalidEnumRepresentation {
+        ty: Type,
+        range: TextRange,
+    },
+}
+
+/// An error found by [`check_file`].
+pub struct Diagnostic {
+    pub error: TypeError,
+    /// The range of the item that was being checked, like the constant whose expression failed to
+    /// type check or the struct carrying a bad annotation
+    pub item: TextRange,
 }
 
 type Result<T> = std::result::Result<T, TypeError>;
 
-#[derive(PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Type {
     Any,
+    /// The type of an expression that failed to type check. The error has already been recorded,
+    /// so anything combined with it is accepted to avoid reporting the same problem again
+    Error,
     Boolean,
     String,
     Integer,
     SignedInteger,
     Float,
+    /// A sized primitive like `u8` or `f32`, see [`Primitive::ty`]
+    Primitive(Primitive),
     Option(Box<Type>),
     List(Box<Type>),
     Tuple(Vec<Type>),
@@ -55,12 +149,16 @@ impl Type {
     fn requires_types(self, expected: &'static [Type], range: TextRange) -> Result<Type> {
         match self {
             Type::Any => Ok(Type::Any),
+            Type::Error => Ok(Type::Error),
             Type::Option(x) => Ok(Type::Option(Box::new(x.requires_types(expected, range)?))),
             Type::List(x) => Ok(Type::List(Box::new(x.requires_types(expected, range)?))),
             Type::Result(x, error) => Ok(Type::Result(
                 Box::new(x.requires_types(expected, range)?),
                 error,
             )),
+            Type::Primitive(primitive) if primitive.is_any_of(expected) => {
+                Ok(Type::Primitive(primitive))
+            }
             actual => {
                 if expected.contains(&actual) {
                     Ok(actual)
@@ -76,27 +174,44 @@ impl Type {
     }
 }
 
-pub fn infer_type(expr: Expression) -> Result<Type> {
+/// Infers the type of `expr`, resolving paths from `scope`.
+///
+/// Inference doesn't stop at the first error. Every error is pushed to `errors` and the part of
+/// the expression that failed gets [`Type::Error`], so the rest of it is still checked.
+pub fn infer_type(expr: Expression, scope: &Scope, errors: &mut Vec<TypeError>) -> Type {
     match expr {
-        Expression::If(expr) => infer_if(expr),
-        Expression::Infix(infix) => infix::infer_infix(infix),
-        Expression::Unary(unary) => unary::infer_unary(unary),
-        Expression::Error(error) => unary::infer_error(error),
-        Expression::Tuple(tuple) => unary::infer_tuple(tuple),
-        Expression::List(list) => unary::infer_list(list),
-        Expression::Literal(lit) => Ok(literal::infer_literal(lit)),
+        Expression::If(expr) => infer_if(expr, scope, errors),
+        Expression::Infix(infix) => infix::infer_infix(infix, scope, errors),
+        Expression::Unary(unary) => unary::infer_unary(unary, scope, errors),
+        Expression::Error(error) => unary::infer_error(error, scope, errors),
+        Expression::Tuple(tuple) => unary::infer_tuple(tuple, scope, errors),
+        Expression::Group(group) => infer_type(group.inner(), scope, errors),
+        Expression::List(list) => unary::infer_list(list, scope, errors),
+        Expression::Literal(lit) => recover(literal::infer_literal(lit, scope), errors),
     }
 }
 
-fn infer_if(expr: IfExpression) -> Result<Type> {
+/// Records the error of a failed check, continuing with [`Type::Error`].
+fn recover(result: Result<Type>, errors: &mut Vec<TypeError>) -> Type {
+    result.unwrap_or_else(|err| {
+        errors.push(err);
+        Type::Error
+    })
+}
+
+fn infer_if(expr: IfExpression, scope: &Scope, errors: &mut Vec<TypeError>) -> Type {
     let condition = expr.condition();
     let condition_range = condition.text_range();
-    infer_type(condition)?.requires_type(Type::Boolean, condition_range)?;
+    let condition = infer_type(condition, scope, errors);
+    recover(
+        condition.requires_type(Type::Boolean, condition_range),
+        errors,
+    );
 
-    let then = infer_type(expr.then())?;
-    let else_ = infer_type(expr.else_())?;
+    let then = infer_type(expr.then(), scope, errors);
+    let else_ = infer_type(expr.else_(), scope, errors);
 
-    common_type(then, else_, expr.text_range())
+    recover(common_type(then, else_, expr.text_range()), errors)
 }
 
 fn common_type(lhs: Type, rhs: Type, range: TextRange) -> Result<Type> {
@@ -104,6 +219,9 @@ fn common_type(lhs: Type, rhs: Type, range: TextRange) -> Result<Type> {
         Ok(lhs)
     } else {
         match (lhs, rhs) {
+            // Errors have been reported already
+            (Type::Error, _) | (_, Type::Error) => Ok(Type::Error),
+
             // Any can be coerced to any other type
             (Type::Any, rhs) => Ok(rhs),
             (lhs, Type::Any) => Ok(lhs),
@@ -112,6 +230,14 @@ fn common_type(lhs: Type, rhs: Type, range: TextRange) -> Result<Type> {
             (Type::SignedInteger, Type::Integer) => Ok(Type::SignedInteger),
             (Type::Integer, Type::SignedInteger) => Ok(Type::SignedInteger),
 
+            // Literals can be given any primitive type of the same kind
+            (Type::Primitive(primitive), rhs) if primitive.accepts(&rhs) => {
+                Ok(Type::Primitive(primitive))
+            }
+            (lhs, Type::Primitive(primitive)) if primitive.accepts(&lhs) => {
+                Ok(Type::Primitive(primitive))
+            }
+
             // Option type should be handled transparently
             (Type::Option(lhs), Type::Option(rhs)) => {
                 Ok(Type::Option(Box::new(common_type(*lhs, *rhs, range)?)))
@@ -123,6 +249,16 @@ fn common_type(lhs: Type, rhs: Type, range: TextRange) -> Result<Type> {
                 Ok(Type::Option(Box::new(common_type(lhs, *inner, range)?)))
             }
 
+            (Type::List(lhs), Type::List(rhs)) => {
+                Ok(Type::List(Box::new(common_type(*lhs, *rhs, range)?)))
+            }
+            (Type::Tuple(lhs), Type::Tuple(rhs)) if lhs.len() == rhs.len() => Ok(Type::Tuple(
+                lhs.into_iter()
+                    .zip(rhs)
+                    .map(|(lhs, rhs)| common_type(lhs, rhs, range))
+                    .collect::<Result<_>>()?,
+            )),
+
             // Result type should be handled transparently
             (Type::Result(lhs_inner, lhs_error), Type::Result(rhs_inner, rhs_error)) => {
                 Ok(Type::Result(
@@ -143,3 +279,97 @@ fn common_type(lhs: Type, rhs: Type, range: TextRange) -> Result<Type> {
         }
     }
 }
+
+/// Parses `input` for the tests of every module, which must parse without errors, and calls `f`
+/// with the file and the scope of its top level. Names are resolved by a [`stub::Stub`] built
+/// from the syntax tree of the file.
+#[cfg(test)]
+fn with_root<T>(input: &str, f: impl FnOnce(&ast::items::File, &Scope) -> T) -> T {
+    let parse = parsing::parse_file(input);
+    assert!(parse.errors().is_empty(), "{:?}", parse.errors());
+
+    let stub = stub::Stub::new(parse.file());
+    let scopes = Scopes::new(&stub);
+    f(parse.file(), &scopes.scope(stub::ROOT))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{infer_type, with_root, Result, Type, TypeError};
+    use ast::items::Item;
+
+    fn infer_all(input: &str) -> (Type, Vec<TypeError>) {
+        with_root(&format!("const A = {}", input), |file, scope| {
+            let Some(Item::Const(constant)) = file.items().next() else {
+                panic!("Expected a constant");
+            };
+            let mut errors = Vec::new();
+            let ty = infer_type(constant.expression(), scope, &mut errors);
+            (ty, errors)
+        })
+    }
+
+    fn infer(input: &str) -> Result<Type> {
+        let (ty, errors) = infer_all(input);
+        match errors.into_iter().next() {
+            Some(err) => Err(err),
+            None => Ok(ty),
+        }
+    }
+
+    #[test]
+    fn test_operators() {
+        assert!(infer("2 * 3").ok() == Some(Type::Integer));
+        assert!(infer("2 * -3").ok() == Some(Type::SignedInteger));
+        assert!(infer("-(1 + 2)").ok() == Some(Type::SignedInteger));
+        assert!(infer("!(true && false)").ok() == Some(Type::Boolean));
+        assert!(infer("(1.5)").ok() == Some(Type::Float));
+        assert!(infer("(1,)").ok() == Some(Type::Tuple(vec![Type::Integer])));
+        assert!(matches!(
+            infer("\"a\" * 2"),
+            Err(TypeError::UnexpectedTypes { .. })
+        ));
+        assert!(matches!(
+            infer("-(\"a\")"),
+            Err(TypeError::UnexpectedTypes { .. })
+        ));
+        assert!(matches!(
+            infer("!1.5"),
+            Err(TypeError::UnexpectedTypes { .. })
+        ));
+    }
+
+    #[test]
+    fn test_comparisons() {
+        assert!(infer("1 < 2").ok() == Some(Type::Boolean));
+        assert!(infer("-1.5 >= 2.0").ok() == Some(Type::Boolean));
+        assert!(infer("!(1 <= 2) && 3 > 2").ok() == Some(Type::Boolean));
+        assert!(matches!(
+            infer("1 < 1.5"),
+            Err(TypeError::NoCommonType { .. })
+        ));
+        assert!(matches!(
+            infer("\"a\" > \"b\""),
+            Err(TypeError::UnexpectedTypes { .. })
+        ));
+    }
+
+    #[test]
+    fn test_errors() {
+        let (ty, errors) = infer_all("[1 + \"a\", true && 1, 2]");
+        assert!(ty == Type::List(Box::new(Type::Error)));
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(errors[0], TypeError::NoCommonType { .. }));
+        assert!(matches!(errors[1], TypeError::UnexpectedType { .. }));
+
+        // Nothing else is reported for expressions built on a failed one
+        let (ty, errors) = infer_all("-(B * 2) + 1");
+        assert!(ty == Type::Error);
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0], TypeError::UnknownName { .. }));
+
+        let (ty, errors) = infer_all("if 1 then B else 2");
+        assert!(ty == Type::Error);
+        assert_eq!(errors.len(), 2);
+    }
+}
@@ -1,13 +1,14 @@
-use crate::Type;
+use crate::scope::Scope;
+use crate::{Result, Type};
 use ast::expression::Literal;
 
-pub fn infer_literal(lit: Literal) -> Type {
-    match lit {
+pub fn infer_literal(lit: Literal, scope: &Scope) -> Result<Type> {
+    Ok(match lit {
         Literal::Boolean(_) => Type::Boolean,
         Literal::None(_) => Type::Option(Box::new(Type::Any)),
         Literal::String(_) => Type::String,
         Literal::Integer(_) => Type::Integer,
         Literal::Float(_) => Type::Float,
-        Literal::Path(_) => todo!(),
-    }
+        Literal::Path(path) => return scope.type_of(&path),
+    })
 }
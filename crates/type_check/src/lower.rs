@@ -0,0 +1,32 @@
+use crate::scope::Scope;
+use crate::{Primitive, Type};
+use ast::items::EnumDefinition;
+use ast::types::Type as AstType;
+
+/// Converts a type written in a `.yip` file into the [`Type`] expressions are checked against.
+/// Named types are resolved from `scope`, aliases become the type they stand for.
+///
+/// Types that can't be checked yet, like user defined ones or handles, become [`Type::Any`].
+pub fn lower_type(ty: AstType, scope: &Scope) -> Type {
+    let lower = |ty| lower_type(ty, scope);
+    match ty {
+        AstType::Borrow(_) | AstType::Own(_) => Type::Any,
+        AstType::Result(result) => Type::Result(
+            Box::new(lower(result.inner())),
+            Box::new(result.error().map(lower).unwrap_or(Type::Any)),
+        ),
+        AstType::Option(option) => Type::Option(Box::new(lower(option.inner()))),
+        AstType::Tuple(tuple) => Type::Tuple(tuple.types().map(lower).collect()),
+        AstType::List(list) => Type::List(Box::new(lower(list.inner()))),
+        AstType::Named(named) => scope.named_type(&named),
+    }
+}
+
+/// The type the discriminants of `definition` are stored as. Enums without an explicit
+/// representation are stored as `u32`.
+pub(crate) fn enum_representation(definition: &EnumDefinition, scope: &Scope) -> Type {
+    definition
+        .representation()
+        .map(|x| lower_type(x, scope))
+        .unwrap_or(Type::Primitive(Primitive::U32))
+}
@@ -0,0 +1,296 @@
+use crate::eval::evaluate_within;
+use crate::scope::Scope;
+use crate::{infer_type, recover, ConstValue, Result, Type, TypeError};
+use ast::expression::Expression;
+use text_size::TextRange;
+
+/// The built-in types a `NamedType` can refer to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Primitive {
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    F32,
+    F64,
+    Bool,
+    String,
+    Bytes,
+    Char,
+}
+
+impl Primitive {
+    pub const ALL: &'static [Primitive] = &[
+        Primitive::U8,
+        Primitive::U16,
+        Primitive::U32,
+        Primitive::U64,
+        Primitive::U128,
+        Primitive::I8,
+        Primitive::I16,
+        Primitive::I32,
+        Primitive::I64,
+        Primitive::I128,
+        Primitive::F32,
+        Primitive::F64,
+        Primitive::Bool,
+        Primitive::String,
+        Primitive::Bytes,
+        Primitive::Char,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Primitive::ALL.iter().copied().find(|x| x.name() == name)
+    }
+
+    /// The name of the type as written in a `.yip` file.
+    pub fn name(self) -> &'static str {
+        match self {
+            Primitive::U8 => "u8",
+            Primitive::U16 => "u16",
+            Primitive::U32 => "u32",
+            Primitive::U64 => "u64",
+            Primitive::U128 => "u128",
+            Primitive::I8 => "i8",
+            Primitive::I16 => "i16",
+            Primitive::I32 => "i32",
+            Primitive::I64 => "i64",
+            Primitive::I128 => "i128",
+            Primitive::F32 => "f32",
+            Primitive::F64 => "f64",
+            Primitive::Bool => "bool",
+            Primitive::String => "string",
+            Primitive::Bytes => "bytes",
+            Primitive::Char => "char",
+        }
+    }
+
+    /// The checker type for this primitive. `bool` and `string` have no width, so they are the
+    /// plain [`Type::Boolean`] and [`Type::String`].
+    pub fn ty(self) -> Type {
+        match self {
+            Primitive::Bool => Type::Boolean,
+            Primitive::String => Type::String,
+            primitive => Type::Primitive(primitive),
+        }
+    }
+
+    /// The unsized type of the literals this primitive accepts, if any.
+    pub(crate) fn literal_type(self) -> Option<Type> {
+        match self {
+            Primitive::U8 | Primitive::U16 | Primitive::U32 | Primitive::U64 | Primitive::U128 => {
+                Some(Type::Integer)
+            }
+            Primitive::I8 | Primitive::I16 | Primitive::I32 | Primitive::I64 | Primitive::I128 => {
+                Some(Type::SignedInteger)
+            }
+            Primitive::F32 | Primitive::F64 => Some(Type::Float),
+            Primitive::Bool => Some(Type::Boolean),
+            Primitive::String => Some(Type::String),
+            Primitive::Bytes | Primitive::Char => None,
+        }
+    }
+
+    /// Whether the literals this primitive accepts are of one of the `types`.
+    pub(crate) fn is_any_of(self, types: &[Type]) -> bool {
+        self.literal_type().is_some_and(|x| types.contains(&x))
+    }
+
+    /// Whether a literal of type `ty` can be given this primitive type. Unsigned literals fit
+    /// signed integers too.
+    pub(crate) fn accepts(self, ty: &Type) -> bool {
+        match self.literal_type() {
+            Some(Type::SignedInteger) => matches!(ty, Type::Integer | Type::SignedInteger),
+            Some(literal) => literal == *ty,
+            None => false,
+        }
+    }
+
+    /// The smallest and largest value of an integer primitive.
+    pub fn bounds(self) -> Option<(i128, u128)> {
+        Some(match self {
+            Primitive::U8 => (0, u8::MAX as u128),
+            Primitive::U16 => (0, u16::MAX as u128),
+            Primitive::U32 => (0, u32::MAX as u128),
+            Primitive::U64 => (0, u64::MAX as u128),
+            Primitive::U128 => (0, u128::MAX),
+            Primitive::I8 => (i8::MIN as i128, i8::MAX as u128),
+            Primitive::I16 => (i16::MIN as i128, i16::MAX as u128),
+            Primitive::I32 => (i32::MIN as i128, i32::MAX as u128),
+            Primitive::I64 => (i64::MIN as i128, i64::MAX as u128),
+            Primitive::I128 => (i128::MIN, i128::MAX as u128),
+            _ => return None,
+        })
+    }
+}
+
+/// Checks that `expr` has the type `expected`. Integers in the value must also fit the width of
+/// the primitive they are given, so `300` is rejected as a `u8`.
+///
+/// Errors are pushed to `errors`, in which case [`Type::Error`] is returned.
+pub fn check_expression(
+    expr: Expression,
+    expected: &Type,
+    scope: &Scope,
+    errors: &mut Vec<TypeError>,
+) -> Type {
+    let range = expr.text_range();
+    let count = errors.len();
+
+    let ty = infer_type(expr.clone(), scope, errors);
+    let ty = recover(ty.requires_type(expected.clone(), range), errors);
+
+    // The value is only meaningful if it type checked
+    if errors.len() == count && has_bounds(expected) {
+        let bounds = evaluate_within(expr, scope).and_then(|value| match value {
+            Some(value) => check_bounds(&value, expected, range),
+            None => Ok(()),
+        });
+        if let Err(err) = bounds {
+            errors.push(err);
+        }
+    }
+
+    if errors.len() == count {
+        ty
+    } else {
+        Type::Error
+    }
+}
+
+fn has_bounds(ty: &Type) -> bool {
+    match ty {
+        Type::Primitive(primitive) => primitive.bounds().is_some(),
+        Type::Option(x) | Type::List(x) => has_bounds(x),
+        Type::Tuple(x) => x.iter().any(has_bounds),
+        Type::Result(x, error) => has_bounds(x) || has_bounds(error),
+        _ => false,
+    }
+}
+
+pub(crate) fn check_bounds(value: &ConstValue, ty: &Type, range: TextRange) -> Result<()> {
+    match (value, ty) {
+        (ConstValue::Integer(x), Type::Primitive(primitive)) => {
+            let Some((_, max)) = primitive.bounds() else {
+                return Ok(());
+            };
+
+            if *x > max {
+                return Err(TypeError::IntegerOutOfRange {
+                    ty: *primitive,
+                    range,
+                });
+            }
+            Ok(())
+        }
+        (ConstValue::SignedInteger(x), Type::Primitive(primitive)) => {
+            let Some((min, _)) = primitive.bounds() else {
+                return Ok(());
+            };
+
+            if *x < min {
+                return Err(TypeError::IntegerOutOfRange {
+                    ty: *primitive,
+                    range,
+                });
+            }
+            Ok(())
+        }
+        (ConstValue::None, Type::Option(_)) => Ok(()),
+        (value, Type::Option(inner)) => check_bounds(value, inner, range),
+        (ConstValue::Error(error), Type::Result(_, ty)) => check_bounds(error, ty, range),
+        (value, Type::Result(inner, _)) => check_bounds(value, inner, range),
+        (ConstValue::List(values), Type::List(ty)) => {
+            values.iter().try_for_each(|x| check_bounds(x, ty, range))
+        }
+        (ConstValue::Tuple(values), Type::Tuple(types)) => values
+            .iter()
+            .zip(types)
+            .try_for_each(|(x, ty)| check_bounds(x, ty, range)),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{check_expression, Primitive};
+    use crate::{lower_type, with_root, Result, Type, TypeError};
+    use ast::items::Item;
+
+    fn check(input: &str) -> Result<Type> {
+        let (ty, errors) = with_root(input, |file, scope| {
+            let Some(Item::Const(constant)) = file.items().last() else {
+                panic!("Expected a constant");
+            };
+            let expected = lower_type(constant.ty().unwrap(), scope);
+            let mut errors = Vec::new();
+            let ty = check_expression(constant.expression(), &expected, scope, &mut errors);
+            (ty, errors)
+        });
+
+        match errors.into_iter().next() {
+            Some(err) => Err(err),
+            None => Ok(ty),
+        }
+    }
+
+    #[test]
+    fn test_names() {
+        for primitive in Primitive::ALL {
+            assert_eq!(Primitive::from_name(primitive.name()), Some(*primitive));
+        }
+        assert_eq!(Primitive::from_name("u256"), None);
+    }
+
+    #[test]
+    fn test_valid() {
+        assert!(check("const X: u8 = 255").ok() == Some(Type::Primitive(Primitive::U8)));
+        assert!(check("const X: i8 = -128").ok() == Some(Type::Primitive(Primitive::I8)));
+        assert!(check("const X: i16 = 300").ok() == Some(Type::Primitive(Primitive::I16)));
+        assert!(check("const X: f32 = 1.5").ok() == Some(Type::Primitive(Primitive::F32)));
+        assert!(check("const X: bool = true").ok() == Some(Type::Boolean));
+        assert!(check("const X: ?u8 = none").is_ok());
+        assert!(check("const X: [u16] = [1, 2, 3]").is_ok());
+        assert!(check("const Y: u8 = 200\nconst X: u8 = Y + 55").is_ok());
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        assert!(matches!(
+            check("const X: u8 = 300"),
+            Err(TypeError::IntegerOutOfRange {
+                ty: Primitive::U8,
+                ..
+            })
+        ));
+        assert!(matches!(
+            check("const X: i8 = -129"),
+            Err(TypeError::IntegerOutOfRange { .. })
+        ));
+        assert!(matches!(
+            check("const X: u32 = -1"),
+            Err(TypeError::UnexpectedType { .. })
+        ));
+        assert!(matches!(
+            check("const X: (u8, u16) = (1, 70000)"),
+            Err(TypeError::IntegerOutOfRange {
+                ty: Primitive::U16,
+                ..
+            })
+        ));
+        assert!(matches!(
+            check("const Y: u8 = 200\nconst X: u8 = Y + 56"),
+            Err(TypeError::IntegerOutOfRange { .. })
+        ));
+        assert!(matches!(
+            check("const X: u8 = 1.5"),
+            Err(TypeError::UnexpectedType { .. })
+        ));
+    }
+}
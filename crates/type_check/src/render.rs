@@ -0,0 +1,273 @@
+use crate::{Type, TypeError};
+use report::Report;
+use std::fmt::{self, Display, Formatter};
+use text_size::TextRange;
+
+/// Types are shown as they would be written in a `.yip` file. Literals that haven't been given a
+/// sized type yet are shown in braces, like `{integer}`.
+impl Display for Type {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Any => f.write_str("_"),
+            Type::Error => f.write_str("{unknown}"),
+            Type::Boolean => f.write_str("bool"),
+            Type::String => f.write_str("string"),
+            Type::Integer => f.write_str("{integer}"),
+            Type::SignedInteger => f.write_str("{signed integer}"),
+            Type::Float => f.write_str("{float}"),
+            Type::Primitive(primitive) => f.write_str(primitive.name()),
+            Type::Option(inner) => write!(f, "?{}", inner),
+            Type::List(inner) => write!(f, "[{}]", inner),
+            Type::Tuple(types) => {
+                f.write_str("(")?;
+                for (idx, ty) in types.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", ty)?;
+                }
+                f.write_str(")")
+            }
+            Type::Result(inner, error) if **error == Type::Any => write!(f, "result {}", inner),
+            Type::Result(inner, error) => write!(f, "result {} error {}", inner, error),
+        }
+    }
+}
+
+impl TypeError {
+    /// The code as shown to users. These never change once assigned.
+    pub fn code(&self) -> &'static str {
+        match self {
+            TypeError::NoCommonType { .. } => "T0001",
+            TypeError::UnexpectedType { .. } => "T0002",
+            TypeError::UnexpectedTypes { .. } => "T0003",
+            TypeError::UnknownAnnotation { .. } => "T0004",
+            TypeError::UnknownAnnotationTarget { .. } => "T0005",
+            TypeError::AnnotationNotAllowed { .. } => "T0006",
+            TypeError::TooManyAnnotationArguments { .. } => "T0007",
+            TypeError::MissingAnnotationArgument { .. } => "T0008",
+            TypeError::UnknownAnnotationArgument { .. } => "T0009",
+            TypeError::DuplicateAnnotationArgument { .. } => "T0010",
+            TypeError::Overflow { .. } => "T0011",
+            TypeError::DivisionByZero { .. } => "T0012",
+            TypeError::ShiftOutOfRange { .. } => "T0013",
+            TypeError::InvalidEscape { .. } => "T0014",
+            TypeError::UnknownName { .. } => "T0015",
+            TypeError::NotAValue { .. } => "T0016",
+            TypeError::CyclicConstant { .. } => "T0017",
+            TypeError::IntegerOutOfRange { .. } => "T0018",
+            TypeError::InvalidEnumRepresentation { .. } => "T0019",
+        }
+    }
+
+    /// The range in the checked file that caused the error.
+    pub fn range(&self) -> TextRange {
+        match *self {
+            TypeError::NoCommonType { range, .. }
+            | TypeError::UnexpectedType { range, .. }
+            | TypeError::UnexpectedTypes { range, .. }
+            | TypeError::UnknownAnnotation { range, .. }
+            | TypeError::UnknownAnnotationTarget { range, .. }
+            | TypeError::AnnotationNotAllowed { range, .. }
+            | TypeError::TooManyAnnotationArguments { range, .. }
+            | TypeError::MissingAnnotationArgument { range, .. }
+            | TypeError::UnknownAnnotationArgument { range, .. }
+            | TypeError::DuplicateAnnotationArgument { range, .. }
+            | TypeError::Overflow { range }
+            | TypeError::DivisionByZero { range }
+            | TypeError::ShiftOutOfRange { range }
+            | TypeError::InvalidEscape { range }
+            | TypeError::UnknownName { range, .. }
+            | TypeError::NotAValue { range, .. }
+            | TypeError::CyclicConstant { range, .. }
+            | TypeError::IntegerOutOfRange { range, .. }
+            | TypeError::InvalidEnumRepresentation { range, .. } => range,
+        }
+    }
+
+    /// Describes the error for users, labelling the range that caused it.
+    pub fn report(&self) -> Report {
+        let range = self.range();
+        let report = Report::error(self.code(), self.to_string());
+
+        match self {
+            TypeError::NoCommonType { lhs, rhs, .. } => {
+                report.with_label(range, format!("mixes `{}` and `{}`", lhs, rhs))
+            }
+            TypeError::UnexpectedType { expected, .. } => {
+                report.with_label(range, format!("expected `{}`", expected))
+            }
+            TypeError::UnexpectedTypes { actual, .. } => {
+                report.with_label(range, format!("this is `{}`", actual))
+            }
+            TypeError::UnknownAnnotation { .. } => report
+                .with_label(range, "not declared")
+                .with_note("annotations must be declared with `annotation` before use"),
+            TypeError::UnknownAnnotationTarget { .. } => {
+                report.with_label(range, "unknown target").with_note(
+                    "targets are item keywords like `struct` or `fn`, or one of `param`, \
+                    `field` and `variant`",
+                )
+            }
+            TypeError::AnnotationNotAllowed { .. } => report.with_label(range, "not allowed here"),
+            TypeError::TooManyAnnotationArguments {
+                expected, actual, ..
+            } => {
+                let label = if actual - expected == 1 {
+                    "unexpected argument"
+                } else {
+                    "unexpected arguments"
+                };
+                report.with_label(range, label)
+            }
+            TypeError::MissingAnnotationArgument { .. } => report
+                .with_label(range, "missing argument")
+                .with_note("only parameters with an option type like `?u32` can be left out"),
+            TypeError::UnknownAnnotationArgument { .. } => {
+                report.with_label(range, "unknown parameter")
+            }
+            TypeError::DuplicateAnnotationArgument { .. } => {
+                report.with_label(range, "already given")
+            }
+            TypeError::Overflow { .. } => report.with_label(range, "overflows"),
+            TypeError::DivisionByZero { .. } => report.with_label(range, "this is zero"),
+            TypeError::ShiftOutOfRange { .. } => report
+                .with_label(range, "shift amount")
+                .with_note("integers can be shifted by 0 to 127 bits"),
+            TypeError::InvalidEscape { .. } => report.with_label(range, "invalid escape"),
+            TypeError::UnknownName { .. } => report.with_label(range, "not found in this scope"),
+            TypeError::NotAValue { .. } => report
+                .with_label(range, "not a value")
+                .with_note("only constants and enum members can be used in expressions"),
+            TypeError::CyclicConstant { .. } => report.with_label(range, "used here"),
+            TypeError::IntegerOutOfRange { ty, .. } => {
+                let report = report.with_label(range, format!("doesn't fit `{}`", ty.name()));
+                match ty.bounds() {
+                    Some((min, max)) => {
+                        report.with_note(format!("`{}` ranges from {} to {}", ty.name(), min, max))
+                    }
+                    None => report,
+                }
+            }
+            TypeError::InvalidEnumRepresentation { .. } => report
+                .with_label(range, "not an integer type")
+                .with_note("enums are stored as one of `u8` to `u128` or `i8` to `i128`"),
+        }
+    }
+}
+
+impl Display for TypeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TypeError::NoCommonType { lhs, rhs, .. } => {
+                write!(f, "no common type for `{}` and `{}`", lhs, rhs)
+            }
+            TypeError::UnexpectedType {
+                expected, actual, ..
+            } => write!(f, "expected `{}`, found `{}`", expected, actual),
+            TypeError::UnexpectedTypes {
+                expected, actual, ..
+            } => {
+                f.write_str("expected ")?;
+                for (idx, ty) in expected.iter().enumerate() {
+                    match idx {
+                        0 => {}
+                        _ if idx == expected.len() - 1 => f.write_str(" or ")?,
+                        _ => f.write_str(", ")?,
+                    }
+                    write!(f, "`{}`", ty)?;
+                }
+                write!(f, ", found `{}`", actual)
+            }
+            TypeError::UnknownAnnotation { name, .. } => {
+                write!(f, "cannot find annotation `@{}`", name)
+            }
+            TypeError::UnknownAnnotationTarget { name, .. } => {
+                write!(f, "unknown annotation target `{}`", name)
+            }
+            TypeError::AnnotationNotAllowed { name, target, .. } => write!(
+                f,
+                "annotation `@{}` can't be placed on `{}`",
+                name,
+                target.name()
+            ),
+            TypeError::TooManyAnnotationArguments {
+                expected, actual, ..
+            } => write!(
+                f,
+                "annotation takes {} argument{}, but {} {} given",
+                expected,
+                if *expected == 1 { "" } else { "s" },
+                actual,
+                if *actual == 1 { "was" } else { "were" }
+            ),
+            TypeError::MissingAnnotationArgument { name, .. } => {
+                write!(f, "missing annotation argument `{}`", name)
+            }
+            TypeError::UnknownAnnotationArgument { name, .. } => {
+                write!(f, "annotation has no parameter `{}`", name)
+            }
+            TypeError::DuplicateAnnotationArgument { name, .. } => {
+                write!(f, "annotation argument `{}` is given more than once", name)
+            }
+            TypeError::Overflow { .. } => f.write_str("value overflows"),
+            TypeError::DivisionByZero { .. } => f.write_str("division by zero"),
+            TypeError::ShiftOutOfRange { .. } => f.write_str("shift amount out of range"),
+            TypeError::InvalidEscape { .. } => f.write_str("invalid escape sequence"),
+            TypeError::UnknownName { name, .. } => write!(f, "cannot find `{}`", name),
+            TypeError::NotAValue { name, .. } => write!(f, "`{}` is not a value", name),
+            TypeError::CyclicConstant { name, .. } => {
+                write!(f, "`{}` depends on its own value", name)
+            }
+            TypeError::IntegerOutOfRange { ty, .. } => {
+                write!(f, "integer out of range for `{}`", ty.name())
+            }
+            TypeError::InvalidEnumRepresentation { ty, .. } => {
+                write!(f, "enum can't be represented as `{}`", ty)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{check_file, with_root, Primitive, Type};
+    use report::{render, Format, Source};
+
+    #[test]
+    fn test_display_type() {
+        let ty = Type::Option(Box::new(Type::List(Box::new(Type::Primitive(
+            Primitive::U8,
+        )))));
+        assert_eq!(ty.to_string(), "?[u8]");
+
+        let ty = Type::Result(
+            Box::new(Type::Tuple(vec![Type::Boolean, Type::String])),
+            Box::new(Type::Primitive(Primitive::I32)),
+        );
+        assert_eq!(ty.to_string(), "result (bool, string) error i32");
+
+        let ty = Type::Result(Box::new(Type::Integer), Box::new(Type::Any));
+        assert_eq!(ty.to_string(), "result {integer}");
+    }
+
+    #[test]
+    fn test_render() {
+        let input = "const A: u8 = 1\nconst N: bool = \"yes\"";
+        let diagnostics = with_root(input, check_file);
+        let source = Source::new("test.yip", input);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            render(&diagnostics[0].error.report(), &source, Format::Plain),
+            concat!(
+                "error[T0002]: expected `bool`, found `string`\n",
+                " --> test.yip:2:17\n",
+                "  |\n",
+                "2 | const N: bool = \"yes\"\n",
+                "  |                 ^^^^^ expected `bool`\n",
+                "\n",
+            )
+        );
+    }
+}
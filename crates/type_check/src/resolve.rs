@@ -0,0 +1,47 @@
+use crate::Primitive;
+use ast::items::{AnnotationDefinition, ConstDefinition, EnumDefinition};
+use ast::types::{NamedType, Type};
+
+/// A module of one of the files a [`Resolver`] resolves names in, as numbered by it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ScopeId {
+    pub file: u32,
+    pub module: u32,
+}
+
+/// What a path refers to, with the module each definition was written in so the paths inside it
+/// can be resolved too.
+#[derive(Debug, Clone)]
+pub enum Definition {
+    Const(ConstDefinition, ScopeId),
+    Enum(EnumDefinition, ScopeId),
+    /// A type alias, with the type it stands for
+    Alias(Type, ScopeId),
+    Primitive(Primitive),
+    Module(ScopeId),
+    /// Structs, unions, interfaces and handles, which have no value
+    Item,
+}
+
+/// Resolves names across a file and the files it imports. Implemented by the semantic model, so
+/// checking constant expressions sees names the same way resolving types does.
+pub trait Resolver {
+    /// Resolves `path` as if it was written in `scope`, following `use` directives and imports.
+    fn resolve(&self, scope: ScopeId, path: &[&str]) -> Option<Definition>;
+
+    /// What the named type `ty`, written in `scope`, refers to. Types are resolved like paths, but
+    /// a type named like a primitive is only that primitive if nothing else is.
+    fn resolve_type(&self, scope: ScopeId, ty: &NamedType) -> Option<Definition>;
+
+    /// The module `name` declared directly in `scope`.
+    fn module(&self, scope: ScopeId, name: &str) -> Option<ScopeId>;
+
+    /// Resolves the name of an annotation written in `scope`. Annotations don't share the
+    /// namespace of items, so only the segments before the last one name modules or imports.
+    /// The module it is declared in is returned too, to resolve the types of its parameters.
+    fn resolve_annotation(
+        &self,
+        scope: ScopeId,
+        path: &[&str],
+    ) -> Option<(AnnotationDefinition, ScopeId)>;
+}
@@ -0,0 +1,288 @@
+use crate::eval::{path_name, Evaluator};
+use crate::lower::enum_representation;
+use crate::resolve::{Definition, Resolver, ScopeId};
+use crate::{infer_type, lower_type, ConstValue, Result, Type, TypeError};
+use ast::expression::PathLiteral;
+use ast::items::{AnnotationDefinition, ConstDefinition, EnumDefinition};
+use ast::types::NamedType;
+use ast::SyntaxNode;
+use rowan::ast::AstNode;
+use std::cell::RefCell;
+use text_size::TextRange;
+
+/// Resolves paths in expressions through a [`Resolver`], and keeps track of the definitions being
+/// computed to detect cycles between them.
+pub struct Scopes<'a> {
+    resolver: &'a dyn Resolver,
+    /// Definitions whose type or value is being computed, by file and range
+    in_progress: RefCell<Vec<(u32, TextRange)>>,
+}
+
+/// What a path resolved to, along with the scope of the module it was defined in.
+enum Resolved<'a> {
+    Const(Scope<'a>, ConstDefinition),
+    EnumMember(Scope<'a>, EnumDefinition, usize),
+    NotAValue,
+}
+
+impl<'a> Scopes<'a> {
+    pub fn new(resolver: &'a dyn Resolver) -> Self {
+        Scopes {
+            resolver,
+            in_progress: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// The scope inside the module `id` of the resolver.
+    pub fn scope(&self, id: ScopeId) -> Scope<'_> {
+        Scope { scopes: self, id }
+    }
+}
+
+/// Reports a path referring to a definition that is being computed already.
+fn cyclic<T>(path: &PathLiteral) -> impl FnOnce() -> Result<T> + '_ {
+    || {
+        Err(TypeError::CyclicConstant {
+            name: path_name(path),
+            range: path.syntax().text_range(),
+        })
+    }
+}
+
+/// A module to resolve paths from.
+#[derive(Clone, Copy)]
+pub struct Scope<'a> {
+    scopes: &'a Scopes<'a>,
+    id: ScopeId,
+}
+
+impl<'a> Scope<'a> {
+    /// Infers the type of the constant or enum member `path` refers to.
+    pub fn type_of(&self, path: &PathLiteral) -> Result<Type> {
+        match self.resolve(path)? {
+            Resolved::Const(scope, constant) => match constant.ty() {
+                Some(ty) => Ok(lower_type(ty, &scope)),
+                None => scope.guard(constant.syntax(), cyclic(path), || {
+                    let mut errors = Vec::new();
+                    let ty = infer_type(constant.expression(), &scope, &mut errors);
+
+                    // Errors in the constant are reported where it is defined, only a cycle
+                    // through `path` is reported here
+                    match errors
+                        .into_iter()
+                        .find(|x| matches!(x, TypeError::CyclicConstant { .. }))
+                    {
+                        Some(err) => Err(err),
+                        None => Ok(ty),
+                    }
+                }),
+            },
+            Resolved::EnumMember(scope, enumeration, _) => {
+                Ok(enum_representation(&enumeration, &scope))
+            }
+            Resolved::NotAValue => Err(TypeError::NotAValue {
+                name: path_name(path),
+                range: path.syntax().text_range(),
+            }),
+        }
+    }
+
+    /// Evaluates the constant or enum member `path` refers to.
+    pub fn value_of(&self, path: &PathLiteral) -> Result<ConstValue> {
+        match self.resolve(path)? {
+            Resolved::Const(scope, constant) => {
+                scope.guard(constant.syntax(), cyclic(path), || {
+                    Evaluator { scope }.evaluate(constant.expression())
+                })
+            }
+            Resolved::EnumMember(scope, enumeration, idx) => {
+                scope.member_value(&enumeration, idx, path)
+            }
+            Resolved::NotAValue => Err(TypeError::NotAValue {
+                name: path_name(path),
+                range: path.syntax().text_range(),
+            }),
+        }
+    }
+
+    /// The scope of the module `name` declared in this one.
+    pub(crate) fn module(&self, name: &str) -> Option<Scope<'a>> {
+        let id = self.scopes.resolver.module(self.id, name)?;
+        Some(self.at(id))
+    }
+
+    /// The declaration of the annotation `path` used in this module, with the scope of the module
+    /// it is declared in.
+    pub(crate) fn annotation(&self, path: &[&str]) -> Option<(AnnotationDefinition, Scope<'a>)> {
+        let (definition, id) = self.scopes.resolver.resolve_annotation(self.id, path)?;
+        Some((definition, self.at(id)))
+    }
+
+    /// Lowers the named type `ty`, written in this module.
+    pub(crate) fn named_type(&self, ty: &NamedType) -> Type {
+        match self.scopes.resolver.resolve_type(self.id, ty) {
+            Some(Definition::Primitive(primitive)) => primitive.ty(),
+            // Aliases of themselves are reported by the semantic model
+            Some(Definition::Alias(ty, module)) => {
+                let (scope, node) = (self.at(module), ty.syntax().clone());
+                scope.guard(&node, || Type::Any, || lower_type(ty, &scope))
+            }
+            _ => Type::Any,
+        }
+    }
+
+    fn at(&self, id: ScopeId) -> Scope<'a> {
+        Scope {
+            scopes: self.scopes,
+            id,
+        }
+    }
+
+    /// Runs `f` unless `node`, defined in this module, is already being computed further up the
+    /// stack, in which case `cycle` is run instead.
+    fn guard<T>(&self, node: &SyntaxNode, cycle: impl FnOnce() -> T, f: impl FnOnce() -> T) -> T {
+        let node = (self.id.file, node.text_range());
+        let in_progress = &self.scopes.in_progress;
+        if in_progress.borrow().contains(&node) {
+            return cycle();
+        }
+
+        in_progress.borrow_mut().push(node);
+        let result = f();
+        in_progress.borrow_mut().pop();
+        result
+    }
+
+    /// Members without an explicit value are one more than the previous member, starting at 0.
+    fn member_value(
+        &self,
+        enumeration: &EnumDefinition,
+        idx: usize,
+        path: &PathLiteral,
+    ) -> Result<ConstValue> {
+        let member = enumeration.members().nth(idx).unwrap();
+        let range = member.syntax().text_range();
+
+        self.guard(member.syntax(), cyclic(path), || {
+            match member.expression() {
+                Some(expression) => Evaluator { scope: *self }.evaluate(expression),
+                None if idx == 0 => Ok(ConstValue::Integer(0)),
+                None => match self.member_value(enumeration, idx - 1, path)? {
+                    ConstValue::Integer(x) => x
+                        .checked_add(1)
+                        .map(ConstValue::Integer)
+                        .ok_or(TypeError::Overflow { range }),
+                    ConstValue::SignedInteger(x) => Ok(ConstValue::signed(x + 1)),
+                    actual => Err(TypeError::UnexpectedType {
+                        expected: Type::Integer,
+                        actual: actual.ty(),
+                        range,
+                    }),
+                },
+            }
+        })
+    }
+
+    fn resolve(&self, path: &PathLiteral) -> Result<Resolved<'a>> {
+        let segments: Vec<_> = path.segments().collect();
+        let segments: Vec<_> = segments.iter().map(|x| x.text()).collect();
+        self.resolve_segments(&segments)
+            .ok_or_else(|| TypeError::UnknownName {
+                name: path_name(path),
+                range: path.syntax().text_range(),
+            })
+    }
+
+    /// Resolves a path to a definition, or to a member of the enum its segments before the last
+    /// one refer to.
+    fn resolve_segments(&self, segments: &[&str]) -> Option<Resolved<'a>> {
+        let resolver = self.scopes.resolver;
+        if let Some(definition) = resolver.resolve(self.id, segments) {
+            return Some(match definition {
+                Definition::Const(constant, module) => Resolved::Const(self.at(module), constant),
+                _ => Resolved::NotAValue,
+            });
+        }
+
+        let (name, prefix) = segments.split_last()?;
+        let Some(Definition::Enum(enumeration, module)) = resolver.resolve(self.id, prefix) else {
+            return None;
+        };
+        let idx = enumeration
+            .members()
+            .position(|x| x.name().text() == *name)?;
+        Some(Resolved::EnumMember(self.at(module), enumeration, idx))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{with_root, ConstValue, Primitive, Result, Type, TypeError};
+    use ast::expression::{Expression, Literal};
+    use ast::items::Item;
+
+    fn resolve(input: &str, path: &str) -> (Result<Type>, Result<ConstValue>) {
+        with_root(&format!("{}\nconst Z = {}", input, path), |file, scope| {
+            let Some(Item::Const(constant)) = file.items().last() else {
+                panic!("Expected a constant");
+            };
+            let Expression::Literal(Literal::Path(path)) = constant.expression() else {
+                panic!("Expected a path");
+            };
+            (scope.type_of(&path), scope.value_of(&path))
+        })
+    }
+
+    #[test]
+    fn test_constants() {
+        let (ty, value) = resolve("const A: bool = true", "A");
+        assert!(ty.ok() == Some(Type::Boolean));
+        assert_eq!(value.ok(), Some(ConstValue::Boolean(true)));
+
+        let (ty, value) = resolve("const A = B + 1\nconst B = 2", "A");
+        assert!(ty.ok() == Some(Type::Integer));
+        assert_eq!(value.ok(), Some(ConstValue::Integer(3)));
+
+        let (_, value) = resolve("mod a {\nconst A = B\nmod b {}\n}\nconst B = 1", "a::A");
+        assert_eq!(value.ok(), Some(ConstValue::Integer(1)));
+    }
+
+    #[test]
+    fn test_enum_members() {
+        let (ty, value) = resolve("enum E: u8 {\nA = 2\nB\n}", "E::B");
+        assert!(ty.ok() == Some(Type::Primitive(Primitive::U8)));
+        assert_eq!(value.ok(), Some(ConstValue::Integer(3)));
+
+        let (_, value) = resolve("mod m {\nenum E {\nA\nB = A\n}\nconst A = 5\n}", "m::E::B");
+        assert_eq!(value.ok(), Some(ConstValue::Integer(5)));
+    }
+
+    #[test]
+    fn test_uses() {
+        let (_, value) = resolve("mod a {\nconst A = 1\nconst B = 2\n}\nuse a::{A, B}", "B");
+        assert_eq!(value.ok(), Some(ConstValue::Integer(2)));
+
+        let (_, value) = resolve("mod a {\nenum E { X }\n}\nuse a::E", "E::X");
+        assert_eq!(value.ok(), Some(ConstValue::Integer(0)));
+    }
+
+    #[test]
+    fn test_errors() {
+        let (ty, value) = resolve("struct S {}", "S");
+        assert!(matches!(ty, Err(TypeError::NotAValue { .. })));
+        assert!(matches!(value, Err(TypeError::NotAValue { .. })));
+
+        let (ty, _) = resolve("interface I {}", "I");
+        assert!(matches!(ty, Err(TypeError::NotAValue { .. })));
+
+        let (ty, _) = resolve("enum E { A }", "E::B");
+        assert!(matches!(ty, Err(TypeError::UnknownName { .. })));
+
+        let (ty, value) = resolve("const A = B\nconst B = A", "A");
+        assert!(matches!(ty, Err(TypeError::CyclicConstant { .. })));
+        assert!(matches!(value, Err(TypeError::CyclicConstant { .. })));
+
+        let (_, value) = resolve("enum E {\nA = E::B\nB\n}", "E::A");
+        assert!(matches!(value, Err(TypeError::CyclicConstant { .. })));
+    }
+}
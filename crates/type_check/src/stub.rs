@@ -0,0 +1,158 @@
+use crate::{Definition, Primitive, Resolver, ScopeId};
+use ast::items::{AnnotationDefinition, File, Item};
+use ast::types::NamedType;
+use rowan::ast::AstChildren;
+use std::collections::HashMap;
+
+/// The top level of the file a [`Stub`] was built from.
+pub(crate) const ROOT: ScopeId = ScopeId { file: 0, module: 0 };
+
+/// Resolves the names of a single file from its syntax tree, so the checker can be tested without
+/// the semantic model. Modules are numbered in the order they are declared in.
+pub(crate) struct Stub {
+    modules: Vec<Module>,
+}
+
+#[derive(Default)]
+struct Module {
+    parent: Option<u32>,
+    definitions: HashMap<String, Definition>,
+    /// Names brought into scope by `use`, mapped to the path they refer to from the top level
+    uses: HashMap<String, Vec<String>>,
+    annotations: HashMap<String, AnnotationDefinition>,
+}
+
+impl Stub {
+    pub(crate) fn new(file: &File) -> Self {
+        let mut stub = Stub {
+            modules: Vec::new(),
+        };
+        stub.add_module(file.items(), None);
+        stub
+    }
+
+    fn add_module(&mut self, items: AstChildren<Item>, parent: Option<u32>) -> ScopeId {
+        let id = self.modules.len() as u32;
+        let scope = ScopeId {
+            file: ROOT.file,
+            module: id,
+        };
+        self.modules.push(Module {
+            parent,
+            ..Module::default()
+        });
+
+        for item in items {
+            let (name, definition) = match item {
+                Item::Const(x) => (x.name(), Definition::Const(x, scope)),
+                Item::Enum(x) => (x.name(), Definition::Enum(x, scope)),
+                Item::Module(x) => (
+                    x.name(),
+                    Definition::Module(self.add_module(x.items(), Some(id))),
+                ),
+                Item::Use(x) => {
+                    for path in x.paths() {
+                        let Some(name) = path.last() else {
+                            continue;
+                        };
+                        let name = name.text().to_string();
+                        let path = path.iter().map(|x| x.text().to_string()).collect();
+                        self.modules[id as usize].uses.insert(name, path);
+                    }
+                    continue;
+                }
+                Item::Struct(x) => (x.name(), Definition::Item),
+                Item::Union(x) => (x.name(), Definition::Item),
+                Item::Alias(x) => (x.name(), Definition::Alias(x.ty(), scope)),
+                Item::Handle(x) => (x.name(), Definition::Item),
+                Item::Interface(x) => (x.name(), Definition::Item),
+                Item::Annotation(x) => {
+                    let name = x.name().text().to_string();
+                    let annotations = &mut self.modules[id as usize].annotations;
+                    annotations.entry(name).or_insert(x);
+                    continue;
+                }
+                Item::Import(_) | Item::Plugin(_) => continue,
+            };
+
+            self.modules[id as usize]
+                .definitions
+                .insert(name.text().to_string(), definition);
+        }
+
+        scope
+    }
+
+    /// Looks `name` up in `module`, then in each enclosing module.
+    fn lookup(&self, module: u32, name: &str) -> Option<Definition> {
+        let module = &self.modules[module as usize];
+        if let Some(definition) = module.definitions.get(name) {
+            return Some(definition.clone());
+        }
+        if let Some(path) = module.uses.get(name) {
+            return path
+                .iter()
+                .try_fold(Definition::Module(ROOT), |x, name| self.child(x, name));
+        }
+
+        self.lookup(module.parent?, name)
+    }
+
+    /// The definition of `name` in the module `definition` refers to.
+    fn child(&self, definition: Definition, name: &str) -> Option<Definition> {
+        let Definition::Module(scope) = definition else {
+            return None;
+        };
+        self.modules[scope.module as usize]
+            .definitions
+            .get(name)
+            .cloned()
+    }
+}
+
+impl Resolver for Stub {
+    fn resolve(&self, scope: ScopeId, path: &[&str]) -> Option<Definition> {
+        let (first, rest) = path.split_first()?;
+        let definition = self.lookup(scope.module, first)?;
+        rest.iter()
+            .try_fold(definition, |x, name| self.child(x, name))
+    }
+
+    fn resolve_type(&self, scope: ScopeId, ty: &NamedType) -> Option<Definition> {
+        let segments: Vec<_> = ty.segments().collect();
+        let segments: Vec<_> = segments.iter().map(|x| x.text()).collect();
+        match (self.resolve(scope, &segments), segments.as_slice()) {
+            (None, [name]) => Primitive::from_name(name).map(Definition::Primitive),
+            (definition, _) => definition,
+        }
+    }
+
+    fn module(&self, scope: ScopeId, name: &str) -> Option<ScopeId> {
+        match self.child(Definition::Module(scope), name)? {
+            Definition::Module(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    fn resolve_annotation(
+        &self,
+        scope: ScopeId,
+        path: &[&str],
+    ) -> Option<(AnnotationDefinition, ScopeId)> {
+        let (name, modules) = path.split_last()?;
+        let module = match modules {
+            [] => std::iter::successors(Some(scope.module), |x| self.modules[*x as usize].parent)
+                .find(|x| self.modules[*x as usize].annotations.contains_key(*name))?,
+            _ => match self.resolve(scope, modules)? {
+                Definition::Module(x) => x.module,
+                _ => return None,
+            },
+        };
+        let annotation = self.modules[module as usize].annotations.get(*name)?;
+        let scope = ScopeId {
+            file: ROOT.file,
+            module,
+        };
+        Some((annotation.clone(), scope))
+    }
+}
@@ -1,62 +1,66 @@
-use crate::literal::infer_literal;
-use crate::{infer_type, Result, Type, common_type};
+use crate::scope::Scope;
+use crate::{common_type, infer_type, recover, Type, TypeError};
 use ast::expression::{
     ErrorValue, ListExpression, TupleExpression, UnaryOperator, UnaryOperatorType,
 };
 
 const UNARY_PLUS_MINUS_TYPES: &[Type] = &[Type::Integer, Type::SignedInteger, Type::Float];
-const UNARY_NOT_TYPES: &[Type] = &[
-    Type::Boolean,
-    Type::Integer,
-    Type::SignedInteger,
-    Type::Float,
-];
-
-pub fn infer_unary(unary: UnaryOperator) -> Result<Type> {
+const UNARY_NOT_TYPES: &[Type] = &[Type::Boolean, Type::Integer, Type::SignedInteger];
+
+pub fn infer_unary(unary: UnaryOperator, scope: &Scope, errors: &mut Vec<TypeError>) -> Type {
     let inner = unary.inner();
     let range = inner.text_range();
-    let inner = infer_literal(inner);
+    let inner = infer_type(inner, scope, errors);
 
-    match unary.op_type() {
+    let ty = match unary.op_type() {
         UnaryOperatorType::Plus => inner.requires_types(UNARY_PLUS_MINUS_TYPES, range),
         UnaryOperatorType::Not => inner.requires_types(UNARY_NOT_TYPES, range),
         UnaryOperatorType::Minus => {
-            let inner = inner.requires_types(UNARY_PLUS_MINUS_TYPES, range)?;
-            if inner == Type::Integer {
-                Ok(Type::SignedInteger)
-            } else {
-                Ok(inner)
-            }
+            inner
+                .requires_types(UNARY_PLUS_MINUS_TYPES, range)
+                .map(|inner| {
+                    if inner == Type::Integer {
+                        Type::SignedInteger
+                    } else {
+                        inner
+                    }
+                })
         }
-    }
+    };
+    recover(ty, errors)
 }
 
-pub fn infer_error(error: ErrorValue) -> Result<Type> {
-    let ty = infer_type(error.inner())?;
-    Ok(Type::Result(Box::new(Type::Any), Box::new(ty)))
+pub fn infer_error(error: ErrorValue, scope: &Scope, errors: &mut Vec<TypeError>) -> Type {
+    let ty = infer_type(error.inner(), scope, errors);
+    Type::Result(Box::new(Type::Any), Box::new(ty))
 }
 
-pub fn infer_tuple(tuple: TupleExpression) -> Result<Type> {
-    let mut types = Vec::new();
-    for expr in tuple.expressions() {
-        types.push(infer_type(expr)?);
-    }
-
-    Ok(Type::Tuple(types))
+pub fn infer_tuple(tuple: TupleExpression, scope: &Scope, errors: &mut Vec<TypeError>) -> Type {
+    Type::Tuple(
+        tuple
+            .expressions()
+            .map(|expr| infer_type(expr, scope, errors))
+            .collect(),
+    )
 }
 
-pub fn infer_list(list: ListExpression) -> Result<Type> {
+/// Every element is checked against the common type of the elements before it, so each element
+/// that doesn't fit is reported.
+pub fn infer_list(list: ListExpression, scope: &Scope, errors: &mut Vec<TypeError>) -> Type {
     let mut iter = list.expressions();
     let Some(common) = iter.next() else {
-        return Ok(Type::List(Box::new(Type::Any)));
+        return Type::List(Box::new(Type::Any));
     };
-    let mut common = infer_type(common)?;
+    let mut common = infer_type(common, scope, errors);
 
     for item in iter {
         let range = item.text_range();
-        let ty = infer_type(item)?;
-        common = common_type(common, ty, range)?;
+        let ty = infer_type(item, scope, errors);
+        match common_type(common.clone(), ty, range) {
+            Ok(ty) => common = ty,
+            Err(err) => errors.push(err),
+        }
     }
 
-    Ok(common)
+    Type::List(Box::new(common))
 }
@@ -1,3 +1,299 @@
-fn main() {
-    println!("This Tool is still work in progress");
+use hir::{FileId, ItemKind, NEW_SOURCE, OLD_SOURCE};
+use loader::{CacheResolver, FileResolver, Resolvers, Workspace};
+use report::{render, render_sources, Format, Report, Source};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use type_check::Scopes;
+
+const USAGE: &str = "Usage: yip check [--format plain|json] <FILE>
+       yip fingerprint <FILE> [INTERFACE]
+       yip compat [--format plain|json] <OLD> <NEW>
+
+Imports of files other than local ones are read from the cache directory in
+$YIP_CACHE, by default the `yip` directory of the user's cache directory.";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    match args.first().map(String::as_str) {
+        Some("check") => check(&args[1..]),
+        Some("fingerprint") => fingerprint(&args[1..]),
+        Some("compat") => compat(&args[1..]),
+        _ => {
+            eprintln!("{}", USAGE);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Parses, resolves and type checks a file and the files it imports, printing every error found.
+fn check(args: &[String]) -> ExitCode {
+    let mut format = Format::Plain;
+    let mut path = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => match args.next().map(String::as_str) {
+                Some("plain") => format = Format::Plain,
+                Some("json") => format = Format::Json,
+                _ => {
+                    eprintln!("{}", USAGE);
+                    return ExitCode::FAILURE;
+                }
+            },
+            _ if path.is_none() => path = Some(arg),
+            _ => {
+                eprintln!("{}", USAGE);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let Some(path) = path else {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    };
+
+    let Some(input) = read(path) else {
+        return ExitCode::FAILURE;
+    };
+    let (workspace, reports) = analyze(path, input);
+
+    let names = names(path, &workspace);
+    let sources = sources(&names, &workspace);
+    for (file, report) in &reports {
+        match format {
+            Format::Plain => print!("{}", render(report, &sources[*file], format)),
+            Format::Json => println!("{}", render(report, &sources[*file], format)),
+        }
+    }
+
+    if reports.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Prints the fingerprint of every interface of a file, or of the one at the given path.
+fn fingerprint(args: &[String]) -> ExitCode {
+    let (path, interface) = match args {
+        [path] => (path, None),
+        [path, interface] => (path, Some(interface)),
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let Some(input) = read(path) else {
+        return ExitCode::FAILURE;
+    };
+    let (workspace, reports) = analyze(path, input);
+
+    // A fingerprint of a broken file would match no valid one
+    let Some(hir) = workspace.root().hir.filter(|_| reports.is_empty()) else {
+        print_errors(path, &workspace, &reports);
+        return ExitCode::FAILURE;
+    };
+    let hir = workspace.hir(hir);
+
+    let interfaces: Vec<_> = hir
+        .items()
+        .iter()
+        .filter(|x| x.kind == ItemKind::Interface)
+        .filter(|x| interface.is_none_or(|path| hir.item_path(x.id) == *path))
+        .collect();
+
+    if let (Some(interface), []) = (interface, interfaces.as_slice()) {
+        eprintln!("error: no interface `{}` in `{}`", interface, path);
+        return ExitCode::FAILURE;
+    }
+
+    for item in interfaces {
+        println!(
+            "{}  {}",
+            hir.fingerprint(workspace.hirs(), item.id),
+            hir.item_path(item.id)
+        );
+    }
+    ExitCode::SUCCESS
+}
+
+/// Compares two versions of a file, printing every change and failing if any of them breaks
+/// compatibility.
+fn compat(args: &[String]) -> ExitCode {
+    let mut format = Format::Plain;
+    let mut paths = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => match args.next().map(String::as_str) {
+                Some("plain") => format = Format::Plain,
+                Some("json") => format = Format::Json,
+                _ => {
+                    eprintln!("{}", USAGE);
+                    return ExitCode::FAILURE;
+                }
+            },
+            _ => paths.push(arg),
+        }
+    }
+
+    let [old_path, new_path] = paths[..] else {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    };
+
+    let (Some(old_input), Some(new_input)) = (read(old_path), read(new_path)) else {
+        return ExitCode::FAILURE;
+    };
+    let (old, old_reports) = analyze(old_path, old_input);
+    let (new, new_reports) = analyze(new_path, new_input);
+    // The roots come first, then the files each of them imports
+    let mut sources = vec![
+        Source::new(old_path, &old.root().text),
+        Source::new(new_path, &new.root().text),
+    ];
+    let imported = old.files()[1..].iter().chain(&new.files()[1..]);
+    sources.extend(imported.map(|x| Source::new(&x.url, &x.text)));
+    let old_imports = old.files().len() - 1;
+    let source = |workspace: &Workspace, root: usize, offset: usize, file: FileId| {
+        let idx = workspace.files().iter().position(|x| x.hir == Some(file))?;
+        Some(if idx == 0 { root } else { offset + idx - 1 })
+    };
+
+    // Both versions have to be valid to be compared
+    print_errors(old_path, &old, &old_reports);
+    print_errors(new_path, &new, &new_reports);
+    let (Some(old_hir), Some(new_hir), true, true) = (
+        old.root().hir,
+        new.root().hir,
+        old_reports.is_empty(),
+        new_reports.is_empty(),
+    ) else {
+        return ExitCode::FAILURE;
+    };
+
+    let changes = hir::compare(old.hir(old_hir), old.hirs(), new.hir(new_hir), new.hirs());
+    for change in &changes {
+        let report = change.report_in(
+            |file| source(&old, OLD_SOURCE, 2, file),
+            |file| source(&new, NEW_SOURCE, 2 + old_imports, file),
+        );
+        match format {
+            Format::Plain => print!("{}", render_sources(&report, &sources, format)),
+            Format::Json => println!("{}", render_sources(&report, &sources, format)),
+        }
+    }
+
+    let breaking = changes.iter().filter(|x| x.breaking).count();
+    if format == Format::Plain {
+        println!(
+            "{} breaking and {} compatible changes",
+            breaking,
+            changes.len() - breaking
+        );
+    }
+
+    if breaking == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn read(path: &str) -> Option<String> {
+    match std::fs::read_to_string(path) {
+        Ok(input) => Some(input),
+        Err(err) => {
+            eprintln!("error: can't read `{}`: {}", path, err);
+            None
+        }
+    }
+}
+
+/// Loads a file and the files it imports, then type checks each of them. Returns every error
+/// found, with the index of the file it was found in.
+fn analyze(path: &str, input: String) -> (Workspace, Vec<(usize, Report)>) {
+    // The URL only has to be absolute for relative imports, the file was read already
+    let url = loader::file_url(Path::new(path)).unwrap_or_else(|_| format!("file://{}", path));
+    let workspace = Workspace::load(&url, input, &resolver());
+
+    let mut reports = Vec::new();
+    for (idx, file) in workspace.files().iter().enumerate() {
+        reports.extend(file.parse.errors().iter().map(|x| (idx, x.report())));
+
+        // Files are only lowered if they were parsed without errors, as the type checker expects
+        let Some(hir) = file.hir else {
+            continue;
+        };
+        let hir = workspace.hir(hir);
+        reports.extend(hir.errors().iter().map(|x| (idx, x.report())));
+
+        // Paths in expressions and annotations are resolved like types, into imported files too
+        let names = hir.names(workspace.hirs());
+        let scopes = Scopes::new(&names);
+        reports.extend(
+            type_check::check_file(file.parse.file(), &scopes.scope(hir.scope(hir.root())))
+                .iter()
+                .map(|x| (idx, x.error.report())),
+        );
+    }
+    (workspace, reports)
+}
+
+/// Reads local imports from disk and everything else from the cache, without fetching anything.
+fn resolver() -> Resolvers {
+    let mut resolvers = Resolvers(vec![Box::new(FileResolver)]);
+    if let Some(dir) = cache_dir() {
+        resolvers.0.push(Box::new(CacheResolver::new(dir)));
+    }
+    resolvers
+}
+
+fn cache_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("YIP_CACHE") {
+        return Some(dir.into());
+    }
+
+    let cache = match std::env::var_os("XDG_CACHE_HOME") {
+        Some(cache) => PathBuf::from(cache),
+        None => PathBuf::from(std::env::var_os("HOME")?).join(".cache"),
+    };
+    Some(cache.join("yip"))
+}
+
+/// The names to show for the files of `workspace`. The root keeps the path it was given as, local
+/// imports are shown by their path and others by their URL.
+fn names(path: &str, workspace: &Workspace) -> Vec<String> {
+    let mut names: Vec<_> = workspace
+        .files()
+        .iter()
+        .map(|file| {
+            loader::file_path(&file.url)
+                .map_or_else(|| file.url.clone(), |x| x.display().to_string())
+        })
+        .collect();
+    names[0] = path.to_string();
+    names
+}
+
+fn sources<'a>(names: &'a [String], workspace: &'a Workspace) -> Vec<Source<'a>> {
+    names
+        .iter()
+        .zip(workspace.files())
+        .map(|(name, file)| Source::new(name, &file.text))
+        .collect()
+}
+
+/// Prints the errors `analyze` found to stderr.
+fn print_errors(path: &str, workspace: &Workspace, reports: &[(usize, Report)]) {
+    let names = names(path, workspace);
+    let sources = sources(&names, workspace);
+    for (file, report) in reports {
+        eprint!("{}", render(report, &sources[*file], Format::Plain));
+    }
 }